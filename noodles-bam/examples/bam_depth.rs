@@ -0,0 +1,54 @@
+//! Prints the read depth at each position of a region of a BAM file.
+//!
+//! The input BAM must have an index in the same directory.
+//!
+//! The results are printed as tab-delimited records with the following columns: reference sequence
+//! name, position, and depth. This is similar to the output of `samtools depth -r <region> <src>`.
+
+use std::{env, fs::File, path::PathBuf};
+
+use noodles::Region;
+use noodles_bam::{self as bam, bai, pileup::Pileup};
+use noodles_sam as sam;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = env::args().skip(1);
+
+    let src = args.next().map(PathBuf::from).expect("missing src");
+    let raw_region = args.next().expect("missing region");
+
+    let mut reader = File::open(&src).map(bam::Reader::new)?;
+    let header: sam::Header = reader.read_header()?.parse()?;
+    let reference_sequences = header.reference_sequences();
+
+    let index = bai::read(src.with_extension("bam.bai"))?;
+    let region = Region::from_str_reference_sequences(&raw_region, reference_sequences)?;
+
+    let (start, end) = match region {
+        Region::Mapped { start, end, .. } => (start, end),
+        _ => return Err("invalid region".into()),
+    };
+
+    let query = reader.query(reference_sequences, &index, &region)?;
+    let pileup = Pileup::builder(query)
+        .set_min_base_quality(0)
+        .set_depth_only(true)
+        .build();
+
+    for result in pileup {
+        let column = result?;
+        let position = column.position();
+
+        if position < start || position > end {
+            continue;
+        }
+
+        let (name, _) = reference_sequences
+            .get_index(column.reference_sequence_id())
+            .expect("invalid reference sequence ID");
+
+        println!("{}\t{}\t{}", name, position, column.depth());
+    }
+
+    Ok(())
+}
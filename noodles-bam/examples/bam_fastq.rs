@@ -0,0 +1,40 @@
+//! Converts a BAM file to FASTQ files.
+//!
+//! Reads are split by segment into `<prefix>.1.fastq`, `<prefix>.2.fastq`, and `<prefix>.s.fastq`
+//! for singletons. This is similar to the outputs of `samtools fastq -1 <prefix>.1.fastq -2
+//! <prefix>.2.fastq -s <prefix>.s.fastq <src>`.
+
+use std::{env, fs::File, io::BufWriter};
+
+use noodles_bam::{self as bam, fastq::Converter};
+use noodles_fastq as fastq;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = env::args().skip(1);
+
+    let src = args.next().expect("missing src");
+    let prefix = args.next().expect("missing prefix");
+
+    let mut reader = File::open(src).map(bam::Reader::new)?;
+    reader.read_header()?;
+    reader.read_reference_sequences()?;
+
+    let create = |suffix: &str| {
+        File::create(format!("{}.{}.fastq", prefix, suffix))
+            .map(BufWriter::new)
+            .map(fastq::Writer::new)
+    };
+
+    let mut read_1_writer = create("1")?;
+    let mut read_2_writer = create("2")?;
+    let mut singleton_writer = create("s")?;
+
+    Converter::default().convert(
+        reader.records(),
+        &mut read_1_writer,
+        &mut read_2_writer,
+        &mut singleton_writer,
+    )?;
+
+    Ok(())
+}
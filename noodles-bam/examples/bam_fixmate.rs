@@ -0,0 +1,35 @@
+//! Fixes the mate information of records in a queryname-grouped BAM file.
+//!
+//! The results are written to the given destination. This is similar to the outputs of `samtools
+//! fixmate <src> <dst>`.
+
+use std::{env, fs::File};
+
+use noodles_bam::{self as bam, fixmate::FixMate};
+use noodles_sam as sam;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = env::args().skip(1);
+
+    let src = args.next().expect("missing src");
+    let dst = args.next().expect("missing dst");
+
+    let mut reader = File::open(src).map(bam::Reader::new)?;
+    let header: sam::Header = reader.read_header()?.parse()?;
+    reader.read_reference_sequences()?;
+
+    let mut writer = File::create(dst).map(bam::Writer::new)?;
+    writer.write_header(&header)?;
+    writer.write_reference_sequences(header.reference_sequences())?;
+
+    let records = FixMate::builder(reader.records())
+        .set_add_mate_cigar(true)
+        .build();
+
+    for result in records {
+        let record = result?;
+        writer.write_record(&record)?;
+    }
+
+    Ok(())
+}
@@ -8,7 +8,7 @@
 
 use std::{env, fs::File, path::PathBuf};
 
-use noodles_bam::{self as bam, bai};
+use noodles_bam::{self as bam, bai, stats::IndexStats};
 use noodles_sam as sam;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -18,28 +18,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let header: sam::Header = reader.read_header()?.parse()?;
 
     let index = bai::read(src.with_extension("bam.bai"))?;
+    let index_stats = IndexStats::from(&index);
 
-    for (reference_sequence, index_reference_sequence) in header
+    for (reference_sequence, counts) in header
         .reference_sequences()
         .values()
-        .zip(index.reference_sequences())
+        .zip(index_stats.reference_sequences())
     {
-        let (mapped_record_count, unmapped_record_count) = index_reference_sequence
-            .metadata()
-            .map(|m| (m.mapped_record_count(), m.unmapped_record_count()))
-            .unwrap_or_default();
-
         println!(
             "{}\t{}\t{}\t{}",
             reference_sequence.name(),
             reference_sequence.len(),
-            mapped_record_count,
-            unmapped_record_count
+            counts.mapped_record_count(),
+            counts.unmapped_record_count()
         );
     }
 
-    let unmapped_record_count = index.unplaced_unmapped_read_count().unwrap_or_default();
-    println!("*\t0\t0\t{}", unmapped_record_count);
+    println!("*\t0\t0\t{}", index_stats.unplaced_unmapped_record_count());
 
     Ok(())
 }
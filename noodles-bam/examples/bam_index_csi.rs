@@ -0,0 +1,104 @@
+//! Builds and writes a coordinate-sorted index (CSI) from a BAM file.
+//!
+//! The input BAM must be coordinate-sorted, i.e., `SO:coordinate`.
+//!
+//! This writes the output to stdout rather than `<src>.csi`.
+//!
+//! The output is similar to the output of `samtools index -c <src>`.
+
+use std::{env, fs::File, io};
+
+use noodles_bam as bam;
+use noodles_csi::{self as csi, index::reference_sequence::bin::Chunk};
+use noodles_sam::{self as sam, header::header::SortOrder};
+
+const MIN_SHIFT: u8 = 14;
+
+fn is_coordinate_sorted(header: &sam::Header) -> bool {
+    if let Some(hdr) = header.header() {
+        if let Some(sort_order) = hdr.sort_order() {
+            return sort_order == SortOrder::Coordinate;
+        }
+    }
+
+    false
+}
+
+// Calculates the smallest depth that can index the longest reference sequence.
+fn calculate_depth(header: &sam::Header) -> u8 {
+    let max_len = header
+        .reference_sequences()
+        .values()
+        .map(|reference_sequence| i64::from(reference_sequence.len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut depth = 0;
+    let mut max_position = 1i64 << MIN_SHIFT;
+
+    while max_position < max_len {
+        depth += 1;
+        max_position <<= 3;
+    }
+
+    depth
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let src = env::args().nth(1).expect("missing src");
+
+    let mut reader = File::open(src).map(bam::Reader::new)?;
+    let header: sam::Header = reader.read_header()?.parse()?;
+    reader.read_reference_sequences()?;
+
+    if !is_coordinate_sorted(&header) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the input BAM must be coordinate-sorted to be indexed",
+        )
+        .into());
+    }
+
+    let mut record = bam::Record::default();
+
+    let mut indexer = csi::index::Indexer::new(MIN_SHIFT, calculate_depth(&header))?;
+    let mut start_position = reader.virtual_position();
+
+    loop {
+        match reader.read_record(&mut record) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let end_position = reader.virtual_position();
+        let chunk = Chunk::new(start_position, end_position);
+
+        let reference_sequence_id = record
+            .reference_sequence_id()
+            .map(|id| i32::from(id) as usize);
+
+        if let Some(start) = record.position().map(i32::from) {
+            let len = record.cigar().reference_len().map(|len| len as i32)?;
+            let end = start + len - 1;
+            let is_mapped = !record.flags().is_unmapped();
+            indexer.add_record(reference_sequence_id, start, end, is_mapped, chunk)?;
+        } else {
+            indexer.add_record(None, 0, 0, false, chunk)?;
+        }
+
+        start_position = end_position;
+    }
+
+    let index = indexer.build(header.reference_sequences().len());
+
+    let stdout = io::stdout();
+    let handle = stdout.lock();
+    let mut writer = csi::Writer::new(handle);
+
+    writer.write_header()?;
+    writer.write_index(&index)?;
+    writer.try_finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! Marks duplicates in a coordinate-sorted BAM file.
+//!
+//! The results are written to the given destination, and duplication metrics are printed to
+//! stderr. This is similar to the outputs of `samtools markdup <src> <dst>`.
+
+use std::{env, fs::File};
+
+use noodles_bam::{self as bam, markdup::MarkDuplicates};
+use noodles_sam as sam;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = env::args().skip(1);
+
+    let src = args.next().expect("missing src");
+    let dst = args.next().expect("missing dst");
+
+    let mut reader = File::open(src).map(bam::Reader::new)?;
+    let header: sam::Header = reader.read_header()?.parse()?;
+    reader.read_reference_sequences()?;
+
+    let mut writer = File::create(dst).map(bam::Writer::new)?;
+    writer.write_header(&header)?;
+    writer.write_reference_sequences(header.reference_sequences())?;
+
+    let mut records = MarkDuplicates::new(reader.records(), &header);
+
+    for result in &mut records {
+        let record = result?;
+        writer.write_record(&record)?;
+    }
+
+    let metrics = records.metrics();
+
+    eprintln!(
+        "unpaired reads examined\t{}",
+        metrics.unpaired_reads_examined()
+    );
+    eprintln!("read pairs examined\t{}", metrics.read_pairs_examined());
+    eprintln!(
+        "unpaired read duplicates\t{}",
+        metrics.unpaired_read_duplicates()
+    );
+    eprintln!("read pair duplicates\t{}", metrics.read_pair_duplicates());
+    eprintln!(
+        "read pair optical duplicates\t{}",
+        metrics.read_pair_optical_duplicates()
+    );
+
+    if let Some(rate) = metrics.duplication_rate() {
+        eprintln!("duplication rate\t{:.6}", rate);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+//! Merges coordinate-sorted BAM files.
+//!
+//! The merged output is written to stdout.
+//!
+//! This is similar to the functionality of `samtools merge`.
+
+use std::{env, fs::File, io};
+
+use noodles_bam::{self as bam, merge::Merger, sort::SortOrder};
+use noodles_sam as sam;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let srcs: Vec<_> = env::args().skip(1).collect();
+
+    if srcs.is_empty() {
+        return Err("missing srcs".into());
+    }
+
+    let mut readers = Vec::with_capacity(srcs.len());
+    let mut headers = Vec::with_capacity(srcs.len());
+
+    for src in srcs {
+        let mut reader = File::open(src).map(bam::Reader::new)?;
+        let header: sam::Header = reader.read_header()?.parse()?;
+        reader.read_reference_sequences()?;
+
+        readers.push(reader);
+        headers.push(header);
+    }
+
+    let stdout = io::stdout();
+    let handle = stdout.lock();
+
+    let merger = Merger::new(SortOrder::Coordinate);
+    let (_, _) = merger.merge(readers, &headers, handle)?;
+
+    Ok(())
+}
@@ -1,9 +1,9 @@
-//! Querys a BAM file with a given region.
+//! Querys a BAM file with one or more given regions.
 //!
 //! The input BAM must have an index in the same directory.
 //!
 //! While the results are not formatted the same, the records printed match the output of `samtools
-//! view <src> <region>`.
+//! view <src> <region>...`.
 
 use std::{env, fs::File, path::PathBuf};
 
@@ -15,7 +15,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut args = env::args();
 
     let src = args.nth(1).map(PathBuf::from).expect("missing src");
-    let raw_region = args.next().expect("missing region");
+    let raw_regions: Vec<_> = args.collect();
+
+    if raw_regions.is_empty() {
+        panic!("missing region");
+    }
 
     let mut reader = File::open(&src).map(bam::Reader::new)?;
     let header: sam::Header = reader.read_header()?.parse()?;
@@ -23,8 +27,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let index = bai::read(src.with_extension("bam.bai"))?;
 
-    let region = Region::from_str_reference_sequences(&raw_region, reference_sequences)?;
-    let query = reader.query(reference_sequences, &index, &region)?;
+    let regions = raw_regions
+        .iter()
+        .map(|s| Region::from_str_reference_sequences(s, reference_sequences))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let query = reader.query_regions(reference_sequences, &index, &regions)?;
 
     for result in query {
         let record = result?;
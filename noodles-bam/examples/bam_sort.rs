@@ -0,0 +1,44 @@
+//! Sorts a BAM file by coordinate or read name.
+//!
+//! The sort order is either `coordinate` (default) or `queryname`. When coordinate sorting, a BAM
+//! index is also written to `<dst>.bai`.
+//!
+//! This is similar to the functionality of `samtools sort` or `samtools sort -n`.
+
+use std::{env, fs::File};
+
+use noodles_bam::{
+    self as bam,
+    sort::{SortOrder, Sorter},
+};
+use noodles_sam as sam;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = env::args().skip(1);
+
+    let src = args.next().expect("missing src");
+    let dst = args.next().expect("missing dst");
+
+    let sort_order = match args.next().as_deref() {
+        None | Some("coordinate") => SortOrder::Coordinate,
+        Some("queryname") => SortOrder::QueryName,
+        Some(s) => return Err(format!("invalid sort order: {}", s).into()),
+    };
+
+    let mut reader = File::open(src).map(bam::Reader::new)?;
+    let mut header: sam::Header = reader.read_header()?.parse()?;
+    reader.read_reference_sequences()?;
+
+    let sorter = Sorter::builder(sort_order)
+        .set_build_index(sort_order == SortOrder::Coordinate)
+        .build();
+
+    let writer = File::create(&dst)?;
+    let (_, index) = sorter.sort(&mut reader, &mut header, writer)?;
+
+    if let Some(index) = index {
+        bam::bai::write(format!("{}.bai", dst), &index)?;
+    }
+
+    Ok(())
+}
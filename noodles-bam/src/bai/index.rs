@@ -5,6 +5,10 @@ pub mod reference_sequence;
 
 pub use self::{builder::Builder, reference_sequence::ReferenceSequence};
 
+use std::io;
+
+use noodles_csi::{self as csi, BinningIndex};
+
 /// A BAM index.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Index {
@@ -75,3 +79,54 @@ impl Index {
         self.n_no_coor
     }
 }
+
+impl BinningIndex for Index {
+    /// Returns the chunks that overlap with the given region.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::bai;
+    /// use noodles_csi::BinningIndex;
+    ///
+    /// let index = bai::Index::new(vec![bai::index::ReferenceSequence::default()], None);
+    /// let chunks = index.query(0, 8, 13)?;
+    /// assert!(chunks.is_empty());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    fn query(
+        &self,
+        reference_sequence_id: usize,
+        start: i32,
+        end: i32,
+    ) -> io::Result<Vec<csi::index::reference_sequence::bin::Chunk>> {
+        let reference_sequence = self
+            .reference_sequences()
+            .get(reference_sequence_id)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "could not find reference in index: {} >= {}",
+                        reference_sequence_id,
+                        self.reference_sequences().len()
+                    ),
+                )
+            })?;
+
+        let query_bins = reference_sequence.query(start, end);
+
+        let chunks: Vec<_> = query_bins
+            .iter()
+            .flat_map(|bin| bin.chunks())
+            .map(|chunk| {
+                csi::index::reference_sequence::bin::Chunk::new(chunk.start(), chunk.end())
+            })
+            .collect();
+
+        let min_offset = reference_sequence.min_offset(start);
+
+        Ok(csi::optimize_chunks(&chunks, min_offset))
+    }
+}
@@ -82,10 +82,12 @@ impl Builder {
     /// let index = bai::Index::builder().build(1);
     /// ```
     pub fn build(mut self, reference_sequence_count: usize) -> Index {
-        let last_reference_sequence_id =
-            ReferenceSequenceId::try_from((reference_sequence_count - 1) as i32)
-                .expect("invalid reference sequence count");
-        self.add_reference_sequences_builders_until(last_reference_sequence_id);
+        if reference_sequence_count > 0 {
+            let last_reference_sequence_id =
+                ReferenceSequenceId::try_from((reference_sequence_count - 1) as i32)
+                    .expect("invalid reference sequence count");
+            self.add_reference_sequences_builders_until(last_reference_sequence_id);
+        }
 
         let reference_sequences = self
             .reference_sequences_builders
@@ -0,0 +1,533 @@
+//! BAM MD and NM data field calculation.
+//!
+//! The mismatched positions (`MD`) data field describes the reference bases at mismatches and
+//! deletions, and the edit distance (`NM`) data field is the number of mismatches, inserted bases,
+//! and deleted bases in an alignment. Both are calculated by walking the CIGAR of a record
+//! against its reference sequence.
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{convert::TryFrom, io};
+
+use noodles_sam::{
+    self as sam,
+    record::{cigar::op::Kind, data::field::Tag},
+};
+
+use crate::{
+    record::{
+        data::{field::Value, Field},
+        sequence::Base,
+    },
+    Record,
+};
+
+/// Calculated MD and NM data field values.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tags {
+    mismatched_positions: String,
+    edit_distance: u32,
+}
+
+impl Tags {
+    /// Returns the mismatched positions (`MD`) value.
+    pub fn mismatched_positions(&self) -> &str {
+        &self.mismatched_positions
+    }
+
+    /// Returns the edit distance (`NM`) value.
+    pub fn edit_distance(&self) -> u32 {
+        self.edit_distance
+    }
+}
+
+/// The status of a stored data field compared to its calculated value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TagStatus {
+    /// The stored value is equal to the calculated value.
+    Equal,
+    /// The data field is missing.
+    Missing,
+    /// The stored value differs from the calculated value.
+    Different,
+}
+
+/// A comparison of the stored MD and NM data fields of a record to their calculated values.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Report {
+    mismatched_positions: TagStatus,
+    edit_distance: TagStatus,
+}
+
+impl Report {
+    /// Returns the status of the mismatched positions (`MD`) data field.
+    pub fn mismatched_positions(&self) -> TagStatus {
+        self.mismatched_positions
+    }
+
+    /// Returns the status of the edit distance (`NM`) data field.
+    pub fn edit_distance(&self) -> TagStatus {
+        self.edit_distance
+    }
+
+    /// Returns whether both stored data fields are equal to their calculated values.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatched_positions == TagStatus::Equal && self.edit_distance == TagStatus::Equal
+    }
+}
+
+/// An MD/NM calculator.
+///
+/// Records that are unmapped or have no position or CIGAR are not aligned to the reference and
+/// are skipped, i.e., `None` is returned. Records with no sequence (`*`), e.g., secondary
+/// alignments, are also skipped.
+///
+/// Bases are compared case-insensitively. An `N` in either the read or reference is always a
+/// mismatch, and a read base of `=` is always a match.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io::{self, BufReader}};
+/// use noodles_bam::{self as bam, calmd::Calmd};
+/// use noodles_fasta as fasta;
+///
+/// let mut fasta_reader = File::open("reference.fa")
+///     .map(BufReader::new)
+///     .map(fasta::Reader::new)?;
+/// let reference_sequences: Vec<_> = fasta_reader.records().collect::<io::Result<_>>()?;
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// reader.read_header()?;
+/// reader.read_reference_sequences()?;
+///
+/// let calmd = Calmd::default();
+///
+/// for result in reader.records() {
+///     let mut record = result?;
+///
+///     let reference_sequence = match record.reference_sequence_id() {
+///         Some(id) => &reference_sequences[i32::from(id) as usize],
+///         None => continue,
+///     };
+///
+///     if let Some(report) = calmd.update(&mut record, reference_sequence.sequence())? {
+///         if !report.is_consistent() {
+///             eprintln!("{:?}: {:?}", record.read_name(), report);
+///         }
+///     }
+/// }
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct Calmd {
+    replace_matches: bool,
+}
+
+impl Calmd {
+    /// Creates an MD/NM calculator builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::calmd::Calmd;
+    /// let builder = Calmd::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Calculates the MD and NM data field values of a record.
+    ///
+    /// The reference sequence is the complete sequence of the reference the record is aligned
+    /// to, e.g., the sequence of a FASTA record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// use noodles_bam::{self as bam, calmd::Calmd, record::ReferenceSequenceId};
+    /// use noodles_sam::record::{Flags, Position};
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_flags(Flags::empty());
+    /// record.set_reference_sequence_id(Some(ReferenceSequenceId::try_from(0)?));
+    /// record.set_position(Some(Position::try_from(2)?))?;
+    /// record.set_cigar(&"2M1I1M1D1M".parse()?)?;
+    /// record.set_sequence(&"ACTGG".parse()?)?;
+    ///
+    /// let tags = Calmd::default()
+    ///     .calculate(&record, b"TACGTAC")?
+    ///     .expect("record is unmapped");
+    ///
+    /// assert_eq!(tags.mismatched_positions(), "3^T0A0");
+    /// assert_eq!(tags.edit_distance(), 3);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn calculate(
+        &self,
+        record: &Record,
+        reference_sequence: &[u8],
+    ) -> io::Result<Option<Tags>> {
+        calculate(record, reference_sequence, None)
+    }
+
+    /// Compares the stored MD and NM data fields of a record to their calculated values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// use noodles_bam::{
+    ///     self as bam,
+    ///     calmd::{Calmd, TagStatus},
+    ///     record::{data::{field::Value, Field}, ReferenceSequenceId},
+    /// };
+    /// use noodles_sam::record::{data::field::Tag, Flags, Position};
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_flags(Flags::empty());
+    /// record.set_reference_sequence_id(Some(ReferenceSequenceId::try_from(0)?));
+    /// record.set_position(Some(Position::try_from(1)?))?;
+    /// record.set_cigar(&"4M".parse()?)?;
+    /// record.set_sequence(&"ACGT".parse()?)?;
+    /// record.insert_data_field(Field::new(Tag::EditDistance, Value::UInt8(0)))?;
+    ///
+    /// let report = Calmd::default()
+    ///     .verify(&record, b"ACCT")?
+    ///     .expect("record is unmapped");
+    ///
+    /// assert_eq!(report.mismatched_positions(), TagStatus::Missing);
+    /// assert_eq!(report.edit_distance(), TagStatus::Different);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn verify(&self, record: &Record, reference_sequence: &[u8]) -> io::Result<Option<Report>> {
+        match self.calculate(record, reference_sequence)? {
+            Some(tags) => compare(record, &tags).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Calculates and sets the MD and NM data fields of a record.
+    ///
+    /// The returned report compares the data fields stored before the update to the calculated
+    /// values. If replacing matches is enabled, read bases that match the reference are also
+    /// replaced with `=`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// use noodles_bam::{self as bam, calmd::Calmd, record::ReferenceSequenceId};
+    /// use noodles_sam::record::{data::field::Tag, Flags, Position};
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_flags(Flags::empty());
+    /// record.set_reference_sequence_id(Some(ReferenceSequenceId::try_from(0)?));
+    /// record.set_position(Some(Position::try_from(1)?))?;
+    /// record.set_cigar(&"4M".parse()?)?;
+    /// record.set_sequence(&"ACGT".parse()?)?;
+    ///
+    /// let calmd = Calmd::builder().set_replace_matches(true).build();
+    /// let report = calmd.update(&mut record, b"ACCT")?.expect("record is unmapped");
+    /// assert!(!report.is_consistent());
+    ///
+    /// let data = record.data();
+    /// let md = data.get(&Tag::MismatchedPositions).transpose()?;
+    /// assert_eq!(md.and_then(|value| value.as_str()), Some("2C1"));
+    /// let nm = data.get(&Tag::EditDistance).transpose()?;
+    /// assert_eq!(nm.and_then(|value| value.as_int()), Some(1));
+    ///
+    /// assert_eq!(record.sequence().to_string(), "==G=");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn update(
+        &self,
+        record: &mut Record,
+        reference_sequence: &[u8],
+    ) -> io::Result<Option<Report>> {
+        let mut sequence = if self.replace_matches {
+            Some(Vec::new())
+        } else {
+            None
+        };
+
+        let tags = match calculate(record, reference_sequence, sequence.as_mut())? {
+            Some(tags) => tags,
+            None => return Ok(None),
+        };
+
+        let report = compare(record, &tags)?;
+
+        record.insert_data_field(Field::new(
+            Tag::MismatchedPositions,
+            Value::String(tags.mismatched_positions),
+        ))?;
+
+        let edit_distance = i32::try_from(tags.edit_distance)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        record.insert_data_field(Field::new(Tag::EditDistance, Value::Int32(edit_distance)))?;
+
+        if let Some(bases) = sequence {
+            record.set_sequence(&sam::record::Sequence::from(bases))?;
+        }
+
+        Ok(Some(report))
+    }
+}
+
+// Calculates the MD and NM values of a record. If `replaced_sequence` is given, it is filled
+// with the read sequence with bases matching the reference replaced with `=`.
+fn calculate(
+    record: &Record,
+    reference_sequence: &[u8],
+    mut replaced_sequence: Option<&mut Vec<sam::record::sequence::Base>>,
+) -> io::Result<Option<Tags>> {
+    if record.flags().is_unmapped() || record.reference_sequence_id().is_none() {
+        return Ok(None);
+    }
+
+    let mut reference_position = match record.position() {
+        Some(position) => (i32::from(position) - 1) as usize,
+        None => return Ok(None),
+    };
+
+    let cigar = record.cigar();
+
+    if cigar.is_empty() {
+        return Ok(None);
+    }
+
+    let sequence = record.sequence();
+
+    if sequence.base_count() == 0 {
+        return Ok(None);
+    }
+    let bases: Vec<Base> = sequence.bases().collect();
+
+    if let Some(replaced_sequence) = replaced_sequence.as_mut() {
+        replaced_sequence.extend(
+            bases
+                .iter()
+                .map(|&base| sam::record::sequence::Base::from(base)),
+        );
+    }
+
+    let reference_base = |i: usize| {
+        reference_sequence
+            .get(i)
+            .map(|b| b.to_ascii_uppercase())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "alignment extends past the end of the reference sequence",
+                )
+            })
+    };
+
+    let mut mismatched_positions = String::new();
+    let mut edit_distance = 0;
+    let mut match_count = 0;
+    let mut read_position = 0;
+
+    for result in cigar.ops() {
+        let op = result?;
+        let len = op.len() as usize;
+
+        match op.kind() {
+            Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                for i in 0..len {
+                    let reference_base = reference_base(reference_position + i)?;
+
+                    let read_base = bases.get(read_position + i).copied().ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "CIGAR read length exceeds sequence length",
+                        )
+                    })?;
+
+                    let is_match = match read_base {
+                        Base::Eq => true,
+                        Base::N => false,
+                        _ => {
+                            reference_base != b'N' && char::from(read_base) as u8 == reference_base
+                        }
+                    };
+
+                    if is_match {
+                        match_count += 1;
+
+                        if let Some(replaced_sequence) = replaced_sequence.as_mut() {
+                            replaced_sequence[read_position + i] = sam::record::sequence::Base::Eq;
+                        }
+                    } else {
+                        push_match_count(&mut mismatched_positions, &mut match_count);
+                        mismatched_positions.push(char::from(reference_base));
+                        edit_distance += 1;
+                    }
+                }
+
+                reference_position += len;
+                read_position += len;
+            }
+            Kind::Insertion => {
+                read_position += len;
+                edit_distance += op.len();
+            }
+            Kind::Deletion => {
+                push_match_count(&mut mismatched_positions, &mut match_count);
+                mismatched_positions.push('^');
+
+                for i in 0..len {
+                    let reference_base = reference_base(reference_position + i)?;
+                    mismatched_positions.push(char::from(reference_base));
+                }
+
+                reference_position += len;
+                edit_distance += op.len();
+            }
+            Kind::Skip => reference_position += len,
+            Kind::SoftClip => read_position += len,
+            Kind::HardClip | Kind::Pad => {}
+        }
+    }
+
+    push_match_count(&mut mismatched_positions, &mut match_count);
+
+    Ok(Some(Tags {
+        mismatched_positions,
+        edit_distance,
+    }))
+}
+
+fn push_match_count(mismatched_positions: &mut String, match_count: &mut u32) {
+    mismatched_positions.push_str(&match_count.to_string());
+    *match_count = 0;
+}
+
+fn compare(record: &Record, tags: &Tags) -> io::Result<Report> {
+    let data = record.data();
+
+    let mismatched_positions = match data.get(&Tag::MismatchedPositions).transpose()? {
+        Some(value) if value.as_str() == Some(tags.mismatched_positions()) => TagStatus::Equal,
+        Some(_) => TagStatus::Different,
+        None => TagStatus::Missing,
+    };
+
+    let edit_distance = match data.get(&Tag::EditDistance).transpose()? {
+        Some(value) if value.as_int() == Some(i64::from(tags.edit_distance())) => TagStatus::Equal,
+        Some(_) => TagStatus::Different,
+        None => TagStatus::Missing,
+    };
+
+    Ok(Report {
+        mismatched_positions,
+        edit_distance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use noodles_sam::record::{Flags, Position};
+
+    use crate::record::ReferenceSequenceId;
+
+    use super::*;
+
+    fn build_record(
+        position: i32,
+        cigar: &str,
+        sequence: &str,
+    ) -> Result<Record, Box<dyn std::error::Error>> {
+        let mut record = Record::default();
+        record.set_flags(Flags::empty());
+        record.set_reference_sequence_id(Some(ReferenceSequenceId::try_from(0)?));
+        record.set_position(Some(Position::try_from(position)?))?;
+        record.set_cigar(&cigar.parse()?)?;
+        record.set_sequence(&sequence.parse()?)?;
+        Ok(record)
+    }
+
+    #[test]
+    fn test_calculate() -> Result<(), Box<dyn std::error::Error>> {
+        let calmd = Calmd::default();
+
+        let record = build_record(2, "1S2M2N2M1H", "TANGT")?;
+        let tags = calmd.calculate(&record, b"nacgtacgt")?;
+        assert_eq!(
+            tags,
+            Some(Tags {
+                mismatched_positions: String::from("1C0A0C0"),
+                edit_distance: 3,
+            })
+        );
+
+        let record = build_record(1, "4M", "A=GN")?;
+        let tags = calmd.calculate(&record, b"ACGN")?;
+        assert_eq!(
+            tags,
+            Some(Tags {
+                mismatched_positions: String::from("3N0"),
+                edit_distance: 1,
+            })
+        );
+
+        let mut record = build_record(1, "4M", "ACGT")?;
+        record.set_flags(Flags::UNMAPPED);
+        assert!(calmd.calculate(&record, b"ACGT")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_with_missing_sequence() -> Result<(), Box<dyn std::error::Error>> {
+        let calmd = Calmd::default();
+
+        let mut record = build_record(1, "4M", "*")?;
+        assert_eq!(record.sequence().base_count(), 0);
+
+        assert!(calmd.calculate(&record, b"ACGT")?.is_none());
+        assert!(calmd.verify(&record, b"ACGT")?.is_none());
+        assert!(calmd.update(&mut record, b"ACGT")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_with_short_reference_sequence() -> Result<(), Box<dyn std::error::Error>> {
+        let record = build_record(3, "4M", "ACGT")?;
+
+        assert!(matches!(
+            Calmd::default().calculate(&record, b"ACGT"),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update() -> Result<(), Box<dyn std::error::Error>> {
+        let calmd = Calmd::default();
+
+        let mut record = build_record(1, "4M", "ACGT")?;
+
+        let report = calmd.update(&mut record, b"ACCT")?;
+        assert_eq!(
+            report,
+            Some(Report {
+                mismatched_positions: TagStatus::Missing,
+                edit_distance: TagStatus::Missing,
+            })
+        );
+        assert_eq!(record.sequence().to_string(), "ACGT");
+
+        let report = calmd.verify(&record, b"ACCT")?;
+        assert!(report.map(|r| r.is_consistent()).unwrap_or(false));
+
+        Ok(())
+    }
+}
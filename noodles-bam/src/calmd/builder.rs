@@ -0,0 +1,39 @@
+use super::Calmd;
+
+/// An MD/NM calculator builder.
+#[derive(Debug, Default)]
+pub struct Builder {
+    replace_matches: bool,
+}
+
+impl Builder {
+    /// Sets whether to replace read bases that match the reference with `=` when updating a
+    /// record.
+    ///
+    /// By default, this is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::calmd::Calmd;
+    /// let calmd = Calmd::builder().set_replace_matches(true).build();
+    /// ```
+    pub fn set_replace_matches(mut self, replace_matches: bool) -> Self {
+        self.replace_matches = replace_matches;
+        self
+    }
+
+    /// Builds an MD/NM calculator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::calmd::Calmd;
+    /// let calmd = Calmd::builder().build();
+    /// ```
+    pub fn build(self) -> Calmd {
+        Calmd {
+            replace_matches: self.replace_matches,
+        }
+    }
+}
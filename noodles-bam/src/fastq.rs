@@ -0,0 +1,401 @@
+//! BAM to FASTQ conversion.
+//!
+//! SAM records can be converted by first converting them to BAM records using
+//! [`Record::try_from_sam_record`].
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io::{self, Write},
+};
+
+use noodles_fastq as fastq;
+use noodles_sam::{self as sam, record::data::field::Tag};
+
+use crate::{record::data::field::Value, Record};
+
+const QUALITY_OFFSET: u8 = b'!';
+const MISSING_QUALITY_SCORE: u8 = 0xff;
+
+// The quality score written for each base of a record with missing quality scores.
+const DEFAULT_QUALITY_SCORE: u8 = 1;
+
+/// A BAM to FASTQ converter.
+///
+/// Each primary record is converted to a FASTQ record in its original read orientation, i.e.,
+/// records with the reverse complemented flag have their sequences reverse complemented and their
+/// quality scores reversed. Secondary and supplementary records are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bam::{self as bam, fastq::Converter};
+/// use noodles_fastq as fastq;
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// reader.read_header()?;
+/// reader.read_reference_sequences()?;
+///
+/// let mut read_1_writer = File::create("sample.1.fastq").map(fastq::Writer::new)?;
+/// let mut read_2_writer = File::create("sample.2.fastq").map(fastq::Writer::new)?;
+/// let mut singleton_writer = File::create("sample.s.fastq").map(fastq::Writer::new)?;
+///
+/// Converter::default().convert(
+///     reader.records(),
+///     &mut read_1_writer,
+///     &mut read_2_writer,
+///     &mut singleton_writer,
+/// )?;
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct Converter {
+    tags: Vec<Tag>,
+}
+
+impl Converter {
+    /// Creates a FASTQ converter builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::fastq::Converter;
+    /// let builder = Converter::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Converts records and writes them to FASTQ writers by segment.
+    ///
+    /// Paired records with only the first segment flag set are written to `read_1_writer`, and
+    /// paired records with only the last segment flag set are written to `read_2_writer`. Mates
+    /// are matched by read name, and each pair is written at the same time so that the two
+    /// outputs remain in the same order. Records are held in memory until their mates are found,
+    /// so queryname-grouped input is preferred.
+    ///
+    /// Unpaired records, records with an ambiguous segment, and paired records whose mates are
+    /// not found are written to `singleton_writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{ffi::CString, io};
+    /// use noodles_bam::{self as bam, fastq::Converter};
+    /// use noodles_fastq as fastq;
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_read_name(&CString::new("r0")?)?;
+    /// record.set_sequence(&"ACGT".parse()?)?;
+    /// record.set_quality_scores(&"NDLS".parse()?)?;
+    ///
+    /// let mut read_1_writer = fastq::Writer::new(Vec::new());
+    /// let mut read_2_writer = fastq::Writer::new(Vec::new());
+    /// let mut singleton_writer = fastq::Writer::new(Vec::new());
+    ///
+    /// Converter::default().convert(
+    ///     vec![Ok(record)],
+    ///     &mut read_1_writer,
+    ///     &mut read_2_writer,
+    ///     &mut singleton_writer,
+    /// )?;
+    ///
+    /// assert!(read_1_writer.get_ref().is_empty());
+    /// assert!(read_2_writer.get_ref().is_empty());
+    /// assert_eq!(singleton_writer.get_ref(), b"@r0\nACGT\n+\nNDLS\n");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn convert<I, W, X, Y>(
+        &self,
+        records: I,
+        read_1_writer: &mut fastq::Writer<W>,
+        read_2_writer: &mut fastq::Writer<X>,
+        singleton_writer: &mut fastq::Writer<Y>,
+    ) -> io::Result<()>
+    where
+        I: IntoIterator<Item = io::Result<Record>>,
+        W: Write,
+        X: Write,
+        Y: Write,
+    {
+        // read name => (arrival index, segment, FASTQ record)
+        let mut unmatched_mates: HashMap<Vec<u8>, (u64, Segment, fastq::Record)> = HashMap::new();
+        let mut i = 0;
+
+        for result in records {
+            let record = result?;
+            let flags = record.flags();
+
+            if flags.is_secondary() || flags.is_supplementary() {
+                continue;
+            }
+
+            let segment = Segment::from(flags);
+            let fastq_record = self.convert_record(&record)?;
+
+            if segment == Segment::Unknown {
+                singleton_writer.write_record(&fastq_record)?;
+                continue;
+            }
+
+            let read_name = read_name(&record)?.to_vec();
+
+            match unmatched_mates.remove(&read_name) {
+                Some((_, mate_segment, mate)) if mate_segment != segment => {
+                    let (read_1, read_2) = if segment == Segment::Read1 {
+                        (fastq_record, mate)
+                    } else {
+                        (mate, fastq_record)
+                    };
+
+                    read_1_writer.write_record(&read_1)?;
+                    read_2_writer.write_record(&read_2)?;
+                }
+                Some((_, _, mate)) => {
+                    // A duplicate segment cannot be paired. The earlier record is written as a
+                    // singleton.
+                    singleton_writer.write_record(&mate)?;
+                    unmatched_mates.insert(read_name, (i, segment, fastq_record));
+                }
+                None => {
+                    unmatched_mates.insert(read_name, (i, segment, fastq_record));
+                }
+            }
+
+            i += 1;
+        }
+
+        let mut singletons: Vec<_> = unmatched_mates.into_values().collect();
+        singletons.sort_by_key(|(i, _, _)| *i);
+
+        for (_, _, fastq_record) in singletons {
+            singleton_writer.write_record(&fastq_record)?;
+        }
+
+        Ok(())
+    }
+
+    /// Converts a BAM record to a FASTQ record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::ffi::CString;
+    /// use noodles_bam::{self as bam, fastq::Converter};
+    /// use noodles_sam::record::Flags;
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_read_name(&CString::new("r0")?)?;
+    /// record.set_flags(Flags::REVERSE_COMPLEMENTED);
+    /// record.set_sequence(&"ACGG".parse()?)?;
+    /// record.set_quality_scores(&"NDLS".parse()?)?;
+    ///
+    /// let fastq_record = Converter::default().convert_record(&record)?;
+    ///
+    /// assert_eq!(fastq_record.read_name(), b"r0");
+    /// assert_eq!(fastq_record.sequence(), b"CCGT");
+    /// assert_eq!(fastq_record.quality_scores(), b"SLDN");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn convert_record(&self, record: &Record) -> io::Result<fastq::Record> {
+        let mut name = read_name(record)?.to_vec();
+
+        let data = record.data();
+
+        for tag in &self.tags {
+            if let Some(result) = data.get(tag) {
+                let value = sam::record::data::field::Value::try_from(Value::from(result?))
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                let field = sam::record::data::Field::new(tag.clone(), value);
+                name.push(b'\t');
+                name.extend(field.to_string().bytes());
+            }
+        }
+
+        let mut sequence: Vec<u8> = record
+            .sequence()
+            .bases()
+            .map(|base| char::from(base) as u8)
+            .collect();
+
+        let raw_quality_scores = record.quality_scores();
+
+        let mut quality_scores: Vec<u8> = if raw_quality_scores
+            .iter()
+            .all(|&s| s == MISSING_QUALITY_SCORE)
+        {
+            vec![DEFAULT_QUALITY_SCORE + QUALITY_OFFSET; sequence.len()]
+        } else {
+            raw_quality_scores
+                .iter()
+                .map(|&s| {
+                    s.checked_add(QUALITY_OFFSET).ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("invalid quality score: {}", s),
+                        )
+                    })
+                })
+                .collect::<io::Result<_>>()?
+        };
+
+        if record.flags().is_reverse_complemented() {
+            sequence = record
+                .sequence()
+                .bases()
+                .rev()
+                .map(|base| char::from(base.complement()) as u8)
+                .collect();
+
+            quality_scores.reverse();
+        }
+
+        Ok(fastq::Record::new(name, sequence, quality_scores))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Segment {
+    Read1,
+    Read2,
+    Unknown,
+}
+
+impl From<sam::record::Flags> for Segment {
+    fn from(flags: sam::record::Flags) -> Self {
+        if !flags.is_paired() {
+            return Self::Unknown;
+        }
+
+        match (flags.is_read_1(), flags.is_read_2()) {
+            (true, false) => Self::Read1,
+            (false, true) => Self::Read2,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+fn read_name(record: &Record) -> io::Result<&[u8]> {
+    record
+        .read_name()
+        .map(|name| name.to_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use noodles_sam::record::Flags;
+
+    use crate::record::data::Field;
+
+    use super::*;
+
+    fn build_record(
+        read_name: &str,
+        flags: Flags,
+        sequence: &str,
+    ) -> Result<Record, Box<dyn std::error::Error>> {
+        let mut record = Record::default();
+        record.set_read_name(&CString::new(read_name)?)?;
+        record.set_flags(flags);
+        record.set_sequence(&sequence.parse()?)?;
+        record.set_quality_scores(&"N".repeat(sequence.len()).parse()?)?;
+        Ok(record)
+    }
+
+    #[test]
+    fn test_convert() -> Result<(), Box<dyn std::error::Error>> {
+        let paired_1 = Flags::PAIRED | Flags::READ_1;
+        let paired_2 = Flags::PAIRED | Flags::READ_2;
+
+        let records = vec![
+            build_record("r0", paired_2, "AC")?,
+            build_record("r1", paired_1, "GT")?,
+            build_record("r0", paired_1 | Flags::SECONDARY, "AA")?,
+            build_record("r0", paired_1, "CG")?,
+            build_record("r2", Flags::empty(), "TT")?,
+            build_record("r1", paired_2 | Flags::SUPPLEMENTARY, "CC")?,
+        ];
+
+        let mut read_1_writer = fastq::Writer::new(Vec::new());
+        let mut read_2_writer = fastq::Writer::new(Vec::new());
+        let mut singleton_writer = fastq::Writer::new(Vec::new());
+
+        Converter::default().convert(
+            records.into_iter().map(Ok),
+            &mut read_1_writer,
+            &mut read_2_writer,
+            &mut singleton_writer,
+        )?;
+
+        assert_eq!(read_1_writer.get_ref(), b"@r0\nCG\n+\nNN\n");
+        assert_eq!(read_2_writer.get_ref(), b"@r0\nAC\n+\nNN\n");
+        assert_eq!(
+            singleton_writer.get_ref(),
+            b"@r2\nTT\n+\nNN\n@r1\nGT\n+\nNN\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_record_with_tags() -> Result<(), Box<dyn std::error::Error>> {
+        let mut record = build_record("r0", Flags::empty(), "ACGT")?;
+        record.insert_data_field(Field::new(
+            Tag::UmiSequence,
+            Value::String(String::from("TTAA")),
+        ))?;
+        record.insert_data_field(Field::new(Tag::AlignmentHitCount, Value::UInt8(1)))?;
+
+        let converter = Converter::builder()
+            .set_tags(vec![
+                Tag::SampleBarcodeSequence,
+                Tag::AlignmentHitCount,
+                Tag::UmiSequence,
+            ])
+            .build();
+
+        let fastq_record = converter.convert_record(&record)?;
+        assert_eq!(fastq_record.read_name(), b"r0\tNH:i:1\tRX:Z:TTAA");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_record_with_missing_quality_scores() -> Result<(), Box<dyn std::error::Error>> {
+        let mut record = Record::default();
+        record.set_read_name(&CString::new("r0")?)?;
+        record.set_sequence(&"ACGT".parse()?)?;
+
+        let fastq_record = Converter::default().convert_record(&record)?;
+        assert_eq!(fastq_record.quality_scores(), b"\"\"\"\"");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_record_with_out_of_range_quality_score(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let record = build_record("r0", Flags::empty(), "ACGT")?;
+
+        // The quality scores are at the end of a record with no data fields.
+        let mut buf = record.to_vec();
+        let i = buf.len() - 1;
+        buf[i] = u8::MAX - QUALITY_OFFSET + 1;
+        let record = Record::from(buf);
+
+        assert!(matches!(
+            Converter::default().convert_record(&record),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use noodles_sam::record::data::field::Tag;
+
+use super::Converter;
+
+/// A FASTQ converter builder.
+#[derive(Debug, Default)]
+pub struct Builder {
+    tags: Vec<Tag>,
+}
+
+impl Builder {
+    /// Sets the data field tags to copy to the FASTQ read name line.
+    ///
+    /// Each data field that is present in a record is appended to the read name as a
+    /// tab-delimited SAM data field, e.g., `r0\tBC:Z:ACGT`. Tags are written in the given order.
+    ///
+    /// By default, no data fields are copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::fastq::Converter;
+    /// use noodles_sam::record::data::field::Tag;
+    ///
+    /// let converter = Converter::builder()
+    ///     .set_tags(vec![Tag::SampleBarcodeSequence, Tag::UmiSequence])
+    ///     .build();
+    /// ```
+    pub fn set_tags(mut self, tags: Vec<Tag>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Builds a FASTQ converter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::fastq::Converter;
+    /// let converter = Converter::builder().build();
+    /// ```
+    pub fn build(self) -> Converter {
+        Converter { tags: self.tags }
+    }
+}
@@ -0,0 +1,468 @@
+//! BAM mate information fixing.
+//!
+//! The two primary reads of a pair each describe their mate, i.e., the mate reference sequence
+//! ID, mate position, mate flags, and template length. These can become inconsistent after
+//! realignment or editing. [`FixMate`] rewrites these fields from the partner read.
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{collections::VecDeque, io};
+
+use noodles_sam::record::{data::field::Tag, Flags};
+
+use crate::{
+    record::{
+        data::{field::Value, Field},
+        ReferenceSequenceId,
+    },
+    Record,
+};
+
+/// An iterator that fixes the mate information of queryname-grouped records.
+///
+/// Records with the same read name must be adjacent, e.g., the output of an aligner or a
+/// queryname sort. For each template, the primary first and last segments are synchronized with
+/// one another:
+///
+///   * the mate reference sequence ID and mate position are set to the reference sequence ID and
+///     position of the mate;
+///   * the mate unmapped and mate reverse complemented flags are set from the flags of the mate;
+///   * the template length is recalculated if both reads are mapped to the same reference
+///     sequence and otherwise set to 0;
+///   * the proper pair flag is cleared if either read is unmapped; and
+///   * optionally, the mate CIGAR (`MC`) and mate mapping quality (`MQ`) data fields are set.
+///
+/// An unmapped read with a mapped mate is placed at the position of its mate. If both reads are
+/// unmapped, their reference sequence IDs and positions are cleared.
+///
+/// Secondary and supplementary records are not used as mates, but their mate fields and data
+/// fields are updated from the primary record of the other segment. Their template lengths are
+/// left unchanged.
+///
+/// Templates without both primary segments are passed through unchanged.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bam::{self as bam, fixmate::FixMate};
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// reader.read_header()?;
+/// reader.read_reference_sequences()?;
+///
+/// let records = FixMate::builder(reader.records())
+///     .set_add_mate_cigar(true)
+///     .build();
+///
+/// for result in records {
+///     let record = result?;
+///     println!("{:?}", record);
+/// }
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct FixMate<I> {
+    records: I,
+    add_mate_cigar: bool,
+    add_mate_mapping_quality: bool,
+    buf: VecDeque<Record>,
+    next_record: Option<Record>,
+}
+
+impl<I> FixMate<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    /// Creates a mate information fixer with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::fixmate::FixMate;
+    /// let records = FixMate::new(iter::empty());
+    /// ```
+    pub fn new(records: I) -> Self {
+        Builder::new(records).build()
+    }
+
+    /// Creates a mate information fixer builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::fixmate::FixMate;
+    /// let builder = FixMate::builder(iter::empty());
+    /// ```
+    pub fn builder(records: I) -> Builder<I> {
+        Builder::new(records)
+    }
+
+    // Reads the next group of records with the same read name.
+    fn read_group(&mut self) -> io::Result<Vec<Record>> {
+        let first_record = match self.next_record.take() {
+            Some(record) => record,
+            None => match self.records.next() {
+                Some(result) => result?,
+                None => return Ok(Vec::new()),
+            },
+        };
+
+        let mut group = vec![first_record];
+
+        for result in self.records.by_ref() {
+            let record = result?;
+
+            if read_name(&record)? == read_name(&group[0])? {
+                group.push(record);
+            } else {
+                self.next_record = Some(record);
+                break;
+            }
+        }
+
+        Ok(group)
+    }
+
+    fn fix_group(&self, records: &mut [Record]) -> io::Result<()> {
+        let is_primary = |flags: Flags| !flags.is_secondary() && !flags.is_supplementary();
+
+        let find = |is_segment: fn(Flags) -> bool| {
+            records.iter().position(|record| {
+                let flags = record.flags();
+                flags.is_paired() && is_primary(flags) && is_segment(flags)
+            })
+        };
+
+        let (i, j) = match (find(Flags::is_read_1), find(Flags::is_read_2)) {
+            (Some(i), Some(j)) => (i, j),
+            _ => return Ok(()),
+        };
+
+        place_unmapped(records, i, j)?;
+
+        let mate_1 = Mate::try_from_record(&records[i])?;
+        let mate_2 = Mate::try_from_record(&records[j])?;
+
+        let template_length = calculate_template_length(&mate_1, &mate_2);
+        let is_proper_pair_possible = !mate_1.is_unmapped && !mate_2.is_unmapped;
+
+        for (k, record) in records.iter_mut().enumerate() {
+            let flags = record.flags();
+
+            if !flags.is_paired() {
+                continue;
+            }
+
+            let mate = if flags.is_read_1() {
+                &mate_2
+            } else if flags.is_read_2() {
+                &mate_1
+            } else {
+                continue;
+            };
+
+            self.update_mate(record, mate)?;
+
+            if k == i {
+                record.set_template_length(template_length);
+            } else if k == j {
+                record.set_template_length(-template_length);
+            }
+
+            if (k == i || k == j) && !is_proper_pair_possible {
+                let mut flags = record.flags();
+                flags.remove(Flags::PROPER_PAIR);
+                record.set_flags(flags);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_mate(&self, record: &mut Record, mate: &Mate) -> io::Result<()> {
+        record.set_mate_reference_sequence_id(mate.reference_sequence_id);
+        record.set_mate_position(mate.position);
+
+        let mut flags = record.flags();
+        flags.set(Flags::MATE_UNMAPPED, mate.is_unmapped);
+        flags.set(
+            Flags::MATE_REVERSE_COMPLEMENTED,
+            mate.is_reverse_complemented,
+        );
+        record.set_flags(flags);
+
+        if self.add_mate_cigar {
+            if mate.is_unmapped {
+                record.remove_data_field(&Tag::MateCigar)?;
+            } else {
+                let value = Value::String(mate.cigar.clone());
+                record.insert_data_field(Field::new(Tag::MateCigar, value))?;
+            }
+        }
+
+        if self.add_mate_mapping_quality {
+            if mate.is_unmapped {
+                record.remove_data_field(&Tag::MateMappingQuality)?;
+            } else {
+                let value = Value::UInt8(mate.mapping_quality);
+                record.insert_data_field(Field::new(Tag::MateMappingQuality, value))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<I> Iterator for FixMate<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.buf.pop_front() {
+                return Some(Ok(record));
+            }
+
+            let mut group = match self.read_group() {
+                Ok(group) => group,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if group.is_empty() {
+                return None;
+            }
+
+            if let Err(e) = self.fix_group(&mut group) {
+                return Some(Err(e));
+            }
+
+            self.buf.extend(group);
+        }
+    }
+}
+
+// The fields of a primary read used to describe it as a mate.
+struct Mate {
+    reference_sequence_id: Option<ReferenceSequenceId>,
+    position: Option<noodles_sam::record::Position>,
+    is_unmapped: bool,
+    is_reverse_complemented: bool,
+    is_read_1: bool,
+    reference_len: u32,
+    cigar: String,
+    mapping_quality: u8,
+}
+
+impl Mate {
+    fn try_from_record(record: &Record) -> io::Result<Self> {
+        let flags = record.flags();
+        let cigar = record.cigar();
+
+        Ok(Self {
+            reference_sequence_id: record.reference_sequence_id(),
+            position: record.position(),
+            is_unmapped: flags.is_unmapped(),
+            is_reverse_complemented: flags.is_reverse_complemented(),
+            is_read_1: flags.is_read_1(),
+            reference_len: cigar.reference_len()?,
+            cigar: cigar.to_string(),
+            mapping_quality: u8::from(record.mapping_quality()),
+        })
+    }
+}
+
+fn read_name(record: &Record) -> io::Result<&[u8]> {
+    record
+        .read_name()
+        .map(|name| name.to_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// Places an unmapped read at the position of its mapped mate or, if both reads are unmapped,
+// clears both of their positions.
+fn place_unmapped(records: &mut [Record], i: usize, j: usize) -> io::Result<()> {
+    let is_unmapped = |record: &Record| record.flags().is_unmapped();
+
+    match (is_unmapped(&records[i]), is_unmapped(&records[j])) {
+        (true, true) => {
+            for &k in &[i, j] {
+                records[k].set_reference_sequence_id(None);
+                records[k].set_position(None)?;
+            }
+        }
+        (true, false) => copy_position(records, j, i)?,
+        (false, true) => copy_position(records, i, j)?,
+        (false, false) => {}
+    }
+
+    Ok(())
+}
+
+fn copy_position(records: &mut [Record], src: usize, dst: usize) -> io::Result<()> {
+    let reference_sequence_id = records[src].reference_sequence_id();
+    let position = records[src].position();
+
+    records[dst].set_reference_sequence_id(reference_sequence_id);
+    records[dst].set_position(position)
+}
+
+// Calculates the observed template length of the first read. The template length of the second
+// read is its negation.
+//
+// The template length is positive for the leftmost read. If both reads start at the same
+// position, the first segment is considered the leftmost.
+fn calculate_template_length(a: &Mate, b: &Mate) -> i32 {
+    if a.is_unmapped || b.is_unmapped || a.reference_sequence_id != b.reference_sequence_id {
+        return 0;
+    }
+
+    let (a_start, b_start) = match (a.position, b.position) {
+        (Some(a_start), Some(b_start)) => (i32::from(a_start), i32::from(b_start)),
+        _ => return 0,
+    };
+
+    let end = |start: i32, reference_len: u32| start + reference_len.max(1) as i32 - 1;
+    let a_end = end(a_start, a.reference_len);
+    let b_end = end(b_start, b.reference_len);
+
+    let len = a_end.max(b_end) - a_start.min(b_start) + 1;
+
+    if a_start < b_start || (a_start == b_start && a.is_read_1) {
+        len
+    } else {
+        -len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, ffi::CString};
+
+    use noodles_sam::record::{MappingQuality, Position};
+
+    use crate::record::data::field::ValueRef;
+
+    use super::*;
+
+    fn build_record(
+        read_name: &str,
+        flags: Flags,
+        position: Option<i32>,
+        cigar: &str,
+    ) -> Result<Record, Box<dyn std::error::Error>> {
+        let mut record = Record::default();
+        record.set_read_name(&CString::new(read_name)?)?;
+        record.set_flags(Flags::PAIRED | flags);
+
+        if let Some(position) = position {
+            record.set_reference_sequence_id(Some(ReferenceSequenceId::try_from(0)?));
+            record.set_position(Some(Position::try_from(position)?))?;
+        }
+
+        record.set_mapping_quality(MappingQuality::from(30));
+
+        if !cigar.is_empty() {
+            record.set_cigar(&cigar.parse()?)?;
+        }
+
+        Ok(record)
+    }
+
+    fn mate_position(record: &Record) -> Option<i32> {
+        record.mate_position().map(i32::from)
+    }
+
+    #[test]
+    fn test_next() -> Result<(), Box<dyn std::error::Error>> {
+        let records = vec![
+            build_record(
+                "r0",
+                Flags::READ_1 | Flags::PROPER_PAIR | Flags::MATE_UNMAPPED,
+                Some(8),
+                "4M",
+            )?,
+            build_record(
+                "r0",
+                Flags::READ_2 | Flags::REVERSE_COMPLEMENTED,
+                Some(13),
+                "2M1D2M",
+            )?,
+            build_record("r0", Flags::READ_1 | Flags::SUPPLEMENTARY, Some(100), "4M")?,
+            build_record("r1", Flags::READ_1, None, "")?,
+        ];
+
+        let actual: Vec<_> = FixMate::builder(records.into_iter().map(Ok))
+            .set_add_mate_cigar(true)
+            .set_add_mate_mapping_quality(true)
+            .build()
+            .collect::<io::Result<_>>()?;
+
+        assert_eq!(actual.len(), 4);
+
+        let flags = actual[0].flags();
+        assert!(flags.is_proper_pair());
+        assert!(!flags.is_mate_unmapped());
+        assert!(flags.is_mate_reverse_complemented());
+        assert_eq!(mate_position(&actual[0]), Some(13));
+        assert_eq!(actual[0].template_length(), 10);
+        assert_eq!(
+            actual[0].data().get(&Tag::MateCigar).transpose()?,
+            Some(ValueRef::String("2M1D2M"))
+        );
+        assert_eq!(
+            actual[0]
+                .data()
+                .get(&Tag::MateMappingQuality)
+                .transpose()?
+                .and_then(|value| value.as_int()),
+            Some(30)
+        );
+
+        assert!(!actual[1].flags().is_mate_reverse_complemented());
+        assert_eq!(mate_position(&actual[1]), Some(8));
+        assert_eq!(actual[1].template_length(), -10);
+        assert_eq!(
+            actual[1].data().get(&Tag::MateCigar).transpose()?,
+            Some(ValueRef::String("4M"))
+        );
+
+        assert_eq!(mate_position(&actual[2]), Some(13));
+        assert_eq!(actual[2].template_length(), 0);
+
+        assert!(actual[3].mate_position().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_unmapped_mate() -> Result<(), Box<dyn std::error::Error>> {
+        let records = vec![
+            build_record("r0", Flags::READ_1 | Flags::PROPER_PAIR, Some(8), "4M")?,
+            build_record("r0", Flags::READ_2 | Flags::UNMAPPED, None, "")?,
+        ];
+
+        let actual: Vec<_> = FixMate::builder(records.into_iter().map(Ok))
+            .set_add_mate_cigar(true)
+            .build()
+            .collect::<io::Result<_>>()?;
+
+        let flags = actual[0].flags();
+        assert!(!flags.is_proper_pair());
+        assert!(flags.is_mate_unmapped());
+        assert_eq!(mate_position(&actual[0]), Some(8));
+        assert_eq!(actual[0].template_length(), 0);
+        assert!(actual[0].data().get(&Tag::MateCigar).is_none());
+
+        assert_eq!(actual[1].position().map(i32::from), Some(8));
+        assert_eq!(mate_position(&actual[1]), Some(8));
+        assert!(actual[1].data().get(&Tag::MateCigar).is_some());
+
+        Ok(())
+    }
+}
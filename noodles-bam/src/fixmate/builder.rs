@@ -0,0 +1,88 @@
+use std::io;
+
+use super::FixMate;
+use crate::Record;
+
+/// A mate information fixer builder.
+pub struct Builder<I> {
+    records: I,
+    add_mate_cigar: bool,
+    add_mate_mapping_quality: bool,
+}
+
+impl<I> Builder<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    pub(crate) fn new(records: I) -> Self {
+        Self {
+            records,
+            add_mate_cigar: false,
+            add_mate_mapping_quality: false,
+        }
+    }
+
+    /// Sets whether to add the mate CIGAR (`MC`) data field.
+    ///
+    /// When enabled, the data field is set to the CIGAR of the mate if the mate is mapped and
+    /// removed otherwise.
+    ///
+    /// By default, this is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::fixmate::FixMate;
+    ///
+    /// let records = FixMate::builder(iter::empty())
+    ///     .set_add_mate_cigar(true)
+    ///     .build();
+    /// ```
+    pub fn set_add_mate_cigar(mut self, add_mate_cigar: bool) -> Self {
+        self.add_mate_cigar = add_mate_cigar;
+        self
+    }
+
+    /// Sets whether to add the mate mapping quality (`MQ`) data field.
+    ///
+    /// When enabled, the data field is set to the mapping quality of the mate if the mate is
+    /// mapped and removed otherwise.
+    ///
+    /// By default, this is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::fixmate::FixMate;
+    ///
+    /// let records = FixMate::builder(iter::empty())
+    ///     .set_add_mate_mapping_quality(true)
+    ///     .build();
+    /// ```
+    pub fn set_add_mate_mapping_quality(mut self, add_mate_mapping_quality: bool) -> Self {
+        self.add_mate_mapping_quality = add_mate_mapping_quality;
+        self
+    }
+
+    /// Builds a mate information fixer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::fixmate::FixMate;
+    ///
+    /// let records = FixMate::builder(iter::empty()).build();
+    /// ```
+    pub fn build(self) -> FixMate<I> {
+        FixMate {
+            records: self.records,
+            add_mate_cigar: self.add_mate_cigar,
+            add_mate_mapping_quality: self.add_mate_mapping_quality,
+            buf: Default::default(),
+            next_record: None,
+        }
+    }
+}
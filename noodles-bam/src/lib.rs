@@ -50,9 +50,18 @@
 //! ```
 
 pub mod bai;
+pub mod calmd;
+pub mod fastq;
+pub mod fixmate;
+pub mod markdup;
+pub mod merge;
+pub mod pileup;
 pub mod reader;
 pub mod record;
-mod writer;
+pub mod sort;
+pub mod stats;
+pub mod validator;
+pub mod writer;
 
 pub use self::{reader::Reader, record::Record, writer::Writer};
 
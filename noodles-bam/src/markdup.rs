@@ -0,0 +1,1168 @@
+//! BAM duplicate marking.
+//!
+//! Duplicates are reads that originate from the same DNA fragment, e.g., by PCR amplification.
+//! They are identified by their library (`@RG LB`), unclipped 5' position, and orientation. For
+//! reads in mapped pairs, the unclipped 5' position and orientation of the mate are also used. The
+//! position of the mate is computed from its CIGAR in the mate CIGAR (`MC`) data field, if
+//! present, or is otherwise approximated by the mate position.
+//!
+//! Within a set of duplicates, the read or pair with the highest sum of base quality scores >= 15
+//! is kept as the representative, and the rest are marked as duplicates. If present, the mate
+//! score (`ms`) data field is added to the score of a pair. Unpaired reads that share a 5'
+//! position with a read in a mapped pair are always marked as duplicates. Duplicates that are
+//! near their representative on the same flowcell tile are counted as optical duplicates.
+//!
+//! Records are processed as a stream and must be coordinate-sorted. A read is held in memory until
+//! no later read can share its 5' position. A read on the reverse strand can only join a 5'
+//! position at or after its start, and a read on the forward strand can only join a 5' position
+//! up to its leading clip length before its start. The leading clip length is limited to a fixed
+//! maximum (see [`Builder::set_max_clip_len`]), so only a window of that length behind the
+//! current position is buffered. A pair is final once the stream is past the window of either of
+//! its ends, so the distance to a mate does not increase the window. The read names of pairs
+//! whose mates have not yet been seen are also held in memory.
+
+mod builder;
+mod metrics;
+
+pub use self::{builder::Builder, metrics::Metrics};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::TryFrom,
+    io, str,
+};
+
+use noodles_sam::{
+    self as sam,
+    record::{cigar::op::Kind, data::field::Tag, Flags},
+};
+
+use crate::{
+    record::data::{
+        field::{Value, ValueRef},
+        Field,
+    },
+    Record,
+};
+
+const DEFAULT_OPTICAL_DUPLICATE_PIXEL_DISTANCE: u32 = 100;
+const DEFAULT_MAX_CLIP_LEN: u32 = 1000;
+const MIN_SCORE_QUALITY: u8 = 15;
+const MISSING_QUALITY_SCORE: u8 = 0xff;
+const SWEEP_INTERVAL: u64 = 4096;
+
+/// An iterator that marks duplicates in coordinate-sorted records.
+///
+/// The `DUPLICATE` flag is set or cleared on primary mapped reads. Unmapped, secondary, and
+/// supplementary reads are passed through unchanged.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bam::{self as bam, markdup::MarkDuplicates};
+/// use noodles_sam as sam;
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// let header: sam::Header = reader
+///     .read_header()?
+///     .parse()
+///     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+/// reader.read_reference_sequences()?;
+///
+/// let mut writer = File::create("sample.markdup.bam").map(bam::Writer::new)?;
+/// writer.write_header(&header)?;
+/// writer.write_reference_sequences(header.reference_sequences())?;
+///
+/// let mut records = MarkDuplicates::new(reader.records(), &header);
+///
+/// for result in &mut records {
+///     let record = result?;
+///     writer.write_record(&record)?;
+/// }
+///
+/// eprintln!("{:?}", records.metrics());
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct MarkDuplicates<I> {
+    records: I,
+    libraries: HashMap<String, usize>,
+    remove_duplicates: bool,
+    tag_duplicate_sets: bool,
+    optical_duplicate_pixel_distance: u32,
+    max_clip_len: u32,
+    state: State,
+}
+
+impl<I> MarkDuplicates<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    /// Creates a duplicate marker with default options.
+    ///
+    /// The header is used to look up the library of each read group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::markdup::MarkDuplicates;
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    /// let records = MarkDuplicates::new(iter::empty(), &header);
+    /// ```
+    pub fn new(records: I, header: &sam::Header) -> Self {
+        Builder::new(records, header).build()
+    }
+
+    /// Creates a duplicate marker builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::markdup::MarkDuplicates;
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    /// let builder = MarkDuplicates::builder(iter::empty(), &header);
+    /// ```
+    pub fn builder(records: I, header: &sam::Header) -> Builder<I> {
+        Builder::new(records, header)
+    }
+
+    /// Returns the metrics of the records processed so far.
+    ///
+    /// These are only complete once the iterator is exhausted.
+    pub fn metrics(&self) -> &Metrics {
+        &self.state.metrics
+    }
+
+    fn push(&mut self, record: Record) -> io::Result<()> {
+        let library_id = self.library_id(&record)?;
+
+        // Negative mate scores are clamped to 0.
+        let mate_score = match record.data().get(&self.state.mate_score_tag) {
+            Some(result) => result?
+                .as_int()
+                .map(|n| n.clamp(0, i64::from(u32::MAX)) as u32)
+                .unwrap_or(0),
+            None => 0,
+        };
+
+        let max_clip_len = self.max_clip_len;
+
+        let state = &mut self.state;
+
+        let horizon = match (record.reference_sequence_id(), record.position()) {
+            (Some(id), Some(position)) => (i32::from(id) as usize, i32::from(position)),
+            _ => (usize::MAX, i32::MAX),
+        };
+
+        if let Some(last_horizon) = state.horizon {
+            if horizon < last_horizon {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "records must be coordinate-sorted",
+                ));
+            }
+        }
+
+        state.horizon = Some(horizon);
+
+        let id = state.first_entry_id + state.entries.len() as u64;
+        let flags = record.flags();
+
+        let role = if flags.is_secondary()
+            || flags.is_supplementary()
+            || flags.is_unmapped()
+            || horizon.0 == usize::MAX
+        {
+            Role::Ignored
+        } else {
+            let end = own_end(&record, max_clip_len)?;
+
+            let score = score(&record);
+            let location = record
+                .read_name()
+                .ok()
+                .and_then(|name| parse_location(name.to_bytes()));
+
+            let candidate = Candidate {
+                id,
+                score,
+                location,
+                pixel_distance: self.optical_duplicate_pixel_distance,
+            };
+
+            match mate_end(&record)? {
+                Some(mate_end) => {
+                    let fragment_group = state
+                        .fragment_groups
+                        .entry((library_id, end))
+                        .or_insert_with(|| Group::new(end.bound(max_clip_len)));
+                    fragment_group.add_pair(
+                        &mut state.entries,
+                        state.first_entry_id,
+                        &mut state.next_set_id,
+                    );
+
+                    let name = record
+                        .read_name()
+                        .map(|name| name.to_bytes().to_vec())
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+                    if state.mates.contains_key(&name) {
+                        Role::Right { name }
+                    } else {
+                        state.mates.insert(name.clone(), None);
+
+                        let (a, b) = if end <= mate_end {
+                            (end, mate_end)
+                        } else {
+                            (mate_end, end)
+                        };
+
+                        // Both reads of a pair that has not yet been seen start after the
+                        // current position, so the group is complete once the stream is past the
+                        // bound of either end.
+                        let bound = a.bound(max_clip_len).min(b.bound(max_clip_len));
+
+                        let key = (library_id, a, b);
+                        let group = state
+                            .pair_groups
+                            .entry(key)
+                            .or_insert_with(|| Group::new(bound));
+
+                        let candidate = Candidate {
+                            score: score.saturating_add(mate_score),
+                            ..candidate
+                        };
+
+                        let decision = group.add(
+                            &mut state.entries,
+                            state.first_entry_id,
+                            &candidate,
+                            &mut state.next_set_id,
+                        );
+
+                        state.entries.push_back(Entry::new(
+                            record,
+                            Role::Left { key, name },
+                            decision,
+                        ));
+
+                        return self.finish_push();
+                    }
+                }
+                None => {
+                    let key = (library_id, end);
+                    let group = state
+                        .fragment_groups
+                        .entry(key)
+                        .or_insert_with(|| Group::new(end.bound(max_clip_len)));
+
+                    let decision = group.add(
+                        &mut state.entries,
+                        state.first_entry_id,
+                        &candidate,
+                        &mut state.next_set_id,
+                    );
+
+                    state
+                        .entries
+                        .push_back(Entry::new(record, Role::Fragment(key), decision));
+
+                    return self.finish_push();
+                }
+            }
+        };
+
+        state
+            .entries
+            .push_back(Entry::new(record, role, Decision::default()));
+
+        self.finish_push()
+    }
+
+    fn finish_push(&mut self) -> io::Result<()> {
+        let state = &mut self.state;
+
+        state.record_count += 1;
+
+        if state.record_count.is_multiple_of(SWEEP_INTERVAL) {
+            state.sweep();
+        }
+
+        Ok(())
+    }
+
+    fn library_id(&self, record: &Record) -> io::Result<usize> {
+        match record.data().get(&Tag::ReadGroup).transpose()? {
+            Some(ValueRef::String(id)) => Ok(self.libraries.get(id).copied().unwrap_or(0)),
+            _ => Ok(0),
+        }
+    }
+
+    fn is_front_ready(&self) -> bool {
+        let state = &self.state;
+
+        match state.entries.front().map(|entry| &entry.role) {
+            Some(Role::Fragment(key)) => state.is_complete(state.fragment_groups.get(key)),
+            Some(Role::Left { key, .. }) => state.is_complete(state.pair_groups.get(key)),
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    // Removes the front entry and applies its final duplicate status. This returns `None` if the
+    // record is removed.
+    fn pop_front(&mut self) -> io::Result<Option<Record>> {
+        let state = &mut self.state;
+
+        let entry = match state.entries.pop_front() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        state.first_entry_id += 1;
+
+        let Entry {
+            mut record,
+            role,
+            decision,
+        } = entry;
+
+        let (is_duplicate, set_id) = match role {
+            Role::Ignored => return Ok(Some(record)),
+            Role::Fragment(key) => {
+                let set_id = state
+                    .fragment_groups
+                    .get_mut(&key)
+                    .and_then(|group| group.release());
+
+                state.metrics.unpaired_reads_examined += 1;
+
+                if decision.is_duplicate {
+                    state.metrics.unpaired_read_duplicates += 1;
+                }
+
+                (decision.is_duplicate, set_id)
+            }
+            Role::Left { key, name } => {
+                let set_id = state
+                    .pair_groups
+                    .get_mut(&key)
+                    .and_then(|group| group.release());
+
+                state.metrics.read_pairs_examined += 1;
+
+                if decision.is_duplicate {
+                    state.metrics.read_pair_duplicates += 1;
+
+                    if decision.is_optical {
+                        state.metrics.read_pair_optical_duplicates += 1;
+                    }
+                }
+
+                state.mates.insert(
+                    name,
+                    Some(MateDecision {
+                        is_duplicate: decision.is_duplicate,
+                        set_id,
+                    }),
+                );
+
+                (decision.is_duplicate, set_id)
+            }
+            Role::Right { name } => match state.mates.remove(&name).flatten() {
+                Some(mate_decision) => (mate_decision.is_duplicate, mate_decision.set_id),
+                None => (false, None),
+            },
+        };
+
+        if is_duplicate && self.remove_duplicates {
+            return Ok(None);
+        }
+
+        let mut flags = record.flags();
+        flags.set(Flags::DUPLICATE, is_duplicate);
+        record.set_flags(flags);
+
+        if self.tag_duplicate_sets {
+            if let Some(set_id) = set_id {
+                let field = Field::new(self.state.duplicate_set_tag.clone(), Value::Int32(set_id));
+                record.insert_data_field(field)?;
+            }
+        }
+
+        Ok(Some(record))
+    }
+}
+
+impl<I> Iterator for MarkDuplicates<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.is_front_ready() {
+                match self.pop_front() {
+                    Ok(Some(record)) => return Some(Ok(record)),
+                    Ok(None) => continue,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+
+            if self.state.is_eof {
+                return None;
+            }
+
+            match self.records.next() {
+                Some(Ok(record)) => {
+                    if let Err(e) = self.push(record) {
+                        return Some(Err(e));
+                    }
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => self.state.is_eof = true,
+            }
+        }
+    }
+}
+
+struct State {
+    entries: VecDeque<Entry>,
+    first_entry_id: u64,
+    fragment_groups: HashMap<FragmentKey, Group>,
+    pair_groups: HashMap<PairKey, Group>,
+    mates: HashMap<Vec<u8>, Option<MateDecision>>,
+    next_set_id: i32,
+    horizon: Option<(usize, i32)>,
+    is_eof: bool,
+    record_count: u64,
+    metrics: Metrics,
+    mate_score_tag: Tag,
+    duplicate_set_tag: Tag,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            first_entry_id: 0,
+            fragment_groups: HashMap::new(),
+            pair_groups: HashMap::new(),
+            mates: HashMap::new(),
+            next_set_id: 0,
+            horizon: None,
+            is_eof: false,
+            record_count: 0,
+            metrics: Metrics::default(),
+            mate_score_tag: Tag::Other(String::from("ms")),
+            duplicate_set_tag: Tag::Other(String::from("DI")),
+        }
+    }
+}
+
+impl State {
+    // A group is complete when no more reads can join it, i.e., when the stream is past its
+    // bound.
+    fn is_complete(&self, group: Option<&Group>) -> bool {
+        match (group, self.horizon) {
+            (Some(group), Some(horizon)) if !self.is_eof => group.bound < horizon,
+            _ => true,
+        }
+    }
+
+    // Removes complete groups that have no buffered reads.
+    fn sweep(&mut self) {
+        let horizon = match self.horizon {
+            Some(horizon) => horizon,
+            None => return,
+        };
+
+        let is_stale = |group: &Group| group.pending == 0 && group.bound < horizon;
+
+        self.fragment_groups.retain(|_, group| !is_stale(group));
+        self.pair_groups.retain(|_, group| !is_stale(group));
+    }
+}
+
+// The unclipped 5' end of a read.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct End {
+    reference_sequence_id: usize,
+    position: i32,
+    is_reverse_complemented: bool,
+}
+
+impl End {
+    // Returns the farthest start position of a read with this 5' end.
+    //
+    // The 5' end of a read on the reverse strand is at or after its start. The 5' end of a read on
+    // the forward strand is its start minus its leading clip length.
+    fn bound(&self, max_clip_len: u32) -> (usize, i32) {
+        let position = if self.is_reverse_complemented {
+            self.position
+        } else {
+            let max_clip_len = i32::try_from(max_clip_len).unwrap_or(i32::MAX);
+            self.position.saturating_add(max_clip_len)
+        };
+
+        (self.reference_sequence_id, position)
+    }
+}
+
+// (library ID, end)
+type FragmentKey = (usize, End);
+
+// (library ID, lesser end, greater end)
+type PairKey = (usize, End, End);
+
+enum Role {
+    Ignored,
+    Fragment(FragmentKey),
+    // The first read of a mapped pair in the stream. It decides the status of the pair.
+    Left { key: PairKey, name: Vec<u8> },
+    // The second read of a mapped pair in the stream. It takes the status of its mate.
+    Right { name: Vec<u8> },
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Decision {
+    is_duplicate: bool,
+    is_optical: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct MateDecision {
+    is_duplicate: bool,
+    set_id: Option<i32>,
+}
+
+type Location = (u32, u32, u32);
+
+#[derive(Clone, Copy, Debug)]
+struct Candidate {
+    id: u64,
+    score: u32,
+    location: Option<Location>,
+    pixel_distance: u32,
+}
+
+impl Candidate {
+    fn is_optical_duplicate_of(&self, other: &Candidate) -> bool {
+        match (self.location, other.location) {
+            (Some((a_tile, a_x, a_y)), Some((b_tile, b_x, b_y))) => {
+                a_tile == b_tile
+                    && a_x.abs_diff(b_x) <= self.pixel_distance
+                    && a_y.abs_diff(b_y) <= self.pixel_distance
+            }
+            _ => false,
+        }
+    }
+}
+
+struct Entry {
+    record: Record,
+    role: Role,
+    decision: Decision,
+}
+
+impl Entry {
+    fn new(record: Record, role: Role, decision: Decision) -> Self {
+        Self {
+            record,
+            role,
+            decision,
+        }
+    }
+}
+
+// A set of reads with the same key.
+struct Group {
+    // The farthest (reference sequence ID, position) a read that joins the group can start.
+    bound: (usize, i32),
+    best: Option<Candidate>,
+    has_pair: bool,
+    size: u32,
+    pending: u32,
+    set_id: Option<i32>,
+}
+
+impl Group {
+    fn new(bound: (usize, i32)) -> Self {
+        Self {
+            bound,
+            best: None,
+            has_pair: false,
+            size: 0,
+            pending: 0,
+            set_id: None,
+        }
+    }
+
+    // Adds a read to the group and returns its duplicate status. If the read replaces the
+    // current representative, the previous representative is marked as a duplicate.
+    fn add(
+        &mut self,
+        entries: &mut VecDeque<Entry>,
+        first_entry_id: u64,
+        candidate: &Candidate,
+        next_set_id: &mut i32,
+    ) -> Decision {
+        self.size += 1;
+        self.pending += 1;
+
+        if self.size > 1 || self.has_pair {
+            self.assign_set_id(next_set_id);
+        }
+
+        if self.has_pair {
+            return Decision {
+                is_duplicate: true,
+                is_optical: false,
+            };
+        }
+
+        match self.best {
+            Some(best) if candidate.score > best.score => {
+                let decision = Decision {
+                    is_duplicate: true,
+                    is_optical: best.is_optical_duplicate_of(candidate),
+                };
+
+                mark(entries, first_entry_id, best.id, decision);
+                self.best = Some(*candidate);
+
+                Decision::default()
+            }
+            Some(best) => Decision {
+                is_duplicate: true,
+                is_optical: candidate.is_optical_duplicate_of(&best),
+            },
+            None => {
+                self.best = Some(*candidate);
+                Decision::default()
+            }
+        }
+    }
+
+    // Marks the group as containing a read of a mapped pair. Unpaired reads in the group are
+    // then all duplicates.
+    fn add_pair(
+        &mut self,
+        entries: &mut VecDeque<Entry>,
+        first_entry_id: u64,
+        next_set_id: &mut i32,
+    ) {
+        self.has_pair = true;
+
+        if let Some(best) = self.best.take() {
+            let decision = Decision {
+                is_duplicate: true,
+                is_optical: false,
+            };
+
+            mark(entries, first_entry_id, best.id, decision);
+            self.assign_set_id(next_set_id);
+        }
+    }
+
+    // Assigns a duplicate set ID to the group, if it does not have one. This is done once any
+    // read in the group is a duplicate.
+    fn assign_set_id(&mut self, next_set_id: &mut i32) {
+        if self.set_id.is_none() {
+            self.set_id = Some(*next_set_id);
+            *next_set_id += 1;
+        }
+    }
+
+    // Releases a buffered read and returns the duplicate set ID, if the group has duplicates.
+    fn release(&mut self) -> Option<i32> {
+        self.pending = self.pending.saturating_sub(1);
+        self.set_id
+    }
+}
+
+fn mark(entries: &mut VecDeque<Entry>, first_entry_id: u64, id: u64, decision: Decision) {
+    if let Some(i) = id.checked_sub(first_entry_id) {
+        if let Some(entry) = entries.get_mut(i as usize) {
+            entry.decision = decision;
+        }
+    }
+}
+
+// Returns the unclipped 5' end of a read.
+//
+// This returns an error if the read is on the forward strand and its leading clip is longer than
+// `max_clip_len`.
+fn own_end(record: &Record, max_clip_len: u32) -> io::Result<End> {
+    let reference_sequence_id = record
+        .reference_sequence_id()
+        .map(|id| i32::from(id) as usize)
+        .expect("missing reference sequence ID");
+    let start = record.position().map(i32::from).expect("missing position");
+    let is_reverse_complemented = record.flags().is_reverse_complemented();
+
+    let ops = record
+        .cigar()
+        .ops()
+        .map(|result| result.map(|op| (op.kind(), op.len())))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let (position, span) = unclipped_position(start, is_reverse_complemented, &ops);
+
+    if !is_reverse_complemented && span as u32 > max_clip_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "leading clip length ({}) exceeds the maximum ({})",
+                span, max_clip_len
+            ),
+        ));
+    }
+
+    Ok(End {
+        reference_sequence_id,
+        position,
+        is_reverse_complemented,
+    })
+}
+
+// Returns the unclipped 5' end of the mate, if the read is in a mapped pair.
+fn mate_end(record: &Record) -> io::Result<Option<End>> {
+    let flags = record.flags();
+
+    if !flags.is_paired() || flags.is_mate_unmapped() {
+        return Ok(None);
+    }
+
+    let (reference_sequence_id, start) =
+        match (record.mate_reference_sequence_id(), record.mate_position()) {
+            (Some(id), Some(position)) => (i32::from(id) as usize, i32::from(position)),
+            _ => return Ok(None),
+        };
+
+    let is_reverse_complemented = flags.is_mate_reverse_complemented();
+
+    let position = match record.data().get(&Tag::MateCigar).transpose()? {
+        Some(ValueRef::String(s)) => {
+            let cigar: sam::record::Cigar = s
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            let ops: Vec<_> = cigar.iter().map(|op| (op.kind(), op.len())).collect();
+            let (position, _) = unclipped_position(start, is_reverse_complemented, &ops);
+
+            position
+        }
+        _ => start,
+    };
+
+    Ok(Some(End {
+        reference_sequence_id,
+        position,
+        is_reverse_complemented,
+    }))
+}
+
+// Returns the unclipped 5' position and the distance between the start and the 5' position.
+fn unclipped_position(
+    start: i32,
+    is_reverse_complemented: bool,
+    ops: &[(Kind, u32)],
+) -> (i32, i32) {
+    let is_clip = |kind: Kind| matches!(kind, Kind::SoftClip | Kind::HardClip);
+
+    let leading_clip_len: u32 = ops
+        .iter()
+        .take_while(|(kind, _)| is_clip(*kind))
+        .map(|(_, len)| len)
+        .sum();
+
+    let trailing_clip_len: u32 = ops
+        .iter()
+        .rev()
+        .take_while(|(kind, _)| is_clip(*kind))
+        .map(|(_, len)| len)
+        .sum();
+
+    let reference_len: u32 = ops
+        .iter()
+        .filter(|(kind, _)| {
+            matches!(
+                kind,
+                Kind::Match | Kind::Deletion | Kind::Skip | Kind::SeqMatch | Kind::SeqMismatch
+            )
+        })
+        .map(|(_, len)| len)
+        .sum();
+
+    if is_reverse_complemented {
+        let span = (reference_len.max(1) - 1 + trailing_clip_len) as i32;
+        (start + span, span)
+    } else {
+        let span = leading_clip_len as i32;
+        (start - span, span)
+    }
+}
+
+fn score(record: &Record) -> u32 {
+    record
+        .quality_scores()
+        .iter()
+        .filter(|&&score| score >= MIN_SCORE_QUALITY && score != MISSING_QUALITY_SCORE)
+        .map(|&score| u32::from(score))
+        .sum()
+}
+
+// Parses the tile and x and y coordinates from the last three colon-delimited fields of an
+// Illumina-style read name.
+fn parse_location(read_name: &[u8]) -> Option<Location> {
+    let mut fields = read_name.rsplit(|&b| b == b':');
+
+    let mut parse = || {
+        fields
+            .next()
+            .and_then(|field| str::from_utf8(field).ok())
+            .and_then(|s| s.parse().ok())
+    };
+
+    let y = parse()?;
+    let x = parse()?;
+    let tile = parse()?;
+
+    Some((tile, x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use noodles_sam::record::Position;
+
+    use crate::record::ReferenceSequenceId;
+
+    use super::*;
+
+    fn build_record(
+        read_name: &str,
+        flags: Flags,
+        position: i32,
+        cigar: &str,
+        quality_scores: &str,
+    ) -> Result<Record, Box<dyn std::error::Error>> {
+        let mut record = Record::default();
+        record.set_read_name(&CString::new(read_name)?)?;
+        record.set_flags(flags);
+        record.set_reference_sequence_id(Some(ReferenceSequenceId::try_from(0)?));
+        record.set_position(Some(Position::try_from(position)?))?;
+        record.set_cigar(&cigar.parse()?)?;
+        record.set_sequence(&"A".repeat(quality_scores.len()).parse()?)?;
+        record.set_quality_scores(&quality_scores.parse()?)?;
+        Ok(record)
+    }
+
+    fn build_pair(
+        read_name: &str,
+        flags: Flags,
+        position: i32,
+        mate_position: i32,
+        quality_scores: &str,
+    ) -> Result<Record, Box<dyn std::error::Error>> {
+        let mut record = build_record(
+            read_name,
+            flags | Flags::PAIRED,
+            position,
+            "4M",
+            quality_scores,
+        )?;
+        record.set_mate_reference_sequence_id(Some(ReferenceSequenceId::try_from(0)?));
+        record.set_mate_position(Some(Position::try_from(mate_position)?));
+        Ok(record)
+    }
+
+    fn is_duplicate(records: &[Record]) -> Vec<bool> {
+        records
+            .iter()
+            .map(|record| record.flags().is_duplicate())
+            .collect()
+    }
+
+    #[test]
+    fn test_next_with_fragments() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+
+        let records = vec![
+            build_record("r0", Flags::DUPLICATE, 10, "4M", "IIII")?,
+            // The unclipped 5' position of r1 is 10.
+            build_record("r1", Flags::empty(), 12, "2S4M", "IIIIII")?,
+            build_record("r2", Flags::REVERSE_COMPLEMENTED, 12, "4M", "IIII")?,
+            build_record("r3", Flags::empty(), 20, "4M", "IIII")?,
+            build_record("r4", Flags::UNMAPPED, 20, "4M", "IIII")?,
+        ];
+
+        let mut markdup = MarkDuplicates::new(records.into_iter().map(Ok), &header);
+        let actual: Vec<_> = (&mut markdup).collect::<io::Result<_>>()?;
+
+        assert_eq!(is_duplicate(&actual), [true, false, false, false, false]);
+
+        let metrics = markdup.metrics();
+        assert_eq!(metrics.unpaired_reads_examined(), 4);
+        assert_eq!(metrics.unpaired_read_duplicates(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_pairs() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+
+        let records = vec![
+            build_pair("a", Flags::MATE_REVERSE_COMPLEMENTED, 10, 50, "IIII")?,
+            build_pair("b", Flags::MATE_REVERSE_COMPLEMENTED, 10, 50, "5555")?,
+            build_record("c", Flags::empty(), 10, "4M", "IIII")?,
+            build_pair("a", Flags::REVERSE_COMPLEMENTED, 50, 10, "IIII")?,
+            build_pair("b", Flags::REVERSE_COMPLEMENTED, 50, 10, "5555")?,
+        ];
+
+        let mut markdup = MarkDuplicates::new(records.into_iter().map(Ok), &header);
+        let actual: Vec<_> = (&mut markdup).collect::<io::Result<_>>()?;
+
+        assert_eq!(is_duplicate(&actual), [false, true, true, false, true]);
+
+        let metrics = markdup.metrics();
+        assert_eq!(metrics.read_pairs_examined(), 2);
+        assert_eq!(metrics.read_pair_duplicates(), 1);
+        assert_eq!(metrics.unpaired_reads_examined(), 1);
+        assert_eq!(metrics.unpaired_read_duplicates(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_remove_duplicates_and_duplicate_sets(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+
+        let records = vec![
+            build_record("r0", Flags::empty(), 10, "4M", "5555")?,
+            build_record("r1", Flags::empty(), 10, "4M", "IIII")?,
+            build_record("r2", Flags::empty(), 20, "4M", "IIII")?,
+        ];
+
+        let actual: Vec<_> = MarkDuplicates::builder(records.into_iter().map(Ok), &header)
+            .set_remove_duplicates(true)
+            .set_tag_duplicate_sets(true)
+            .build()
+            .collect::<io::Result<_>>()?;
+
+        assert_eq!(actual.len(), 2);
+
+        let tag = Tag::Other(String::from("DI"));
+
+        assert_eq!(actual[0].read_name()?.to_bytes(), b"r1");
+        assert_eq!(
+            actual[0]
+                .data()
+                .get(&tag)
+                .transpose()?
+                .and_then(|v| v.as_int()),
+            Some(0)
+        );
+
+        assert_eq!(actual[1].read_name()?.to_bytes(), b"r2");
+        assert!(actual[1].data().get(&tag).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_duplicate_sets_and_fragment_covered_by_pair(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+        let tag = Tag::Other(String::from("DI"));
+
+        let fragment = build_record("f", Flags::empty(), 10, "4M", "IIII")?;
+        let pair = [
+            build_pair("p", Flags::MATE_REVERSE_COMPLEMENTED, 10, 50, "IIII")?,
+            build_pair("p", Flags::REVERSE_COMPLEMENTED, 50, 10, "IIII")?,
+        ];
+
+        let inputs = [
+            vec![fragment.clone(), pair[0].clone(), pair[1].clone()],
+            vec![pair[0].clone(), fragment, pair[1].clone()],
+        ];
+
+        for records in inputs {
+            let actual: Vec<_> = MarkDuplicates::builder(records.into_iter().map(Ok), &header)
+                .set_tag_duplicate_sets(true)
+                .build()
+                .collect::<io::Result<_>>()?;
+
+            let fragment = actual
+                .iter()
+                .find(|record| {
+                    record
+                        .read_name()
+                        .map(|name| name.to_bytes() == b"f")
+                        .unwrap_or(false)
+                })
+                .expect("missing fragment");
+
+            assert!(fragment.flags().is_duplicate());
+            assert_eq!(
+                fragment
+                    .data()
+                    .get(&tag)
+                    .transpose()?
+                    .and_then(|v| v.as_int()),
+                Some(0)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_unsorted_records() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+
+        let records = vec![
+            build_record("r0", Flags::empty(), 20, "4M", "IIII")?,
+            build_record("r1", Flags::empty(), 10, "4M", "IIII")?,
+        ];
+
+        let result: io::Result<Vec<_>> =
+            MarkDuplicates::new(records.into_iter().map(Ok), &header).collect();
+
+        assert!(matches!(result, Err(e) if e.kind() == io::ErrorKind::InvalidData));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_late_long_clip() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+
+        // The unclipped 5' position of r2 is 10, which is the same as r0's, even though it is
+        // only seen after the stream moved past r0 and r1.
+        let records = vec![
+            build_record("r0", Flags::empty(), 10, "4M", "5555")?,
+            build_record("r1", Flags::empty(), 20, "4M", "IIII")?,
+            build_record("r2", Flags::empty(), 60, "50S4M", &"I".repeat(54))?,
+        ];
+
+        let actual: Vec<_> = MarkDuplicates::builder(records.into_iter().map(Ok), &header)
+            .set_max_clip_len(50)
+            .build()
+            .collect::<io::Result<_>>()?;
+
+        assert_eq!(is_duplicate(&actual), [true, false, false]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_clip_longer_than_max_clip_len() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+
+        let records = vec![build_record(
+            "r0",
+            Flags::empty(),
+            60,
+            "8S4M",
+            "IIIIIIIIIIII",
+        )?];
+
+        let result: io::Result<Vec<_>> =
+            MarkDuplicates::builder(records.into_iter().map(Ok), &header)
+                .set_max_clip_len(4)
+                .build()
+                .collect();
+
+        assert!(matches!(result, Err(e) if e.kind() == io::ErrorKind::InvalidData));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_distant_mate() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+
+        // The stream ends with an error, so the pair must be emitted before its mate is read.
+        let records = vec![
+            Ok(build_pair(
+                "a",
+                Flags::MATE_REVERSE_COMPLEMENTED,
+                10,
+                1_000_000,
+                "IIII",
+            )?),
+            Ok(build_record("r0", Flags::empty(), 5000, "4M", "IIII")?),
+            Err(io::Error::new(io::ErrorKind::InvalidData, "truncated")),
+        ];
+
+        let mut markdup = MarkDuplicates::builder(records.into_iter(), &header)
+            .set_max_clip_len(100)
+            .build();
+
+        let record = markdup.next().transpose()?;
+        assert_eq!(
+            record
+                .as_ref()
+                .map(|record| record.read_name())
+                .transpose()?,
+            Some(CString::new("a")?.as_c_str())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_negative_mate_score() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+        let mate_score_tag = Tag::Other(String::from("ms"));
+
+        let mut a = build_pair("a", Flags::MATE_REVERSE_COMPLEMENTED, 10, 50, "5555")?;
+        a.insert_data_field(Field::new(mate_score_tag.clone(), Value::Int32(-5)))?;
+
+        let mut b = build_pair("b", Flags::MATE_REVERSE_COMPLEMENTED, 10, 50, "IIII")?;
+        b.insert_data_field(Field::new(mate_score_tag, Value::Int32(0)))?;
+
+        let records = vec![
+            a,
+            b,
+            build_pair("a", Flags::REVERSE_COMPLEMENTED, 50, 10, "5555")?,
+            build_pair("b", Flags::REVERSE_COMPLEMENTED, 50, 10, "IIII")?,
+        ];
+
+        let actual: Vec<_> =
+            MarkDuplicates::new(records.into_iter().map(Ok), &header).collect::<io::Result<_>>()?;
+
+        assert_eq!(is_duplicate(&actual), [true, false, true, false]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unclipped_position() {
+        let ops = [(Kind::SoftClip, 2), (Kind::Match, 4), (Kind::HardClip, 3)];
+        assert_eq!(unclipped_position(10, false, &ops), (8, 2));
+        assert_eq!(unclipped_position(10, true, &ops), (16, 6));
+    }
+
+    #[test]
+    fn test_parse_location() {
+        assert_eq!(
+            parse_location(b"M1:7:FC:1:1101:2048:1024"),
+            Some((1101, 2048, 1024))
+        );
+        assert_eq!(parse_location(b"r0"), None);
+    }
+}
@@ -0,0 +1,165 @@
+use std::{collections::HashMap, io};
+
+use noodles_sam as sam;
+
+use super::{MarkDuplicates, DEFAULT_MAX_CLIP_LEN, DEFAULT_OPTICAL_DUPLICATE_PIXEL_DISTANCE};
+use crate::Record;
+
+/// A duplicate marker builder.
+pub struct Builder<I> {
+    records: I,
+    libraries: HashMap<String, usize>,
+    remove_duplicates: bool,
+    tag_duplicate_sets: bool,
+    optical_duplicate_pixel_distance: u32,
+    max_clip_len: u32,
+}
+
+impl<I> Builder<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    pub(crate) fn new(records: I, header: &sam::Header) -> Self {
+        let mut library_ids = HashMap::new();
+        let mut libraries = HashMap::new();
+
+        // Library 0 is reserved for reads with no known library.
+        for (id, read_group) in header.read_groups() {
+            if let Some(library) = read_group.library() {
+                let next_id = library_ids.len() + 1;
+                let library_id = *library_ids.entry(library).or_insert(next_id);
+                libraries.insert(id.clone(), library_id);
+            }
+        }
+
+        Self {
+            records,
+            libraries,
+            remove_duplicates: false,
+            tag_duplicate_sets: false,
+            optical_duplicate_pixel_distance: DEFAULT_OPTICAL_DUPLICATE_PIXEL_DISTANCE,
+            max_clip_len: DEFAULT_MAX_CLIP_LEN,
+        }
+    }
+
+    /// Sets whether to remove duplicates from the output rather than marking them.
+    ///
+    /// By default, duplicates are marked and kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::markdup::MarkDuplicates;
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    /// let records = MarkDuplicates::builder(iter::empty(), &header)
+    ///     .set_remove_duplicates(true)
+    ///     .build();
+    /// ```
+    pub fn set_remove_duplicates(mut self, remove_duplicates: bool) -> Self {
+        self.remove_duplicates = remove_duplicates;
+        self
+    }
+
+    /// Sets whether to add a duplicate set index (`DI`) data field to reads in a duplicate set.
+    ///
+    /// Every read in a set of duplicates, including the representative read, is given the same
+    /// index.
+    ///
+    /// By default, this is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::markdup::MarkDuplicates;
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    /// let records = MarkDuplicates::builder(iter::empty(), &header)
+    ///     .set_tag_duplicate_sets(true)
+    ///     .build();
+    /// ```
+    pub fn set_tag_duplicate_sets(mut self, tag_duplicate_sets: bool) -> Self {
+        self.tag_duplicate_sets = tag_duplicate_sets;
+        self
+    }
+
+    /// Sets the maximum distance, in pixels, between two duplicate reads on the same tile for
+    /// them to be considered optical duplicates.
+    ///
+    /// The tile and coordinates are parsed from Illumina-style read names, i.e., the last three
+    /// colon-delimited fields of the read name.
+    ///
+    /// By default, this is 100.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::markdup::MarkDuplicates;
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    /// let records = MarkDuplicates::builder(iter::empty(), &header)
+    ///     .set_optical_duplicate_pixel_distance(2500)
+    ///     .build();
+    /// ```
+    pub fn set_optical_duplicate_pixel_distance(
+        mut self,
+        optical_duplicate_pixel_distance: u32,
+    ) -> Self {
+        self.optical_duplicate_pixel_distance = optical_duplicate_pixel_distance;
+        self
+    }
+
+    /// Sets the maximum length of the leading clip of a read on the forward strand.
+    ///
+    /// This bounds how far the unclipped 5' position of a read can be before its start, which
+    /// determines how many records are buffered. A read with a longer leading clip is an error.
+    ///
+    /// By default, this is 1000.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::markdup::MarkDuplicates;
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    /// let records = MarkDuplicates::builder(iter::empty(), &header)
+    ///     .set_max_clip_len(300)
+    ///     .build();
+    /// ```
+    pub fn set_max_clip_len(mut self, max_clip_len: u32) -> Self {
+        self.max_clip_len = max_clip_len;
+        self
+    }
+
+    /// Builds a duplicate marker.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::markdup::MarkDuplicates;
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    /// let records = MarkDuplicates::builder(iter::empty(), &header).build();
+    /// ```
+    pub fn build(self) -> MarkDuplicates<I> {
+        MarkDuplicates {
+            records: self.records,
+            libraries: self.libraries,
+            remove_duplicates: self.remove_duplicates,
+            tag_duplicate_sets: self.tag_duplicate_sets,
+            optical_duplicate_pixel_distance: self.optical_duplicate_pixel_distance,
+            max_clip_len: self.max_clip_len,
+            state: Default::default(),
+        }
+    }
+}
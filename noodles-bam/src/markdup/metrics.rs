@@ -0,0 +1,61 @@
+/// Duplicate marking metrics.
+///
+/// These are similar to the metrics reported by Picard `MarkDuplicates`. Read pairs are counted
+/// once per pair.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Metrics {
+    pub(crate) unpaired_reads_examined: u64,
+    pub(crate) read_pairs_examined: u64,
+    pub(crate) unpaired_read_duplicates: u64,
+    pub(crate) read_pair_duplicates: u64,
+    pub(crate) read_pair_optical_duplicates: u64,
+}
+
+impl Metrics {
+    /// Returns the number of mapped reads examined that are not part of a mapped pair.
+    pub fn unpaired_reads_examined(&self) -> u64 {
+        self.unpaired_reads_examined
+    }
+
+    /// Returns the number of mapped read pairs examined.
+    pub fn read_pairs_examined(&self) -> u64 {
+        self.read_pairs_examined
+    }
+
+    /// Returns the number of unpaired reads marked as duplicates.
+    pub fn unpaired_read_duplicates(&self) -> u64 {
+        self.unpaired_read_duplicates
+    }
+
+    /// Returns the number of read pairs marked as duplicates.
+    pub fn read_pair_duplicates(&self) -> u64 {
+        self.read_pair_duplicates
+    }
+
+    /// Returns the number of read pairs marked as duplicates that are also optical duplicates.
+    pub fn read_pair_optical_duplicates(&self) -> u64 {
+        self.read_pair_optical_duplicates
+    }
+
+    /// Returns the fraction of examined reads that are duplicates.
+    ///
+    /// This is `None` if no reads were examined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::markdup::Metrics;
+    /// assert!(Metrics::default().duplication_rate().is_none());
+    /// ```
+    pub fn duplication_rate(&self) -> Option<f64> {
+        let examined = self.unpaired_reads_examined + 2 * self.read_pairs_examined;
+
+        if examined == 0 {
+            return None;
+        }
+
+        let duplicates = self.unpaired_read_duplicates + 2 * self.read_pair_duplicates;
+
+        Some(duplicates as f64 / examined as f64)
+    }
+}
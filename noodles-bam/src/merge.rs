@@ -0,0 +1,744 @@
+//! BAM file merging.
+//!
+//! Merging combines multiple BAM files that are sorted in the same order into a single sorted BAM
+//! file. The SAM headers of the inputs are reconciled into one header:
+//!
+//!   * reference sequences (`@SQ`) are unioned by name, in order of first appearance. A reference
+//!     sequence that appears in more than one input must have the same length in each.
+//!   * read groups (`@RG`) and programs (`@PG`) that are identical across inputs are
+//!     deduplicated. Those with colliding IDs but different fields are renamed by appending a
+//!     numeric suffix, e.g., `rg0` becomes `rg0-1`. Programs are compared after their previous
+//!     program IDs (`PP`) are renamed, so a program whose parent was renamed is renamed too.
+//!   * comments (`@CO`) are unioned.
+//!
+//! Records are rewritten to match the merged header, i.e., their reference sequence IDs and
+//! mate reference sequence IDs are remapped, and their `RG` and `PG` data fields are renamed.
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    io::{self, Read, Write},
+};
+
+use indexmap::IndexMap;
+use noodles_sam::{self as sam, record::data::field::Tag};
+
+use crate::{
+    bai,
+    record::{
+        data::{
+            field::{Value, ValueRef},
+            Field,
+        },
+        ReferenceSequenceId,
+    },
+    sort::{self, RecordSource, SortOrder},
+    Reader, Record, Writer,
+};
+
+/// A BAM merger.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bam::{self as bam, merge::Merger, sort::SortOrder};
+/// use noodles_sam as sam;
+///
+/// let mut readers = Vec::new();
+/// let mut headers = Vec::new();
+///
+/// for src in &["sample.l1.bam", "sample.l2.bam"] {
+///     let mut reader = File::open(src).map(bam::Reader::new)?;
+///     let header: sam::Header = reader
+///         .read_header()?
+///         .parse()
+///         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+///     reader.read_reference_sequences()?;
+///
+///     readers.push(reader);
+///     headers.push(header);
+/// }
+///
+/// let merger = Merger::new(SortOrder::Coordinate);
+/// let writer = File::create("sample.bam")?;
+/// merger.merge(readers, &headers, writer)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Merger {
+    sort_order: SortOrder,
+    build_index: bool,
+}
+
+impl Merger {
+    /// Creates a BAM merger with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::{merge::Merger, sort::SortOrder};
+    /// let merger = Merger::new(SortOrder::Coordinate);
+    /// ```
+    pub fn new(sort_order: SortOrder) -> Self {
+        Builder::new(sort_order).build()
+    }
+
+    /// Creates a BAM merger builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::{merge::Merger, sort::SortOrder};
+    /// let merger = Merger::builder(SortOrder::Coordinate).build();
+    /// ```
+    pub fn builder(sort_order: SortOrder) -> Builder {
+        Builder::new(sort_order)
+    }
+
+    /// Merges the remaining records of the given BAM readers and writes them as BAM to the given
+    /// writer.
+    ///
+    /// Each reader is expected to be positioned at the start of its records and to be sorted in
+    /// the sort order of this merger. `headers` are the parsed SAM headers of the readers, in the
+    /// same order.
+    ///
+    /// This returns the underlying writer and, if enabled, the BAM index of the merged output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::{self as bam, merge::Merger, sort::SortOrder};
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    ///
+    /// let mut writer = bam::Writer::new(Vec::new());
+    /// writer.write_header(&header)?;
+    /// writer.write_reference_sequences(header.reference_sequences())?;
+    /// let (data, _) = writer.finish()?;
+    ///
+    /// let mut reader = bam::Reader::new(&data[..]);
+    /// reader.read_header()?;
+    /// reader.read_reference_sequences()?;
+    ///
+    /// let merger = Merger::new(SortOrder::Coordinate);
+    /// let (output, _) = merger.merge(vec![reader], &[header], Vec::new())?;
+    /// assert!(!output.is_empty());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn merge<R, W>(
+        &self,
+        readers: Vec<Reader<R>>,
+        headers: &[sam::Header],
+        writer: W,
+    ) -> io::Result<(W, Option<bai::Index>)>
+    where
+        R: Read,
+        W: Write,
+    {
+        if readers.len() != headers.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the number of readers and headers must be equal",
+            ));
+        }
+
+        if self.build_index && self.sort_order != SortOrder::Coordinate {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "an index can only be built for coordinate-sorted output",
+            ));
+        }
+
+        let (header, remaps) = reconcile_headers(headers, self.sort_order)?;
+
+        let inputs = readers
+            .into_iter()
+            .zip(remaps)
+            .map(|(reader, remap)| Input { reader, remap })
+            .collect();
+
+        let mut writer = Writer::builder(writer)
+            .set_build_index(self.build_index)
+            .build();
+
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+
+        sort::merge(self.sort_order, inputs, |record| {
+            writer.write_record(&record)
+        })?;
+
+        writer.finish()
+    }
+}
+
+// How records of an input are rewritten to match the merged header.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Remap {
+    reference_sequence_ids: Vec<usize>,
+    read_group_ids: HashMap<String, String>,
+    program_ids: HashMap<String, String>,
+}
+
+impl Remap {
+    fn apply(&self, record: &mut Record) -> io::Result<()> {
+        let reference_sequence_id =
+            self.remap_reference_sequence_id(record.reference_sequence_id())?;
+        record.set_reference_sequence_id(reference_sequence_id);
+
+        let mate_reference_sequence_id =
+            self.remap_reference_sequence_id(record.mate_reference_sequence_id())?;
+        record.set_mate_reference_sequence_id(mate_reference_sequence_id);
+
+        remap_data_field(record, Tag::ReadGroup, &self.read_group_ids)?;
+        remap_data_field(record, Tag::Program, &self.program_ids)?;
+
+        Ok(())
+    }
+
+    fn remap_reference_sequence_id(
+        &self,
+        reference_sequence_id: Option<ReferenceSequenceId>,
+    ) -> io::Result<Option<ReferenceSequenceId>> {
+        let id = match reference_sequence_id {
+            Some(id) => i32::from(id) as usize,
+            None => return Ok(None),
+        };
+
+        let merged_id = self.reference_sequence_ids.get(id).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "invalid reference sequence ID")
+        })?;
+
+        ReferenceSequenceId::try_from(*merged_id as i32)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn remap_data_field(
+    record: &mut Record,
+    tag: Tag,
+    ids: &HashMap<String, String>,
+) -> io::Result<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let new_id = match record.data().get(&tag).transpose()? {
+        Some(ValueRef::String(id)) => match ids.get(id) {
+            Some(new_id) => new_id.clone(),
+            None => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+
+    record.insert_data_field(Field::new(tag, Value::String(new_id)))?;
+
+    Ok(())
+}
+
+struct Input<R>
+where
+    R: Read,
+{
+    reader: Reader<R>,
+    remap: Remap,
+}
+
+impl<R> RecordSource for Input<R>
+where
+    R: Read,
+{
+    fn next_record(&mut self) -> io::Result<Option<Record>> {
+        let mut record = Record::default();
+
+        if self.reader.read_record(&mut record)? == 0 {
+            return Ok(None);
+        }
+
+        self.remap.apply(&mut record)?;
+
+        Ok(Some(record))
+    }
+}
+
+// Builds a merged header from the given headers and the remapping of each input to it.
+fn reconcile_headers(
+    headers: &[sam::Header],
+    sort_order: SortOrder,
+) -> io::Result<(sam::Header, Vec<Remap>)> {
+    let mut merged = sam::Header::default();
+
+    let mut header = headers
+        .iter()
+        .find_map(|header| header.header().cloned())
+        .unwrap_or_default();
+    *header.sort_order_mut() = Some(sort_order.into());
+    *merged.header_mut() = Some(header);
+
+    let reserved_read_group_ids: HashSet<_> = headers
+        .iter()
+        .flat_map(|header| header.read_groups().keys())
+        .cloned()
+        .collect();
+
+    let reserved_program_ids: HashSet<_> = headers
+        .iter()
+        .flat_map(|header| header.programs().keys())
+        .cloned()
+        .collect();
+
+    let mut remaps = Vec::with_capacity(headers.len());
+
+    for header in headers {
+        let reference_sequence_ids = merge_reference_sequences(
+            merged.reference_sequences_mut(),
+            header.reference_sequences(),
+        )?;
+
+        if sort_order == SortOrder::Coordinate
+            && !reference_sequence_ids.windows(2).all(|ids| ids[0] < ids[1])
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "reference sequences are not in a compatible order",
+            ));
+        }
+
+        let read_group_ids = merge_entries(
+            merged.read_groups_mut(),
+            header.read_groups(),
+            &reserved_read_group_ids,
+            |read_group| read_group.id_mut(),
+            |_, _| {},
+        );
+
+        // Programs are merged parents first so that a program's previous program ID (`PP`) is
+        // remapped before it is compared to the merged programs.
+        let program_ids = merge_entries(
+            merged.programs_mut(),
+            sort_programs(header.programs()),
+            &reserved_program_ids,
+            |program| program.id_mut(),
+            |program, ids| {
+                let previous_id = program.previous_id_mut();
+
+                if let Some(id) = previous_id.as_ref().and_then(|id| ids.get(id)) {
+                    *previous_id = Some(id.clone());
+                }
+            },
+        );
+
+        for comment in header.comments() {
+            if !merged.comments().contains(comment) {
+                merged.add_comment(comment.clone());
+            }
+        }
+
+        remaps.push(Remap {
+            reference_sequence_ids,
+            read_group_ids,
+            program_ids,
+        });
+    }
+
+    Ok((merged, remaps))
+}
+
+// Adds the reference sequences of an input to the merged reference sequences and returns the
+// merged ID of each input reference sequence.
+fn merge_reference_sequences(
+    merged: &mut sam::header::ReferenceSequences,
+    reference_sequences: &sam::header::ReferenceSequences,
+) -> io::Result<Vec<usize>> {
+    let mut ids = Vec::with_capacity(reference_sequences.len());
+
+    for (name, reference_sequence) in reference_sequences {
+        let id = match merged.get_full(name) {
+            Some((i, _, merged_reference_sequence)) => {
+                if merged_reference_sequence.len() != reference_sequence.len() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "reference sequence {} has conflicting lengths: {} != {}",
+                            name,
+                            merged_reference_sequence.len(),
+                            reference_sequence.len()
+                        ),
+                    ));
+                }
+
+                i
+            }
+            None => {
+                merged.insert(name.clone(), reference_sequence.clone());
+                merged.len() - 1
+            }
+        };
+
+        ids.push(id);
+    }
+
+    Ok(ids)
+}
+
+// Adds read groups or programs of an input to the merged entries.
+//
+// Each entry is first remapped using the IDs that were renamed so far. This returns the IDs that
+// were renamed, as a map of input ID to merged ID.
+//
+// Identical entries are deduplicated. An entry that collides with a different entry is renamed to
+// an ID that is not used by any input.
+fn merge_entries<'a, T, I, F, G>(
+    merged: &mut IndexMap<String, T>,
+    entries: I,
+    reserved_ids: &HashSet<String>,
+    id_mut: F,
+    remap: G,
+) -> HashMap<String, String>
+where
+    T: Clone + PartialEq + 'a,
+    I: IntoIterator<Item = (&'a String, &'a T)>,
+    F: Fn(&mut T) -> &mut String,
+    G: Fn(&mut T, &HashMap<String, String>),
+{
+    let mut renamed_ids = HashMap::new();
+
+    for (id, entry) in entries {
+        let mut entry = entry.clone();
+        remap(&mut entry, &renamed_ids);
+
+        match merged.get(id) {
+            Some(merged_entry) if merged_entry == &entry => {}
+            Some(_) => {
+                let new_id = (1..)
+                    .map(|i| format!("{}-{}", id, i))
+                    .find(|new_id| !merged.contains_key(new_id) && !reserved_ids.contains(new_id))
+                    .expect("exhausted IDs");
+
+                *id_mut(&mut entry) = new_id.clone();
+                merged.insert(new_id.clone(), entry);
+
+                renamed_ids.insert(id.clone(), new_id);
+            }
+            None => {
+                merged.insert(id.clone(), entry);
+            }
+        }
+    }
+
+    renamed_ids
+}
+
+// Orders programs so that each program follows its previous program (`PP`) in the same header.
+fn sort_programs(
+    programs: &IndexMap<String, sam::header::Program>,
+) -> Vec<(&String, &sam::header::Program)> {
+    let mut visited = HashSet::new();
+    let mut sorted = Vec::with_capacity(programs.len());
+
+    for id in programs.keys() {
+        let start = sorted.len();
+        let mut next = Some(id.as_str());
+
+        while let Some((_, id, program)) = next.and_then(|id| programs.get_full(id)) {
+            if !visited.insert(id) {
+                break;
+            }
+
+            sorted.push((id, program));
+            next = program.previous_id();
+        }
+
+        sorted[start..].reverse();
+    }
+
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use noodles_sam::{
+        header::{Program, ReadGroup, ReferenceSequence},
+        record::Position,
+    };
+
+    use super::*;
+
+    fn build_header(
+        reference_sequences: &[(&str, i32)],
+        read_groups: Vec<ReadGroup>,
+        programs: Vec<Program>,
+    ) -> sam::Header {
+        let mut builder = sam::Header::builder();
+
+        for &(name, len) in reference_sequences {
+            builder = builder.add_reference_sequence(ReferenceSequence::new(name.into(), len));
+        }
+
+        for read_group in read_groups {
+            builder = builder.add_read_group(read_group);
+        }
+
+        for program in programs {
+            builder = builder.add_program(program);
+        }
+
+        builder.build()
+    }
+
+    #[test]
+    fn test_reconcile_headers() -> io::Result<()> {
+        let headers = [
+            build_header(
+                &[("sq0", 8), ("sq1", 13)],
+                vec![ReadGroup::builder().set_id("rg0").set_sample("a").build()],
+                vec![Program::new(String::from("pg0"))],
+            ),
+            build_header(
+                &[("sq1", 13), ("sq2", 21)],
+                vec![ReadGroup::builder().set_id("rg0").set_sample("b").build()],
+                vec![
+                    Program::new(String::from("pg0")),
+                    Program::builder().set_id("pg1").set_name("a").build(),
+                ],
+            ),
+            build_header(
+                &[("sq0", 8)],
+                vec![],
+                vec![Program::builder()
+                    .set_id("pg1")
+                    .set_name("b")
+                    .set_previous_id("pg0")
+                    .build()],
+            ),
+        ];
+
+        let (header, remaps) = reconcile_headers(&headers, SortOrder::Coordinate)?;
+
+        assert_eq!(
+            header.header().and_then(|header| header.sort_order()),
+            Some(sam::header::header::SortOrder::Coordinate)
+        );
+
+        let names: Vec<_> = header.reference_sequences().keys().collect();
+        assert_eq!(names, ["sq0", "sq1", "sq2"]);
+
+        let read_group_ids: Vec<_> = header.read_groups().keys().collect();
+        assert_eq!(read_group_ids, ["rg0", "rg0-1"]);
+        assert_eq!(header.read_groups()["rg0-1"].sample(), Some("b"));
+
+        let program_ids: Vec<_> = header.programs().keys().collect();
+        assert_eq!(program_ids, ["pg0", "pg1", "pg1-1"]);
+        assert_eq!(header.programs()["pg1-1"].name(), Some("b"));
+        assert_eq!(header.programs()["pg1-1"].previous_id(), Some("pg0"));
+
+        assert_eq!(remaps.len(), 3);
+        assert_eq!(
+            remaps[0],
+            Remap {
+                reference_sequence_ids: vec![0, 1],
+                ..Default::default()
+            }
+        );
+        assert_eq!(remaps[1].reference_sequence_ids, [1, 2]);
+        assert_eq!(
+            remaps[1].read_group_ids.get("rg0").map(|id| id.as_str()),
+            Some("rg0-1")
+        );
+        assert!(remaps[1].program_ids.is_empty());
+        assert_eq!(remaps[2].reference_sequence_ids, [0]);
+        assert_eq!(
+            remaps[2].program_ids.get("pg1").map(|id| id.as_str()),
+            Some("pg1-1")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconcile_headers_with_colliding_previous_program() -> io::Result<()> {
+        let headers = [
+            build_header(
+                &[("sq0", 8)],
+                vec![],
+                vec![
+                    Program::builder().set_id("pg0").set_name("a").build(),
+                    Program::builder()
+                        .set_id("pg1")
+                        .set_previous_id("pg0")
+                        .build(),
+                ],
+            ),
+            build_header(
+                &[("sq0", 8)],
+                vec![],
+                vec![
+                    Program::builder()
+                        .set_id("pg1")
+                        .set_previous_id("pg0")
+                        .build(),
+                    Program::builder().set_id("pg0").set_name("b").build(),
+                ],
+            ),
+        ];
+
+        let (header, remaps) = reconcile_headers(&headers, SortOrder::Coordinate)?;
+
+        let program_ids: Vec<_> = header.programs().keys().collect();
+        assert_eq!(program_ids, ["pg0", "pg1", "pg0-1", "pg1-1"]);
+        assert_eq!(header.programs()["pg0-1"].name(), Some("b"));
+        assert_eq!(header.programs()["pg1-1"].previous_id(), Some("pg0-1"));
+
+        assert!(remaps[0].program_ids.is_empty());
+        assert_eq!(
+            remaps[1].program_ids.get("pg0").map(|id| id.as_str()),
+            Some("pg0-1")
+        );
+        assert_eq!(
+            remaps[1].program_ids.get("pg1").map(|id| id.as_str()),
+            Some("pg1-1")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconcile_headers_with_incompatible_reference_sequences() {
+        let headers = [
+            build_header(&[("sq0", 8)], vec![], vec![]),
+            build_header(&[("sq0", 13)], vec![], vec![]),
+        ];
+
+        assert!(matches!(
+            reconcile_headers(&headers, SortOrder::QueryName),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        let headers = [
+            build_header(&[("sq0", 8), ("sq1", 13)], vec![], vec![]),
+            build_header(&[("sq1", 13), ("sq0", 8)], vec![], vec![]),
+        ];
+
+        assert!(reconcile_headers(&headers, SortOrder::QueryName).is_ok());
+        assert!(matches!(
+            reconcile_headers(&headers, SortOrder::Coordinate),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+
+    #[test]
+    fn test_merge() -> io::Result<()> {
+        fn build_input(header: &sam::Header, records: &[(&str, i32, i32)]) -> io::Result<Vec<u8>> {
+            let mut writer = Writer::new(Vec::new());
+            writer.write_header(header)?;
+            writer.write_reference_sequences(header.reference_sequences())?;
+
+            for &(read_name, reference_sequence_id, position) in records {
+                let mut record = Record::default();
+
+                let read_name = CString::new(read_name)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                record.set_read_name(&read_name)?;
+
+                let reference_sequence_id = ReferenceSequenceId::try_from(reference_sequence_id)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                record.set_reference_sequence_id(Some(reference_sequence_id));
+
+                let position = Position::try_from(position)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                record.set_position(Some(position))?;
+
+                record.insert_data_field(Field::new(
+                    Tag::ReadGroup,
+                    Value::String(String::from("rg0")),
+                ))?;
+
+                writer.write_record(&record)?;
+            }
+
+            writer.finish().map(|(data, _)| data)
+        }
+
+        let headers = [
+            build_header(
+                &[("sq0", 8), ("sq1", 13)],
+                vec![ReadGroup::builder().set_id("rg0").set_sample("a").build()],
+                vec![],
+            ),
+            build_header(
+                &[("sq1", 13)],
+                vec![ReadGroup::builder().set_id("rg0").set_sample("b").build()],
+                vec![],
+            ),
+        ];
+
+        let inputs = [
+            build_input(&headers[0], &[("r0", 0, 5), ("r1", 1, 3)])?,
+            build_input(&headers[1], &[("r2", 0, 2), ("r3", 0, 8)])?,
+        ];
+
+        let mut readers = Vec::new();
+
+        for input in &inputs {
+            let mut reader = Reader::new(&input[..]);
+            reader.read_header()?;
+            reader.read_reference_sequences()?;
+            readers.push(reader);
+        }
+
+        let merger = Merger::builder(SortOrder::Coordinate)
+            .set_build_index(true)
+            .build();
+        let (output, index) = merger.merge(readers, &headers, Vec::new())?;
+        assert!(index.is_some());
+
+        let mut reader = Reader::new(&output[..]);
+        let header: sam::Header = reader
+            .read_header()?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        reader.read_reference_sequences()?;
+
+        assert_eq!(header.reference_sequences().len(), 2);
+        assert_eq!(header.read_groups().len(), 2);
+
+        let mut actual = Vec::new();
+
+        for result in reader.records() {
+            let record = result?;
+
+            let read_name = record
+                .read_name()
+                .map(|s| s.to_string_lossy().into_owned())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let reference_sequence_id = record.reference_sequence_id().map(i32::from);
+            let read_group = match record.data().get(&Tag::ReadGroup).transpose()? {
+                Some(ValueRef::String(s)) => s.to_string(),
+                _ => panic!("missing read group"),
+            };
+
+            actual.push((read_name, reference_sequence_id, read_group));
+        }
+
+        let expected = [
+            (String::from("r0"), Some(0), String::from("rg0")),
+            (String::from("r2"), Some(1), String::from("rg0-1")),
+            (String::from("r1"), Some(1), String::from("rg0")),
+            (String::from("r3"), Some(1), String::from("rg0-1")),
+        ];
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use super::Merger;
+use crate::sort::SortOrder;
+
+/// A BAM merger builder.
+pub struct Builder {
+    sort_order: SortOrder,
+    build_index: bool,
+}
+
+impl Builder {
+    pub(crate) fn new(sort_order: SortOrder) -> Self {
+        Self {
+            sort_order,
+            build_index: false,
+        }
+    }
+
+    /// Sets whether to build a BAM index (BAI) of the merged output.
+    ///
+    /// This is only valid when merging coordinate-sorted inputs.
+    ///
+    /// By default, no index is built.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::{merge::Merger, sort::SortOrder};
+    /// let merger = Merger::builder(SortOrder::Coordinate)
+    ///     .set_build_index(true)
+    ///     .build();
+    /// ```
+    pub fn set_build_index(mut self, build_index: bool) -> Self {
+        self.build_index = build_index;
+        self
+    }
+
+    /// Builds a BAM merger.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::{merge::Merger, sort::SortOrder};
+    /// let merger = Merger::builder(SortOrder::QueryName).build();
+    /// ```
+    pub fn build(self) -> Merger {
+        Merger {
+            sort_order: self.sort_order,
+            build_index: self.build_index,
+        }
+    }
+}
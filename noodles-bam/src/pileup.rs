@@ -0,0 +1,598 @@
+//! BAM record pileup.
+//!
+//! A pileup walks the reference positions covered by a coordinate-sorted stream of records, e.g.,
+//! the records returned by [`crate::Reader::query`], and stacks the aligned read bases at each
+//! position.
+
+mod builder;
+mod column;
+
+pub use self::{
+    builder::Builder,
+    column::{Alignment, Column},
+};
+
+use std::{convert::TryFrom, io};
+
+use noodles_sam::record::{cigar::op::Kind, Flags};
+
+use crate::{record::sequence::Base, Record};
+
+const MISSING_QUALITY_SCORE: u8 = 0xff;
+
+/// An iterator over pileup columns of coordinate-sorted records.
+///
+/// Columns are returned for each reference position that has at least one read aligned to it,
+/// after filtering. Positions that are only spanned by skipped regions (`N`) are not returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::fs::File;
+/// use noodles::Region;
+/// use noodles_bam::{self as bam, bai, pileup::Pileup};
+/// use noodles_sam as sam;
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// let header: sam::Header = reader.read_header()?.parse()?;
+///
+/// let index = bai::read("sample.bam.bai")?;
+/// let region = Region::mapped("sq0", 8, 13);
+/// let query = reader.query(header.reference_sequences(), &index, &region)?;
+///
+/// let pileup = Pileup::builder(query).set_min_mapping_quality(20).build();
+///
+/// for result in pileup {
+///     let column = result?;
+///     println!("{}\t{}", column.position(), column.depth());
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Pileup<I> {
+    records: I,
+    max_depth: usize,
+    min_mapping_quality: u8,
+    min_base_quality: u8,
+    required_flags: Flags,
+    excluded_flags: Flags,
+    depth_only: bool,
+    pending: Option<ActiveRead>,
+    is_eof: bool,
+    active: Vec<ActiveRead>,
+    cursor: Option<(usize, i32)>,
+}
+
+impl<I> Pileup<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    /// Creates a pileup with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::pileup::Pileup;
+    /// let pileup = Pileup::new(iter::empty());
+    /// ```
+    pub fn new(records: I) -> Self {
+        Builder::new(records).build()
+    }
+
+    /// Creates a pileup builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::pileup::Pileup;
+    /// let builder = Pileup::builder(iter::empty());
+    /// ```
+    pub fn builder(records: I) -> Builder<I> {
+        Builder::new(records)
+    }
+
+    // Reads the next record that passes the read filters into `pending`.
+    fn fill_pending(&mut self) -> io::Result<()> {
+        while self.pending.is_none() && !self.is_eof {
+            let record = match self.records.next() {
+                Some(result) => result?,
+                None => {
+                    self.is_eof = true;
+                    break;
+                }
+            };
+
+            if !self.is_read_allowed(&record) {
+                continue;
+            }
+
+            let read = match ActiveRead::from_record(&record)? {
+                Some(read) => read,
+                None => continue,
+            };
+
+            if let Some(cursor) = self.cursor {
+                if (read.reference_sequence_id, read.start) < cursor {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "records must be coordinate-sorted",
+                    ));
+                }
+            }
+
+            self.pending = Some(read);
+        }
+
+        Ok(())
+    }
+
+    fn is_read_allowed(&self, record: &Record) -> bool {
+        let flags = record.flags();
+
+        flags.contains(self.required_flags)
+            && !flags.intersects(self.excluded_flags)
+            && u8::from(record.mapping_quality()) >= self.min_mapping_quality
+    }
+
+    fn next_column(&mut self) -> io::Result<Option<Column>> {
+        loop {
+            if let Some((reference_sequence_id, position)) = self.cursor {
+                self.active.retain(|read| {
+                    read.reference_sequence_id == reference_sequence_id && read.end >= position
+                });
+            }
+
+            self.fill_pending()?;
+
+            if self.active.is_empty() {
+                match self.pending {
+                    Some(ref read) => {
+                        self.cursor = Some((read.reference_sequence_id, read.start));
+                    }
+                    None => return Ok(None),
+                }
+            }
+
+            let (reference_sequence_id, position) = self.cursor.expect("missing cursor");
+
+            while let Some(read) = self.pending.take() {
+                if (read.reference_sequence_id, read.start) != (reference_sequence_id, position) {
+                    self.pending = Some(read);
+                    break;
+                }
+
+                if self.active.len() < self.max_depth {
+                    self.active.push(read);
+                }
+
+                self.fill_pending()?;
+            }
+
+            self.cursor = Some((reference_sequence_id, position + 1));
+
+            let column = self.build_column(reference_sequence_id, position);
+
+            if column.depth > 0 {
+                return Ok(Some(column));
+            }
+        }
+    }
+
+    fn build_column(&self, reference_sequence_id: usize, position: i32) -> Column {
+        let mut depth = 0;
+        let mut alignments = Vec::new();
+
+        for read in &self.active {
+            let i = match read.entry_index(position) {
+                Some(i) => i,
+                None => continue,
+            };
+
+            let entry = &read.entries[i];
+
+            let (base, quality_score) = match entry.kind {
+                EntryKind::Base(base, quality_score) => {
+                    if quality_score != MISSING_QUALITY_SCORE
+                        && quality_score < self.min_base_quality
+                    {
+                        continue;
+                    }
+
+                    let quality_score = if quality_score == MISSING_QUALITY_SCORE {
+                        None
+                    } else {
+                        Some(quality_score)
+                    };
+
+                    (Some(base), quality_score)
+                }
+                EntryKind::Deletion => (None, None),
+            };
+
+            depth += 1;
+
+            if !self.depth_only {
+                alignments.push(Alignment {
+                    base,
+                    quality_score,
+                    insertion: entry.insertion.clone(),
+                    mapping_quality: read.mapping_quality,
+                    is_reverse_complemented: read.is_reverse_complemented,
+                    is_head: i == 0,
+                    is_tail: i == read.entries.len() - 1,
+                });
+            }
+        }
+
+        Column {
+            reference_sequence_id,
+            position,
+            depth,
+            alignments,
+        }
+    }
+}
+
+impl<I> Iterator for Pileup<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Column>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_column().transpose()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum EntryKind {
+    Base(Base, u8),
+    Deletion,
+}
+
+// A read's alignment to a single reference position.
+#[derive(Debug)]
+struct Entry {
+    kind: EntryKind,
+    insertion: Vec<Base>,
+}
+
+// A run of entries that are aligned to consecutive reference positions.
+#[derive(Debug)]
+struct Block {
+    // The offset of the first reference position from the start of the read.
+    offset: usize,
+    // The index of the first entry.
+    entry_start: usize,
+}
+
+// A read that is being piled up, expanded to one entry per aligned or deleted reference position.
+//
+// Skipped regions (`N`) have no entries. They separate the entries into blocks.
+#[derive(Debug)]
+struct ActiveRead {
+    reference_sequence_id: usize,
+    start: i32,
+    end: i32,
+    mapping_quality: u8,
+    is_reverse_complemented: bool,
+    blocks: Vec<Block>,
+    entries: Vec<Entry>,
+}
+
+impl ActiveRead {
+    // Returns `None` if the record is not placed or does not align to the reference.
+    fn from_record(record: &Record) -> io::Result<Option<Self>> {
+        let reference_sequence_id = match record.reference_sequence_id() {
+            Some(id) => i32::from(id) as usize,
+            None => return Ok(None),
+        };
+
+        let start = match record.position() {
+            Some(position) => i32::from(position),
+            None => return Ok(None),
+        };
+
+        let bases: Vec<_> = record.sequence().bases().collect();
+        let quality_scores = record.quality_scores();
+
+        let read_base = |i: usize| {
+            let base = bases.get(i).copied().unwrap_or(Base::N);
+            let quality_score = quality_scores
+                .get(i)
+                .copied()
+                .unwrap_or(MISSING_QUALITY_SCORE);
+            (base, quality_score)
+        };
+
+        let mut blocks = Vec::new();
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut read_position = 0;
+        let mut reference_offset = 0;
+
+        for result in record.cigar().ops() {
+            let op = result?;
+            let len = op.len() as usize;
+
+            match op.kind() {
+                Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                    push_block(&mut blocks, reference_offset, entries.len());
+
+                    for i in read_position..read_position + len {
+                        let (base, quality_score) = read_base(i);
+                        entries.push(Entry::new(EntryKind::Base(base, quality_score)));
+                    }
+
+                    read_position += len;
+                    reference_offset += len;
+                }
+                Kind::Insertion => {
+                    // A leading insertion has no preceding position to attach to and is dropped.
+                    if let Some(entry) = entries.last_mut() {
+                        let end = (read_position + len).min(bases.len());
+                        let start = read_position.min(end);
+                        entry.insertion.extend_from_slice(&bases[start..end]);
+                    }
+
+                    read_position += len;
+                }
+                Kind::Deletion => {
+                    push_block(&mut blocks, reference_offset, entries.len());
+                    entries.extend((0..len).map(|_| Entry::new(EntryKind::Deletion)));
+                    reference_offset += len;
+                }
+                Kind::Skip => reference_offset += len,
+                Kind::SoftClip => read_position += len,
+                Kind::HardClip | Kind::Pad => {}
+            }
+        }
+
+        if entries.is_empty() {
+            return Ok(None);
+        }
+
+        let end = start + reference_offset as i32 - 1;
+
+        Ok(Some(Self {
+            reference_sequence_id,
+            start,
+            end,
+            mapping_quality: u8::from(record.mapping_quality()),
+            is_reverse_complemented: record.flags().is_reverse_complemented(),
+            blocks,
+            entries,
+        }))
+    }
+
+    // Returns the index of the entry at the given reference position, if any.
+    fn entry_index(&self, position: i32) -> Option<usize> {
+        let offset = usize::try_from(position - self.start).ok()?;
+
+        let i = self
+            .blocks
+            .partition_point(|block| block.offset <= offset)
+            .checked_sub(1)?;
+
+        let block = &self.blocks[i];
+        let entry_end = self
+            .blocks
+            .get(i + 1)
+            .map(|block| block.entry_start)
+            .unwrap_or(self.entries.len());
+
+        let j = block.entry_start + (offset - block.offset);
+        Some(j).filter(|&j| j < entry_end)
+    }
+}
+
+// Starts a new block if the next entry does not continue the last block, i.e., after a skip.
+fn push_block(blocks: &mut Vec<Block>, reference_offset: usize, entry_start: usize) {
+    let is_contiguous = blocks
+        .last()
+        .map(|block| block.offset + (entry_start - block.entry_start) == reference_offset)
+        .unwrap_or(false);
+
+    if !is_contiguous {
+        blocks.push(Block {
+            offset: reference_offset,
+            entry_start,
+        });
+    }
+}
+
+impl Entry {
+    fn new(kind: EntryKind) -> Self {
+        Self {
+            kind,
+            insertion: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_sam::record::{MappingQuality, Position};
+
+    use crate::record::ReferenceSequenceId;
+
+    use super::*;
+
+    fn build_record(
+        reference_sequence_id: i32,
+        position: i32,
+        flags: Flags,
+        cigar: &str,
+        sequence: &str,
+        quality_scores: &str,
+    ) -> Result<Record, Box<dyn std::error::Error>> {
+        let mut record = Record::default();
+        record
+            .set_reference_sequence_id(Some(ReferenceSequenceId::try_from(reference_sequence_id)?));
+        record.set_position(Some(Position::try_from(position)?))?;
+        record.set_flags(flags);
+        record.set_mapping_quality(MappingQuality::from(60));
+        record.set_cigar(&cigar.parse()?)?;
+        record.set_sequence(&sequence.parse()?)?;
+        record.set_quality_scores(&quality_scores.parse()?)?;
+        Ok(record)
+    }
+
+    #[test]
+    fn test_next() -> Result<(), Box<dyn std::error::Error>> {
+        let records = vec![
+            build_record(0, 1, Flags::empty(), "2M1I2M1D2M", "ACGTACG", "NNNNNNN")?,
+            build_record(0, 3, Flags::REVERSE_COMPLEMENTED, "3M", "TTT", "N!N")?,
+            build_record(1, 2, Flags::empty(), "1M2N1M", "GG", "NN")?,
+        ];
+
+        let pileup = Pileup::new(records.into_iter().map(Ok));
+        let columns: Vec<_> = pileup.collect::<io::Result<_>>()?;
+
+        let positions: Vec<_> = columns
+            .iter()
+            .map(|column| {
+                (
+                    column.reference_sequence_id(),
+                    column.position(),
+                    column.depth(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            positions,
+            [
+                (0, 1, 1),
+                (0, 2, 1),
+                (0, 3, 2),
+                (0, 4, 1),
+                (0, 5, 2),
+                (0, 6, 1),
+                (0, 7, 1),
+                (1, 2, 1),
+                (1, 5, 1),
+            ]
+        );
+
+        let alignment = &columns[0].alignments()[0];
+        assert_eq!(alignment.base(), Some(Base::A));
+        assert_eq!(alignment.quality_score(), Some(45));
+        assert!(alignment.is_head());
+        assert!(!alignment.is_tail());
+
+        assert_eq!(columns[1].alignments()[0].insertion(), [Base::G]);
+
+        let alignment = &columns[2].alignments()[1];
+        assert_eq!(alignment.base(), Some(Base::T));
+        assert!(alignment.is_reverse_complemented());
+        assert!(alignment.is_head());
+
+        let alignments = columns[4].alignments();
+        assert!(alignments[0].is_deletion());
+        assert_eq!(alignments[0].quality_score(), None);
+        assert!(alignments[1].is_tail());
+
+        assert!(columns[6].alignments()[0].is_tail());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_filters() -> Result<(), Box<dyn std::error::Error>> {
+        let records = vec![
+            build_record(0, 1, Flags::empty(), "2M", "AC", "NN")?,
+            build_record(0, 1, Flags::empty(), "2M", "AC", "NN")?,
+            build_record(0, 1, Flags::DUPLICATE, "2M", "AC", "NN")?,
+            build_record(0, 2, Flags::empty(), "1M", "C", "!")?,
+        ];
+
+        let pileup = Pileup::builder(records.clone().into_iter().map(Ok))
+            .set_max_depth(1)
+            .set_depth_only(true)
+            .build();
+        let columns: Vec<_> = pileup.collect::<io::Result<_>>()?;
+
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].depth(), 1);
+        assert!(columns[0].alignments().is_empty());
+        assert_eq!(columns[1].depth(), 1);
+
+        let pileup = Pileup::builder(records.into_iter().map(Ok))
+            .set_min_base_quality(0)
+            .set_excluded_flags(Flags::empty())
+            .build();
+        let depths: Vec<_> = pileup
+            .map(|result| result.map(|column| column.depth()))
+            .collect::<io::Result<_>>()?;
+
+        assert_eq!(depths, [3, 4]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_skip() -> Result<(), Box<dyn std::error::Error>> {
+        let record = build_record(0, 1, Flags::empty(), "2M100000N1D2M", "ACGT", "NNNN")?;
+
+        let read = ActiveRead::from_record(&record)?.expect("missing read");
+        assert_eq!(read.end, 100005);
+        assert_eq!(read.entries.len(), 5);
+
+        let pileup = Pileup::new(vec![Ok(record)].into_iter());
+        let columns: Vec<_> = pileup.collect::<io::Result<_>>()?;
+
+        let positions: Vec<_> = columns.iter().map(|column| column.position()).collect();
+        assert_eq!(positions, [1, 2, 100003, 100004, 100005]);
+
+        assert!(columns[0].alignments()[0].is_head());
+        assert!(columns[2].alignments()[0].is_deletion());
+        assert_eq!(columns[3].alignments()[0].base(), Some(Base::G));
+        assert!(columns[4].alignments()[0].is_tail());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_leading_insertion() -> Result<(), Box<dyn std::error::Error>> {
+        let records = vec![build_record(
+            0,
+            1,
+            Flags::empty(),
+            "1S2I2M",
+            "TGGAC",
+            "NNNNN",
+        )?];
+
+        let pileup = Pileup::new(records.into_iter().map(Ok));
+        let columns: Vec<_> = pileup.collect::<io::Result<_>>()?;
+
+        assert_eq!(columns.len(), 2);
+
+        let alignment = &columns[0].alignments()[0];
+        assert_eq!(alignment.base(), Some(Base::A));
+        assert!(alignment.insertion().is_empty());
+        assert!(alignment.is_head());
+
+        assert_eq!(columns[1].alignments()[0].base(), Some(Base::C));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_unsorted_records() -> Result<(), Box<dyn std::error::Error>> {
+        let records = vec![
+            build_record(0, 5, Flags::empty(), "1M", "A", "N")?,
+            build_record(0, 1, Flags::empty(), "1M", "A", "N")?,
+        ];
+
+        let mut pileup = Pileup::new(records.into_iter().map(Ok));
+
+        assert!(matches!(
+            pileup.next(),
+            Some(Err(ref e)) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use std::io;
+
+use noodles_sam::record::Flags;
+
+use super::Pileup;
+use crate::Record;
+
+// samtools mpileup defaults.
+const DEFAULT_MAX_DEPTH: usize = 8000;
+const DEFAULT_MIN_BASE_QUALITY: u8 = 13;
+
+/// A pileup builder.
+pub struct Builder<I> {
+    records: I,
+    max_depth: usize,
+    min_mapping_quality: u8,
+    min_base_quality: u8,
+    required_flags: Flags,
+    excluded_flags: Flags,
+    depth_only: bool,
+}
+
+impl<I> Builder<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    pub(crate) fn new(records: I) -> Self {
+        Self {
+            records,
+            max_depth: DEFAULT_MAX_DEPTH,
+            min_mapping_quality: 0,
+            min_base_quality: DEFAULT_MIN_BASE_QUALITY,
+            required_flags: Flags::empty(),
+            excluded_flags: Flags::UNMAPPED | Flags::SECONDARY | Flags::QC_FAIL | Flags::DUPLICATE,
+            depth_only: false,
+        }
+    }
+
+    /// Sets the maximum number of reads to pile up at a position.
+    ///
+    /// Reads that start at a position where the depth has already reached the maximum are
+    /// skipped.
+    ///
+    /// By default, this is 8000.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::pileup::Pileup;
+    /// let pileup = Pileup::builder(iter::empty()).set_max_depth(250).build();
+    /// ```
+    pub fn set_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the minimum mapping quality of reads to pile up.
+    ///
+    /// By default, this is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::pileup::Pileup;
+    /// let pileup = Pileup::builder(iter::empty()).set_min_mapping_quality(20).build();
+    /// ```
+    pub fn set_min_mapping_quality(mut self, min_mapping_quality: u8) -> Self {
+        self.min_mapping_quality = min_mapping_quality;
+        self
+    }
+
+    /// Sets the minimum quality score of bases to pile up.
+    ///
+    /// Deletions are not filtered by base quality.
+    ///
+    /// By default, this is 13.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::pileup::Pileup;
+    /// let pileup = Pileup::builder(iter::empty()).set_min_base_quality(0).build();
+    /// ```
+    pub fn set_min_base_quality(mut self, min_base_quality: u8) -> Self {
+        self.min_base_quality = min_base_quality;
+        self
+    }
+
+    /// Sets the flags that reads must have all of to be piled up.
+    ///
+    /// By default, no flags are required.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::pileup::Pileup;
+    /// use noodles_sam::record::Flags;
+    /// let pileup = Pileup::builder(iter::empty())
+    ///     .set_required_flags(Flags::PROPER_PAIR)
+    ///     .build();
+    /// ```
+    pub fn set_required_flags(mut self, required_flags: Flags) -> Self {
+        self.required_flags = required_flags;
+        self
+    }
+
+    /// Sets the flags that reads must have none of to be piled up.
+    ///
+    /// By default, unmapped, secondary, QC fail, and duplicate reads are excluded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::pileup::Pileup;
+    /// use noodles_sam::record::Flags;
+    /// let pileup = Pileup::builder(iter::empty())
+    ///     .set_excluded_flags(Flags::UNMAPPED | Flags::SECONDARY)
+    ///     .build();
+    /// ```
+    pub fn set_excluded_flags(mut self, excluded_flags: Flags) -> Self {
+        self.excluded_flags = excluded_flags;
+        self
+    }
+
+    /// Sets whether to only compute the depth of each column.
+    ///
+    /// When enabled, columns do not include their alignments, which avoids building them.
+    ///
+    /// By default, this is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::pileup::Pileup;
+    /// let pileup = Pileup::builder(iter::empty()).set_depth_only(true).build();
+    /// ```
+    pub fn set_depth_only(mut self, depth_only: bool) -> Self {
+        self.depth_only = depth_only;
+        self
+    }
+
+    /// Builds a pileup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    /// use noodles_bam::pileup::Pileup;
+    /// let pileup = Pileup::builder(iter::empty()).build();
+    /// ```
+    pub fn build(self) -> Pileup<I> {
+        Pileup {
+            records: self.records,
+            max_depth: self.max_depth,
+            min_mapping_quality: self.min_mapping_quality,
+            min_base_quality: self.min_base_quality,
+            required_flags: self.required_flags,
+            excluded_flags: self.excluded_flags,
+            depth_only: self.depth_only,
+            pending: None,
+            is_eof: false,
+            active: Vec::new(),
+            cursor: None,
+        }
+    }
+}
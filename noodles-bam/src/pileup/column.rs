@@ -0,0 +1,96 @@
+use crate::record::sequence::Base;
+
+/// A read aligned to a pileup column.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alignment {
+    pub(crate) base: Option<Base>,
+    pub(crate) quality_score: Option<u8>,
+    pub(crate) insertion: Vec<Base>,
+    pub(crate) mapping_quality: u8,
+    pub(crate) is_reverse_complemented: bool,
+    pub(crate) is_head: bool,
+    pub(crate) is_tail: bool,
+}
+
+impl Alignment {
+    /// Returns the read base aligned to the position.
+    ///
+    /// This is `None` if the position is deleted from the read.
+    pub fn base(&self) -> Option<Base> {
+        self.base
+    }
+
+    /// Returns the quality score of the read base aligned to the position.
+    ///
+    /// This is `None` if the position is deleted from the read or the read has no quality
+    /// scores.
+    pub fn quality_score(&self) -> Option<u8> {
+        self.quality_score
+    }
+
+    /// Returns whether the position is deleted from the read.
+    pub fn is_deletion(&self) -> bool {
+        self.base.is_none()
+    }
+
+    /// Returns the read bases inserted between this position and the next reference position.
+    ///
+    /// This is empty if there is no insertion. An insertion that precedes the first aligned
+    /// position of a read, e.g., in `1S2I4M`, has no position to follow and is not reported.
+    pub fn insertion(&self) -> &[Base] {
+        &self.insertion
+    }
+
+    /// Returns the mapping quality of the read.
+    pub fn mapping_quality(&self) -> u8 {
+        self.mapping_quality
+    }
+
+    /// Returns whether the read is reverse complemented.
+    pub fn is_reverse_complemented(&self) -> bool {
+        self.is_reverse_complemented
+    }
+
+    /// Returns whether this is the first reference position the read is aligned to.
+    pub fn is_head(&self) -> bool {
+        self.is_head
+    }
+
+    /// Returns whether this is the last reference position the read is aligned to.
+    pub fn is_tail(&self) -> bool {
+        self.is_tail
+    }
+}
+
+/// A pileup column, i.e., the reads aligned to a single reference position.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Column {
+    pub(crate) reference_sequence_id: usize,
+    pub(crate) position: i32,
+    pub(crate) depth: usize,
+    pub(crate) alignments: Vec<Alignment>,
+}
+
+impl Column {
+    /// Returns the reference sequence ID of the column.
+    pub fn reference_sequence_id(&self) -> usize {
+        self.reference_sequence_id
+    }
+
+    /// Returns the 1-based reference position of the column.
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Returns the number of reads aligned to the position, including deletions.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the reads aligned to the position, in the order they were read.
+    ///
+    /// This is empty if the pileup only computes depth.
+    pub fn alignments(&self) -> &[Alignment] {
+        &self.alignments
+    }
+}
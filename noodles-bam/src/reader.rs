@@ -14,6 +14,7 @@ use std::{
 use byteorder::{LittleEndian, ReadBytesExt};
 use noodles::Region;
 use noodles_bgzf::{self as bgzf, VirtualPosition};
+use noodles_csi::{self as csi, index::reference_sequence::bin::Chunk, BinningIndex};
 use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
 
 use super::{bai, Record, MAGIC_NUMBER};
@@ -265,6 +266,9 @@ where
 
     /// Returns an iterator over records that intersect the given region.
     ///
+    /// The index can be any binning index, e.g., a BAM index (BAI) or a coordinate-sorted index
+    /// (CSI).
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -287,37 +291,73 @@ where
     /// }
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn query(
+    pub fn query<I>(
         &mut self,
         reference_sequences: &ReferenceSequences,
-        index: &bai::Index,
+        index: &I,
         region: &Region,
-    ) -> io::Result<Query<'_, R>> {
+    ) -> io::Result<Query<'_, R>>
+    where
+        I: BinningIndex,
+    {
         let (i, start, end) = resolve_region(reference_sequences, region)?;
+        let chunks = index.query(i, start, end)?;
+        Ok(Query::new(self, chunks, vec![(i, start, end)]))
+    }
 
-        let index_reference_sequence = index.reference_sequences().get(i).ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::InvalidInput,
-                format!(
-                    "could not find reference in index: {} >= {}",
-                    i,
-                    reference_sequences.len()
-                ),
-            )
-        })?;
-
-        let query_bins = index_reference_sequence.query(start, end);
-
-        let chunks: Vec<_> = query_bins
-            .iter()
-            .flat_map(|bin| bin.chunks())
-            .cloned()
-            .collect();
+    /// Returns an iterator over records that intersect any of the given regions.
+    ///
+    /// The chunks of all regions are merged before reading, so each BGZF block is read at most
+    /// once. Records are returned in file order, and a record that intersects more than one region
+    /// is only returned once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::fs::File;
+    /// use noodles::Region;
+    /// use noodles_bam::{self as bam, bai};
+    /// use noodles_sam as sam;
+    ///
+    /// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+    /// let header: sam::Header = reader.read_header()?.parse()?;
+    ///
+    /// let reference_sequences = header.reference_sequences();
+    /// let index = bai::read("sample.bam.bai")?;
+    /// let regions = [
+    ///     Region::mapped("sq0", 17711, 28657),
+    ///     Region::mapped("sq1", 8, 13),
+    /// ];
+    /// let query = reader.query_regions(&reference_sequences, &index, &regions)?;
+    ///
+    /// for result in query {
+    ///     let record = result?;
+    ///     println!("{:?}", record);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn query_regions<I>(
+        &mut self,
+        reference_sequences: &ReferenceSequences,
+        index: &I,
+        regions: &[Region],
+    ) -> io::Result<Query<'_, R>>
+    where
+        I: BinningIndex,
+    {
+        let mut chunks = Vec::new();
+        let mut intervals = Vec::with_capacity(regions.len());
+
+        for region in regions {
+            let (i, start, end) = resolve_region(reference_sequences, region)?;
+            chunks.extend(index.query(i, start, end)?);
+            intervals.push((i, start, end));
+        }
 
-        let min_offset = index_reference_sequence.min_offset(start);
-        let merged_chunks = bai::optimize_chunks(&chunks, min_offset);
+        let merged_chunks = csi::optimize_chunks(&chunks, VirtualPosition::default());
+        let block_merged_chunks = merge_chunks_by_block(merged_chunks);
 
-        Ok(Query::new(self, merged_chunks, i, start, end))
+        Ok(Query::new(self, block_merged_chunks, intervals))
     }
 
     /// Returns an iterator of unmapped records after querying for the unmapped region.
@@ -405,6 +445,25 @@ fn bytes_with_nul_to_string(buf: &[u8]) -> io::Result<String> {
         })
 }
 
+// Merges sorted, non-overlapping chunks where a chunk starts in the same BGZF block that the
+// previous chunk ends in. This avoids seeking to and decompressing the same block more than once.
+fn merge_chunks_by_block(chunks: Vec<Chunk>) -> Vec<Chunk> {
+    let mut merged_chunks: Vec<Chunk> = Vec::with_capacity(chunks.len());
+
+    for chunk in chunks {
+        if let Some(last_chunk) = merged_chunks.last_mut() {
+            if chunk.start().compressed() <= last_chunk.end().compressed() {
+                *last_chunk = Chunk::new(last_chunk.start(), chunk.end());
+                continue;
+            }
+        }
+
+        merged_chunks.push(chunk);
+    }
+
+    merged_chunks
+}
+
 fn resolve_region(
     reference_sequences: &ReferenceSequences,
     region: &Region,
@@ -429,3 +488,162 @@ fn resolve_region(
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, io::Cursor};
+
+    use noodles_sam as sam;
+
+    use crate::Writer;
+
+    use super::*;
+
+    fn build_record(
+        reference_sequence_name: &str,
+        position: i32,
+    ) -> Result<sam::Record, Box<dyn std::error::Error>> {
+        Ok(sam::Record::builder()
+            .set_flags(sam::record::Flags::empty())
+            .set_reference_sequence_name(reference_sequence_name.parse()?)
+            .set_position(sam::record::Position::try_from(position)?)
+            .set_cigar("4M".parse()?)
+            .build())
+    }
+
+    #[test]
+    fn test_query_with_csi() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq1"), 13))
+            .build();
+        let reference_sequences = header.reference_sequences();
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(reference_sequences)?;
+        writer.write_sam_record(reference_sequences, &build_record("sq0", 1)?)?;
+        writer.write_sam_record(reference_sequences, &build_record("sq0", 3)?)?;
+        writer.write_sam_record(reference_sequences, &build_record("sq1", 5)?)?;
+        let (data, _) = writer.finish()?;
+
+        let mut reader = Reader::new(Cursor::new(data));
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        let mut indexer = csi::index::Indexer::new(14, 6)?;
+        let mut record = Record::default();
+        let mut start_position = reader.virtual_position();
+
+        while reader.read_record(&mut record)? != 0 {
+            let end_position = reader.virtual_position();
+
+            let reference_sequence_id = record
+                .reference_sequence_id()
+                .map(|id| i32::from(id) as usize);
+            let start = record.position().map(i32::from).expect("missing position");
+            let end = start + record.cigar().reference_len()? as i32 - 1;
+
+            indexer.add_record(
+                reference_sequence_id,
+                start,
+                end,
+                !record.flags().is_unmapped(),
+                Chunk::new(start_position, end_position),
+            )?;
+
+            start_position = end_position;
+        }
+
+        let index = indexer.build(reference_sequences.len());
+
+        let region = Region::mapped("sq0", 1, 2);
+        let positions: Vec<_> = reader
+            .query(reference_sequences, &index, &region)?
+            .map(|result| result.map(|r| r.position().map(i32::from)))
+            .collect::<io::Result<_>>()?;
+        assert_eq!(positions, [Some(1)]);
+
+        let region = Region::mapped("sq1", 1, 13);
+        let positions: Vec<_> = reader
+            .query(reference_sequences, &index, &region)?
+            .map(|result| result.map(|r| r.position().map(i32::from)))
+            .collect::<io::Result<_>>()?;
+        assert_eq!(positions, [Some(5)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_regions() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 34))
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq1"), 13))
+            .build();
+        let reference_sequences = header.reference_sequences();
+
+        let mut writer = Writer::builder(Vec::new()).set_build_index(true).build();
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(reference_sequences)?;
+
+        for &(name, position) in &[("sq0", 1), ("sq0", 8), ("sq0", 21), ("sq1", 5)] {
+            writer.write_sam_record(reference_sequences, &build_record(name, position)?)?;
+        }
+
+        let (data, index) = writer.finish()?;
+        let index = index.expect("missing index");
+
+        let mut reader = Reader::new(Cursor::new(data));
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        // The first two regions both intersect the record at sq0:8-11.
+        let regions = [
+            Region::mapped("sq1", 1, 13),
+            Region::mapped("sq0", 2, 9),
+            Region::mapped("sq0", 10, 12),
+        ];
+
+        let records: Vec<_> = reader
+            .query_regions(reference_sequences, &index, &regions)?
+            .map(|result| {
+                result.map(|r| {
+                    (
+                        r.reference_sequence_id().map(i32::from),
+                        r.position().map(i32::from),
+                    )
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        assert_eq!(
+            records,
+            [(Some(0), Some(1)), (Some(0), Some(8)), (Some(1), Some(5))]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_chunks_by_block() {
+        fn vp(compressed: u64, uncompressed: u16) -> VirtualPosition {
+            VirtualPosition::from((compressed << 16) | u64::from(uncompressed))
+        }
+
+        let chunks = vec![
+            Chunk::new(vp(0, 8), vp(0, 13)),
+            Chunk::new(vp(0, 21), vp(55, 5)),
+            Chunk::new(vp(55, 34), vp(89, 0)),
+            Chunk::new(vp(89, 0), vp(89, 8)),
+            Chunk::new(vp(144, 3), vp(233, 0)),
+        ];
+
+        let actual = merge_chunks_by_block(chunks);
+        let expected = [
+            Chunk::new(vp(0, 8), vp(89, 8)),
+            Chunk::new(vp(144, 3), vp(233, 0)),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,8 +1,9 @@
 use std::io::{self, Read, Seek};
 
 use noodles_bgzf::VirtualPosition;
+use noodles_csi::index::reference_sequence::bin::Chunk;
 
-use crate::{bai::index::reference_sequence::bin::Chunk, Record};
+use crate::Record;
 
 use super::Reader;
 
@@ -12,18 +13,22 @@ enum State {
     End,
 }
 
-/// An iterator over records of a BAM reader that intersect a given region.
+// A resolved region: (reference sequence ID, start, end).
+pub(crate) type Interval = (usize, i32, i32);
+
+/// An iterator over records of a BAM reader that intersect a given region or regions.
+///
+/// Records are returned in file order. A record that intersects more than one region is only
+/// returned once.
 ///
-/// This is created by calling [`Reader::query`].
+/// This is created by calling [`Reader::query`] or [`Reader::query_regions`].
 pub struct Query<'a, R>
 where
     R: Read + Seek,
 {
     reader: &'a mut Reader<R>,
     chunks: Vec<Chunk>,
-    reference_sequence_id: usize,
-    start: i32,
-    end: i32,
+    intervals: Vec<Interval>,
     i: usize,
     state: State,
     record: Record,
@@ -36,16 +41,12 @@ where
     pub(crate) fn new(
         reader: &'a mut Reader<R>,
         chunks: Vec<Chunk>,
-        reference_sequence_id: usize,
-        start: i32,
-        end: i32,
+        intervals: Vec<Interval>,
     ) -> Self {
         Self {
             reader,
             chunks,
-            reference_sequence_id,
-            start,
-            end,
+            intervals,
             i: 0,
             state: State::Seek,
             record: Record::default(),
@@ -65,6 +66,21 @@ where
         Ok(Some(chunk.end()))
     }
 
+    fn intersects(&self, record: &Record) -> io::Result<bool> {
+        let reference_sequence_id = match record.reference_sequence_id() {
+            Some(id) => i32::from(id) as usize,
+            None => return Ok(false),
+        };
+
+        let record_start = record.position().map(i32::from).expect("missing position");
+        let record_reference_len = record.cigar().reference_len()? as i32;
+        let record_end = record_start + record_reference_len - 1;
+
+        Ok(self.intervals.iter().any(|&(id, start, end)| {
+            id == reference_sequence_id && in_interval(record_start, record_end, start, end)
+        }))
+    }
+
     fn read_record(&mut self) -> Option<io::Result<Record>> {
         match self.reader.read_record(&mut self.record) {
             Ok(0) => None,
@@ -97,33 +113,11 @@ where
                         }
 
                         match result {
-                            Ok(record) => {
-                                if let Some(reference_sequence_id) = record.reference_sequence_id()
-                                {
-                                    let reference_sequence_id =
-                                        i32::from(reference_sequence_id) as usize;
-
-                                    let record_start =
-                                        record.position().map(i32::from).expect("missing position");
-                                    let record_reference_len = match record.cigar().reference_len()
-                                    {
-                                        Ok(len) => len as i32,
-                                        Err(e) => return Some(Err(e)),
-                                    };
-                                    let record_end = record_start + record_reference_len - 1;
-
-                                    if reference_sequence_id == self.reference_sequence_id
-                                        && in_interval(
-                                            record_start,
-                                            record_end,
-                                            self.start,
-                                            self.end,
-                                        )
-                                    {
-                                        return Some(Ok(record));
-                                    }
-                                }
-                            }
+                            Ok(record) => match self.intersects(&record) {
+                                Ok(true) => return Some(Ok(record)),
+                                Ok(false) => {}
+                                Err(e) => return Some(Err(e)),
+                            },
                             Err(e) => return Some(Err(e)),
                         }
                     }
@@ -15,12 +15,17 @@ pub use self::{
 use std::{
     convert::TryFrom,
     ffi::{self, CStr},
-    fmt, mem,
-    ops::{Deref, DerefMut},
+    fmt, io, mem,
+    ops::{Deref, DerefMut, Range},
 };
 
 use byteorder::{ByteOrder, LittleEndian};
-use noodles_sam as sam;
+use noodles_sam::{self as sam, record::data::field::Tag};
+
+use crate::writer::record::{
+    encode_cigar_op, region_to_bin, write_cigar, write_data_field, write_qual, write_seq,
+    NULL_QUALITY_SCORE, UNMAPPED_BIN,
+};
 
 pub(crate) const UNMAPPED_POSITION: i32 = -1;
 
@@ -43,7 +48,9 @@ pub(crate) const UNMAPPED_POSITION: i32 = -1;
 ///
 /// Additionally, it encodes the BAM index bin (`bin`).
 ///
-/// A `bam::Record` wraps a raw byte buffer, and the fields should be considered immutable.
+/// A `bam::Record` wraps a raw byte buffer. Fields can be edited in place using the `set_*`
+/// methods and the data field methods, which keep the length fields (`l_read_name`, `n_cigar_op`
+/// and `l_seq`) and the bin consistent with the record.
 #[derive(Clone, Eq, PartialEq)]
 pub struct Record(Vec<u8>);
 
@@ -241,6 +248,10 @@ impl Record {
 
     /// Returns the CIGAR operations that describe how the read was mapped.
     ///
+    /// If the CIGAR is the placeholder `kSmN`, where `k` is the read length and `m` is the
+    /// reference length, and the record has a `CG` data field, the CIGAR in the data field is
+    /// returned instead. This is how BAM stores CIGARs with more than 65535 operations.
+    ///
     /// # Examples
     ///
     /// ```
@@ -249,10 +260,24 @@ impl Record {
     /// assert!(record.cigar().is_empty());
     /// ```
     pub fn cigar(&self) -> Cigar<'_> {
-        let offset = 32 + (self.l_read_name() as usize);
-        let len = mem::size_of::<u32>() * (self.n_cigar_op() as usize);
-        let bytes = &self.0[offset..offset + len];
-        Cigar::new(bytes)
+        match self.cigar_data_field() {
+            Some(bytes) => Cigar::new(bytes),
+            None => Cigar::new(&self.0[self.cigar_range()]),
+        }
+    }
+
+    // Returns the raw CIGAR in the `CG` data field if the CIGAR is a placeholder.
+    pub(crate) fn cigar_data_field(&self) -> Option<&[u8]> {
+        let bytes = &self.0[self.cigar_range()];
+
+        if !is_cigar_placeholder(bytes, self.l_seq()) {
+            return None;
+        }
+
+        match self.data().get(&Tag::Cigar) {
+            Some(Ok(data::field::ValueRef::UInt32Array(ops))) => Some(ops.as_bytes()),
+            _ => None,
+        }
     }
 
     /// Returns the bases in the sequence of this record.
@@ -265,12 +290,7 @@ impl Record {
     /// assert!(record.sequence().is_empty());
     /// ```
     pub fn sequence(&self) -> Sequence<'_> {
-        let offset = 32
-            + (self.l_read_name() as usize)
-            + mem::size_of::<u32>() * (self.n_cigar_op() as usize);
-        let len = ((self.l_seq() + 1) / 2) as usize;
-
-        let bytes = &self.0[offset..offset + len];
+        let bytes = &self.0[self.sequence_range()];
         let base_count = self.l_seq() as usize;
         Sequence::new(bytes, base_count)
     }
@@ -285,15 +305,7 @@ impl Record {
     /// assert!(record.quality_scores().is_empty());
     /// ```
     pub fn quality_scores(&self) -> QualityScores<'_> {
-        let l_seq = self.l_seq();
-
-        let offset = 32
-            + (self.l_read_name() as usize)
-            + mem::size_of::<u32>() * (self.n_cigar_op() as usize)
-            + ((self.l_seq() + 1) / 2) as usize;
-        let len = l_seq as usize;
-
-        let bytes = &self.0[offset..offset + len];
+        let bytes = &self.0[self.quality_scores_range()];
         QualityScores::new(bytes)
     }
 
@@ -307,20 +319,499 @@ impl Record {
     /// assert!(record.data().is_empty());
     /// ```
     pub fn data(&self) -> Data<'_> {
-        let l_seq = self.l_seq();
+        let bytes = &self.0[self.data_range()];
+        Data::new(bytes)
+    }
 
-        let offset = 32
-            + (self.l_read_name() as usize)
-            + mem::size_of::<u32>() * (self.n_cigar_op() as usize)
-            + ((self.l_seq() + 1) / 2) as usize
-            + l_seq as usize;
-        let len = self.block_size() as usize;
+    /// Sets the reference sequence ID of this record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use noodles_bam::{self as bam, record::ReferenceSequenceId};
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_reference_sequence_id(Some(ReferenceSequenceId::try_from(1)?));
+    ///
+    /// assert_eq!(record.reference_sequence_id().map(i32::from), Some(1));
+    /// # Ok::<(), bam::record::reference_sequence_id::TryFromIntError>(())
+    /// ```
+    pub fn set_reference_sequence_id(
+        &mut self,
+        reference_sequence_id: Option<ReferenceSequenceId>,
+    ) {
+        let id = reference_sequence_id
+            .map(i32::from)
+            .unwrap_or(reference_sequence_id::UNMAPPED);
+
+        LittleEndian::write_i32(&mut self.0[0..4], id);
+    }
 
-        let bytes = &self.0[offset..len];
-        Data::new(bytes)
+    /// Sets the start position of this record.
+    ///
+    /// This also updates the bin of the record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use noodles_bam as bam;
+    /// use noodles_sam as sam;
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_position(Some(sam::record::Position::try_from(8)?))?;
+    ///
+    /// assert_eq!(record.position().map(i32::from), Some(8));
+    /// assert_eq!(record.bin(), 4681);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_position(&mut self, position: Option<sam::record::Position>) -> io::Result<()> {
+        let pos = position
+            .map(|p| i32::from(p) - 1)
+            .unwrap_or(UNMAPPED_POSITION);
+
+        LittleEndian::write_i32(&mut self.0[4..8], pos);
+
+        self.update_bin()
+    }
+
+    /// Sets the mapping quality of this record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// use noodles_sam as sam;
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_mapping_quality(sam::record::MappingQuality::from(13));
+    ///
+    /// assert_eq!(u8::from(record.mapping_quality()), 13);
+    /// ```
+    pub fn set_mapping_quality(&mut self, mapping_quality: sam::record::MappingQuality) {
+        self.0[9] = u8::from(mapping_quality);
+    }
+
+    /// Sets the SAM flags of this record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// use noodles_sam as sam;
+    ///
+    /// let mut record = bam::Record::default();
+    /// let flags = record.flags() | sam::record::Flags::DUPLICATE;
+    /// record.set_flags(flags);
+    ///
+    /// assert!(record.flags().is_duplicate());
+    /// ```
+    pub fn set_flags(&mut self, flags: sam::record::Flags) {
+        LittleEndian::write_u16(&mut self.0[14..16], u16::from(flags));
+    }
+
+    /// Sets the reference sequence ID of the mate of this record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use noodles_bam::{self as bam, record::ReferenceSequenceId};
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_mate_reference_sequence_id(Some(ReferenceSequenceId::try_from(1)?));
+    ///
+    /// assert_eq!(record.mate_reference_sequence_id().map(i32::from), Some(1));
+    /// # Ok::<(), bam::record::reference_sequence_id::TryFromIntError>(())
+    /// ```
+    pub fn set_mate_reference_sequence_id(
+        &mut self,
+        mate_reference_sequence_id: Option<ReferenceSequenceId>,
+    ) {
+        let id = mate_reference_sequence_id
+            .map(i32::from)
+            .unwrap_or(reference_sequence_id::UNMAPPED);
+
+        LittleEndian::write_i32(&mut self.0[20..24], id);
+    }
+
+    /// Sets the start position of the mate of this record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use noodles_bam as bam;
+    /// use noodles_sam as sam;
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_mate_position(Some(sam::record::Position::try_from(13)?));
+    ///
+    /// assert_eq!(record.mate_position().map(i32::from), Some(13));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_mate_position(&mut self, mate_position: Option<sam::record::Position>) {
+        let pos = mate_position
+            .map(|p| i32::from(p) - 1)
+            .unwrap_or(UNMAPPED_POSITION);
+
+        LittleEndian::write_i32(&mut self.0[24..28], pos);
+    }
+
+    /// Sets the template length of this record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let mut record = bam::Record::default();
+    /// record.set_template_length(144);
+    /// assert_eq!(record.template_length(), 144);
+    /// ```
+    pub fn set_template_length(&mut self, template_length: i32) {
+        LittleEndian::write_i32(&mut self.0[28..32], template_length);
+    }
+
+    /// Sets the read name of this record.
+    ///
+    /// The read name, including the NUL terminator, can be at most 255 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{ffi::CString, io};
+    /// use noodles_bam as bam;
+    ///
+    /// let mut record = bam::Record::default();
+    /// let read_name = CString::new("r0").map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    /// record.set_read_name(&read_name)?;
+    ///
+    /// assert_eq!(record.read_name().map(|name| name.to_bytes()), Ok(&b"r0"[..]));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_read_name(&mut self, read_name: &CStr) -> io::Result<()> {
+        let buf = read_name.to_bytes_with_nul();
+
+        let l_read_name = u8::try_from(buf.len()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid read name length: expected <= 255, got {}",
+                    buf.len()
+                ),
+            )
+        })?;
+
+        let range = self.read_name_range();
+        self.0.splice(range, buf.iter().copied());
+        self.0[8] = l_read_name;
+
+        Ok(())
+    }
+
+    /// Sets the CIGAR operations of this record.
+    ///
+    /// This also updates the bin of the record.
+    ///
+    /// If the CIGAR has more than 65535 operations, a placeholder CIGAR is written, and the real
+    /// CIGAR is stored in the `CG` data field. The placeholder uses the read length, so the
+    /// sequence should be set before the CIGAR.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_cigar(&"36M".parse()?)?;
+    ///
+    /// assert_eq!(record.cigar().to_string(), "36M");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_cigar(&mut self, cigar: &sam::record::Cigar) -> io::Result<()> {
+        if cigar.len() > usize::from(u16::MAX) {
+            use sam::record::cigar::{op::Kind, Op};
+
+            let placeholder = sam::record::Cigar::from(vec![
+                Op::new(Kind::SoftClip, self.l_seq()),
+                Op::new(Kind::Skip, cigar.reference_len()),
+            ]);
+
+            self.set_raw_cigar(&placeholder)?;
+
+            let ops = cigar.iter().copied().map(encode_cigar_op).collect();
+            let field = data::Field::new(Tag::Cigar, data::field::Value::UInt32Array(ops));
+            self.insert_data_field(field)?;
+        } else {
+            self.set_raw_cigar(cigar)?;
+            self.remove_data_field(&Tag::Cigar)?;
+        }
+
+        self.update_bin()
+    }
+
+    fn set_raw_cigar(&mut self, cigar: &sam::record::Cigar) -> io::Result<()> {
+        let n_cigar_op = u16::try_from(cigar.len()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid CIGAR operation count: expected <= {}, got {}",
+                    u16::MAX,
+                    cigar.len()
+                ),
+            )
+        })?;
+
+        let mut buf = Vec::with_capacity(mem::size_of::<u32>() * cigar.len());
+        write_cigar(&mut buf, cigar)?;
+
+        let range = self.cigar_range();
+        self.0.splice(range, buf);
+        LittleEndian::write_u16(&mut self.0[12..14], n_cigar_op);
+
+        Ok(())
+    }
+
+    /// Sets the sequence of this record.
+    ///
+    /// If the length of the sequence changes, the quality scores are reset to missing (`*`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_sequence(&"ACGT".parse()?)?;
+    ///
+    /// assert_eq!(record.sequence().to_string(), "ACGT");
+    /// assert_eq!(*record.quality_scores(), [0xff, 0xff, 0xff, 0xff]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_sequence(&mut self, sequence: &sam::record::Sequence) -> io::Result<()> {
+        let l_seq = u32::try_from(sequence.len())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        let mut buf = Vec::new();
+        write_seq(&mut buf, sequence)?;
+
+        if l_seq != self.l_seq() {
+            let range = self.quality_scores_range();
+            let scores = vec![NULL_QUALITY_SCORE; sequence.len()];
+            self.0.splice(range, scores);
+        }
+
+        let range = self.sequence_range();
+        self.0.splice(range, buf);
+        LittleEndian::write_u32(&mut self.0[16..20], l_seq);
+
+        Ok(())
+    }
+
+    /// Sets the quality scores of this record.
+    ///
+    /// The number of quality scores must be the same as the length of the sequence. If the
+    /// quality scores are empty, they are set to missing (`*`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.set_sequence(&"ACGT".parse()?)?;
+    /// record.set_quality_scores(&"NDLS".parse()?)?;
+    ///
+    /// assert_eq!(*record.quality_scores(), [45, 35, 43, 50]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_quality_scores(
+        &mut self,
+        quality_scores: &sam::record::QualityScores,
+    ) -> io::Result<()> {
+        let l_seq = self.l_seq() as usize;
+
+        let buf = if quality_scores.is_empty() {
+            vec![NULL_QUALITY_SCORE; l_seq]
+        } else if quality_scores.len() == l_seq {
+            let mut buf = Vec::with_capacity(l_seq);
+            write_qual(&mut buf, quality_scores)?;
+            buf
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "quality scores length does not match sequence length",
+            ));
+        };
+
+        let range = self.quality_scores_range();
+        self.0.splice(range, buf);
+
+        Ok(())
+    }
+
+    /// Inserts a data field into this record.
+    ///
+    /// If a field with the same tag already exists, it is replaced in place, and the previous
+    /// field is returned. Otherwise, the field is appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::{self as bam, record::data::{field::Value, Field}};
+    /// use noodles_sam::record::data::field::Tag;
+    ///
+    /// let mut record = bam::Record::default();
+    ///
+    /// let field = Field::new(Tag::ReadGroup, Value::String(String::from("rg0")));
+    /// assert!(record.insert_data_field(field)?.is_none());
+    ///
+    /// let field = Field::new(Tag::ReadGroup, Value::String(String::from("rg1")));
+    /// let previous_field = record.insert_data_field(field)?;
+    /// assert_eq!(
+    ///     previous_field.as_ref().map(|f| f.value()),
+    ///     Some(&Value::String(String::from("rg0")))
+    /// );
+    ///
+    /// let fields: Vec<_> = record.data().fields().collect::<io::Result<_>>()?;
+    /// assert_eq!(fields, [Field::new(Tag::ReadGroup, Value::String(String::from("rg1")))]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn insert_data_field(&mut self, field: data::Field) -> io::Result<Option<data::Field>> {
+        let mut buf = Vec::new();
+        write_data_field(&mut buf, &field)?;
+
+        let data_range = self.data_range();
+
+        match data::find_field(&self.0[data_range.clone()], field.tag())? {
+            Some(range) => {
+                let range = (data_range.start + range.start)..(data_range.start + range.end);
+                let previous_field = parse_data_field(&self.0[range.clone()])?;
+                self.0.splice(range, buf);
+                Ok(Some(previous_field))
+            }
+            None => {
+                self.0.extend(buf);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes the data field with the given tag from this record.
+    ///
+    /// The removed field is returned, if it exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::{self as bam, record::data::{field::Value, Field}};
+    /// use noodles_sam::record::data::field::Tag;
+    ///
+    /// let mut record = bam::Record::default();
+    /// record.insert_data_field(Field::new(Tag::AlignmentHitCount, Value::UInt8(1)))?;
+    ///
+    /// let field = record.remove_data_field(&Tag::AlignmentHitCount)?;
+    /// assert_eq!(field.as_ref().map(|f| f.value()), Some(&Value::UInt8(1)));
+    ///
+    /// assert!(record.data().is_empty());
+    /// assert!(record.remove_data_field(&Tag::AlignmentHitCount)?.is_none());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn remove_data_field(&mut self, tag: &Tag) -> io::Result<Option<data::Field>> {
+        let data_range = self.data_range();
+
+        match data::find_field(&self.0[data_range.clone()], tag)? {
+            Some(range) => {
+                let range = (data_range.start + range.start)..(data_range.start + range.end);
+                let field = parse_data_field(&self.0[range.clone()])?;
+                self.0.drain(range);
+                Ok(Some(field))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn read_name_range(&self) -> Range<usize> {
+        let start = 32;
+        start..start + self.l_read_name() as usize
+    }
+
+    fn cigar_range(&self) -> Range<usize> {
+        let start = self.read_name_range().end;
+        start..start + mem::size_of::<u32>() * (self.n_cigar_op() as usize)
+    }
+
+    fn sequence_range(&self) -> Range<usize> {
+        let start = self.cigar_range().end;
+        start..start + self.l_seq().div_ceil(2) as usize
+    }
+
+    fn quality_scores_range(&self) -> Range<usize> {
+        let start = self.sequence_range().end;
+        start..start + self.l_seq() as usize
+    }
+
+    fn data_range(&self) -> Range<usize> {
+        let start = self.quality_scores_range().end;
+        start..self.0.len()
+    }
+
+    fn update_bin(&mut self) -> io::Result<()> {
+        let bin = self.calculate_bin()?;
+        LittleEndian::write_u16(&mut self.0[10..12], bin);
+        Ok(())
+    }
+
+    // Calculates the bin from the position and CIGAR of this record.
+    pub(crate) fn calculate_bin(&self) -> io::Result<u16> {
+        match self.position() {
+            Some(position) => {
+                let reference_len = self.cigar().reference_len()?;
+                Ok(calculate_bin(position, reference_len))
+            }
+            None => Ok(UNMAPPED_BIN),
+        }
     }
 }
 
+// § 4.2.2 `N_CIGAR_OP` field (2020-04-30)
+//
+// A placeholder CIGAR is `kSmN`, where `k` is the read length and `m` is the reference length.
+fn is_cigar_placeholder(raw_cigar: &[u8], read_len: u32) -> bool {
+    const SOFT_CLIP: u32 = 4;
+    const SKIP: u32 = 3;
+
+    if raw_cigar.len() != 2 * mem::size_of::<u32>() {
+        return false;
+    }
+
+    let first_op = LittleEndian::read_u32(&raw_cigar[0..4]);
+    let second_op = LittleEndian::read_u32(&raw_cigar[4..8]);
+
+    first_op & 0x0f == SOFT_CLIP && first_op >> 4 == read_len && second_op & 0x0f == SKIP
+}
+
+// § 4.2.1 BIN field calculation (2020-04-30)
+//
+// Records without a reference length, e.g., unmapped records with a position, are treated as
+// having a length of 1.
+pub(crate) fn calculate_bin(position: sam::record::Position, reference_len: u32) -> u16 {
+    // 0-based, [start, end)
+    let start = i32::from(position) - 1;
+    let end = start + (reference_len as i32).max(1);
+    region_to_bin(start, end) as u16
+}
+
+fn parse_data_field(buf: &[u8]) -> io::Result<data::Field> {
+    data::Reader::new(buf)
+        .fields()
+        .next()
+        .unwrap_or_else(|| Err(io::Error::from(io::ErrorKind::UnexpectedEof)))
+}
+
 impl Default for Record {
     fn default() -> Self {
         Self::from(vec![
@@ -387,7 +878,7 @@ impl From<Vec<u8>> for Record {
 mod tests {
     use std::{
         ffi::CString,
-        io::{self, BufWriter, Write},
+        io::{BufWriter, Write},
     };
 
     use byteorder::WriteBytesExt;
@@ -512,6 +1003,19 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_sequence_range() -> io::Result<()> {
+        let record = build_record()?;
+        assert_eq!(record.sequence_range(), 46..48);
+
+        let mut buf = record.to_vec();
+        LittleEndian::write_u32(&mut buf[16..20], u32::MAX);
+        let record = Record::from(buf);
+        assert_eq!(record.sequence_range(), 46..46 + (1 << 31));
+
+        Ok(())
+    }
+
     #[test]
     fn test_mate_reference_sequence_id() -> io::Result<()> {
         let record = build_record()?;
@@ -573,4 +1077,189 @@ mod tests {
         assert_eq!(*record.data(), expected);
         Ok(())
     }
+
+    #[test]
+    fn test_set_read_name() -> io::Result<()> {
+        let mut record = build_record()?;
+
+        let read_name =
+            CString::new("r0").map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        record.set_read_name(&read_name)?;
+
+        assert_eq!(record.l_read_name(), 3);
+        assert_eq!(
+            record.read_name().map(|name| name.to_bytes()),
+            Ok(&b"r0"[..])
+        );
+        assert_eq!(*record.cigar(), [0x40, 0x00, 0x00, 0x00]);
+        assert_eq!(*record.sequence(), [0x18, 0x42]);
+        assert_eq!(record.block_size(), 57);
+
+        let read_name = CString::new(vec![b'n'; 255])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        assert!(matches!(
+            record.set_read_name(&read_name),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_cigar() -> Result<(), Box<dyn std::error::Error>> {
+        let mut record = build_record()?;
+        record.set_cigar(&"2M16384N2M".parse()?)?;
+
+        assert_eq!(record.n_cigar_op(), 3);
+        assert_eq!(record.cigar().to_string(), "2M16384N2M");
+        assert_eq!(*record.sequence(), [0x18, 0x42]);
+        assert_eq!(*record.quality_scores(), [0x1f, 0x1d, 0x1e, 0x20]);
+        // [61061, 77449) spans two 16 kbp bins.
+        assert_eq!(record.bin(), 585);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_cigar_with_long_cigar() -> Result<(), Box<dyn std::error::Error>> {
+        use sam::record::cigar::{op::Kind, Op};
+
+        let mut ops = vec![Op::new(Kind::Match, 1)];
+        ops.extend((0..u16::MAX).map(|_| Op::new(Kind::Deletion, 1)));
+        ops.push(Op::new(Kind::Match, 3));
+        let cigar = sam::record::Cigar::from(ops);
+
+        let mut record = build_record()?;
+        record.set_cigar(&cigar)?;
+
+        assert_eq!(record.n_cigar_op(), 2);
+        assert_eq!(sam::record::Cigar::try_from(record.cigar())?, cigar);
+        assert!(record.data().get(&Tag::Cigar).is_some());
+
+        record.set_cigar(&"4M".parse()?)?;
+
+        assert_eq!(record.n_cigar_op(), 1);
+        assert_eq!(record.cigar().to_string(), "4M");
+        assert!(record.data().get(&Tag::Cigar).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_position() -> Result<(), Box<dyn std::error::Error>> {
+        let mut record = build_record()?;
+
+        record.set_position(Some(sam::record::Position::try_from(16384)?))?;
+        assert_eq!(record.position().map(i32::from), Some(16384));
+        assert_eq!(record.bin(), 585);
+
+        record.set_position(None)?;
+        assert!(record.position().is_none());
+        assert_eq!(record.bin(), 4680);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_sequence() -> Result<(), Box<dyn std::error::Error>> {
+        let mut record = build_record()?;
+
+        record.set_sequence(&"TGCA".parse()?)?;
+        assert_eq!(record.sequence().to_string(), "TGCA");
+        assert_eq!(*record.quality_scores(), [0x1f, 0x1d, 0x1e, 0x20]);
+
+        record.set_sequence(&"ACG".parse()?)?;
+        assert_eq!(record.l_seq(), 3);
+        assert_eq!(record.sequence().to_string(), "ACG");
+        assert_eq!(*record.quality_scores(), [0xff, 0xff, 0xff]);
+        assert_eq!(record.data().len(), 12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_quality_scores() -> Result<(), Box<dyn std::error::Error>> {
+        let mut record = build_record()?;
+
+        record.set_quality_scores(&"NDLS".parse()?)?;
+        assert_eq!(*record.quality_scores(), [45, 35, 43, 50]);
+
+        record.set_quality_scores(&sam::record::QualityScores::default())?;
+        assert_eq!(*record.quality_scores(), [0xff, 0xff, 0xff, 0xff]);
+
+        assert!(matches!(
+            record.set_quality_scores(&"NDL".parse()?),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_data_field() -> io::Result<()> {
+        use data::{field::Value, Field};
+
+        let mut record = build_record()?;
+
+        let previous_field =
+            record.insert_data_field(Field::new(Tag::EditDistance, Value::UInt8(2)))?;
+        assert_eq!(
+            previous_field,
+            Some(Field::new(Tag::EditDistance, Value::UInt8(0)))
+        );
+
+        record.insert_data_field(Field::new(
+            Tag::ReadGroup,
+            Value::String(String::from("rg0")),
+        ))?;
+
+        let fields: Vec<_> = record.data().fields().collect::<io::Result<_>>()?;
+
+        assert_eq!(
+            fields,
+            [
+                Field::new(Tag::EditDistance, Value::UInt8(2)),
+                Field::new(Tag::Program, Value::String(String::from("SNAP"))),
+                Field::new(Tag::ReadGroup, Value::String(String::from("rg0"))),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_data_field() -> io::Result<()> {
+        use data::{field::Value, Field};
+
+        let mut record = build_record()?;
+
+        let field = record.remove_data_field(&Tag::EditDistance)?;
+        assert_eq!(field, Some(Field::new(Tag::EditDistance, Value::UInt8(0))));
+
+        let fields: Vec<_> = record.data().fields().collect::<io::Result<_>>()?;
+        assert_eq!(
+            fields,
+            [Field::new(
+                Tag::Program,
+                Value::String(String::from("SNAP"))
+            )]
+        );
+
+        assert!(record.remove_data_field(&Tag::ReadGroup)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_bin() -> Result<(), sam::record::position::TryFromIntError> {
+        use sam::record::Position;
+
+        assert_eq!(calculate_bin(Position::try_from(1)?, 4), 4681);
+        assert_eq!(calculate_bin(Position::try_from(16384)?, 1), 4681);
+        assert_eq!(calculate_bin(Position::try_from(16385)?, 1), 4682);
+        assert_eq!(calculate_bin(Position::try_from(16384)?, 0), 4681);
+        assert_eq!(calculate_bin(Position::try_from(16384)?, 2), 585);
+
+        Ok(())
+    }
 }
@@ -128,11 +128,21 @@ impl Record {
             builder = builder.set_quality_scores(quality_scores);
         }
 
-        let data = self
+        let mut data: sam::record::Data = self
             .data()
             .try_into()
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
+        // The real CIGAR was read from the `CG` data field, so it is dropped here.
+        if self.cigar_data_field().is_some() {
+            data = data
+                .iter()
+                .filter(|field| field.tag() != &sam::record::data::field::Tag::Cigar)
+                .cloned()
+                .collect::<Vec<_>>()
+                .into();
+        }
+
         builder = builder.set_data(data);
 
         Ok(builder.build())
@@ -271,4 +281,43 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_try_from_sam_record_with_long_cigar() -> Result<(), Box<dyn std::error::Error>> {
+        use sam::record::{
+            cigar::{op, Op},
+            data::field::Tag,
+        };
+
+        let ops: Vec<_> = (0..=u16::MAX)
+            .flat_map(|_| vec![Op::new(op::Kind::Match, 1), Op::new(op::Kind::Deletion, 1)])
+            .collect();
+        let cigar = sam::record::Cigar::from(ops);
+        let read_len = usize::from(u16::MAX) + 1;
+        let sequence: sam::record::Sequence = "A".repeat(read_len).parse()?;
+        let quality_scores: sam::record::QualityScores = "I".repeat(read_len).parse()?;
+
+        let reference_sequences = build_reference_sequences();
+
+        let sam_record = sam::Record::builder()
+            .set_read_name("r0".parse()?)
+            .set_flags(sam::record::Flags::empty())
+            .set_reference_sequence_name("sq1".parse()?)
+            .set_position(sam::record::Position::try_from(1)?)
+            .set_cigar(cigar.clone())
+            .set_sequence(sequence)
+            .set_quality_scores(quality_scores)
+            .build();
+
+        let bam_record = Record::try_from_sam_record(&reference_sequences, &sam_record)?;
+
+        assert_eq!(bam_record.n_cigar_op(), 2);
+        assert_eq!(sam::record::Cigar::try_from(bam_record.cigar())?, cigar);
+        assert!(bam_record.data().get(&Tag::Cigar).is_some());
+
+        let actual = bam_record.try_into_sam_record(&reference_sequences)?;
+        assert_eq!(actual, sam_record);
+
+        Ok(())
+    }
 }
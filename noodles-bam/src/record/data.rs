@@ -5,11 +5,19 @@ pub mod reader;
 
 pub use self::{field::Field, reader::Reader};
 
-use std::{convert::TryFrom, error, fmt, ops::Deref};
+use std::{
+    convert::TryFrom,
+    error, fmt, io, mem,
+    ops::{Deref, Range},
+};
 
-use noodles_sam as sam;
+use byteorder::{ByteOrder, LittleEndian};
+use noodles_sam::{self as sam, record::data::field::Tag};
 
-use self::reader::Fields;
+use self::{
+    field::value::{Subtype, Type},
+    reader::Fields,
+};
 
 /// BAM record data.
 ///
@@ -66,6 +74,40 @@ impl<'a> Data<'a> {
         let reader = Reader::new(self.0);
         reader.fields()
     }
+
+    /// Returns the value of the field with the given tag.
+    ///
+    /// Preceding fields are skipped using their type codes rather than being parsed, and only
+    /// the matching value is decoded. Strings are borrowed, and arrays are returned as typed
+    /// views over the raw data without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::record::{data::field::ValueRef, Data};
+    /// use noodles_sam::record::data::field::Tag;
+    ///
+    /// // NH:i:1  RG:Z:rg0
+    /// let raw_data = [
+    ///     0x4e, 0x48, 0x69, 0x01, 0x00, 0x00, 0x00,
+    ///     0x52, 0x47, 0x5a, 0x72, 0x67, 0x30, 0x00,
+    /// ];
+    /// let data = Data::new(&raw_data);
+    ///
+    /// assert_eq!(data.get(&Tag::ReadGroup).transpose()?, Some(ValueRef::String("rg0")));
+    /// assert!(data.get(&Tag::Comment).is_none());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn get(&self, tag: &Tag) -> Option<io::Result<field::ValueRef<'a>>> {
+        let data = self.0;
+
+        match find_field(data, tag) {
+            Ok(Some(range)) => Some(field::value_ref::decode(&data[range])),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
 }
 
 impl<'a> Deref for Data<'a> {
@@ -82,6 +124,82 @@ impl<'a> fmt::Debug for Data<'a> {
     }
 }
 
+// Finds the byte range of the field with the given tag in raw data.
+//
+// Values are skipped using their type codes rather than being parsed.
+pub(crate) fn find_field(data: &[u8], tag: &Tag) -> io::Result<Option<Range<usize>>> {
+    let raw_tag = tag.as_ref().as_bytes();
+    let mut start = 0;
+
+    while start < data.len() {
+        let end = start + field_len(&data[start..])?;
+
+        if &data[start..start + 2] == raw_tag {
+            return Ok(Some(start..end));
+        }
+
+        start = end;
+    }
+
+    Ok(None)
+}
+
+// Returns the length of the raw field at the start of the given buffer.
+fn field_len(buf: &[u8]) -> io::Result<usize> {
+    // tag (2) + val_type (1)
+    const HEADER_LEN: usize = 3;
+
+    let ty = buf
+        .get(2)
+        .copied()
+        .ok_or_else(unexpected_eof)
+        .and_then(|b| {
+            Type::try_from(b).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })?;
+
+    let value_len = match ty {
+        Type::Char | Type::Int8 | Type::UInt8 => mem::size_of::<u8>(),
+        Type::Int16 | Type::UInt16 => mem::size_of::<u16>(),
+        Type::Int32 | Type::UInt32 | Type::Float => mem::size_of::<u32>(),
+        Type::String | Type::Hex => buf[HEADER_LEN..]
+            .iter()
+            .position(|&b| b == 0)
+            .map(|i| i + 1)
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "string is not NUL-terminated")
+            })?,
+        Type::Array => {
+            let header = buf
+                .get(HEADER_LEN..HEADER_LEN + 5)
+                .ok_or_else(unexpected_eof)?;
+
+            let subtype = Subtype::try_from(header[0])
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let n = LittleEndian::read_u32(&header[1..]) as usize;
+
+            let size = match subtype {
+                Subtype::Int8 | Subtype::UInt8 => mem::size_of::<u8>(),
+                Subtype::Int16 | Subtype::UInt16 => mem::size_of::<u16>(),
+                Subtype::Int32 | Subtype::UInt32 | Subtype::Float => mem::size_of::<u32>(),
+            };
+
+            5 + size * n
+        }
+    };
+
+    let len = HEADER_LEN + value_len;
+
+    if len > buf.len() {
+        Err(unexpected_eof())
+    } else {
+        Ok(len)
+    }
+}
+
+fn unexpected_eof() -> io::Error {
+    io::Error::from(io::ErrorKind::UnexpectedEof)
+}
+
 /// An error returned when BAM data fails to convert to SAM data.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum TryFromDataError {
@@ -187,4 +305,56 @@ mod tests {
             Err(TryFromDataError::OutOfRange(u32::MAX))
         );
     }
+
+    #[test]
+    fn test_find_field() -> io::Result<()> {
+        let raw_data = [
+            0x4e, 0x48, 0x69, 0x01, 0x00, 0x00, 0x00, // NH:i:1
+            0x5a, 0x42, 0x42, 0x53, 0x02, 0x00, 0x00, 0x00, 0x08, 0x00, 0x0d,
+            0x00, // ZB:B:S,8,13
+            0x52, 0x47, 0x5a, 0x72, 0x67, 0x30, 0x00, // RG:Z:rg0
+        ];
+
+        assert_eq!(find_field(&raw_data, &Tag::AlignmentHitCount)?, Some(0..7));
+        assert_eq!(find_field(&raw_data, &Tag::ReadGroup)?, Some(19..26));
+        assert_eq!(find_field(&raw_data, &Tag::Comment)?, None);
+
+        let raw_data = [0x52, 0x47, 0x5a, 0x72, 0x67, 0x30];
+        assert!(find_field(&raw_data, &Tag::Comment).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get() -> io::Result<()> {
+        use field::ValueRef;
+
+        let raw_data = [
+            0x4e, 0x48, 0x69, 0x01, 0x00, 0x00, 0x00, // NH:i:1
+            0x5a, 0x42, 0x42, 0x53, 0x02, 0x00, 0x00, 0x00, 0x08, 0x00, 0x0d,
+            0x00, // ZB:B:S,8,13
+            0x52, 0x47, 0x5a, 0x72, 0x67, 0x30, 0x00, // RG:Z:rg0
+        ];
+        let data = Data::new(&raw_data);
+
+        assert_eq!(
+            data.get(&Tag::AlignmentHitCount).transpose()?,
+            Some(ValueRef::Int32(1))
+        );
+        assert_eq!(
+            data.get(&Tag::ReadGroup).transpose()?,
+            Some(ValueRef::String("rg0"))
+        );
+        assert!(data.get(&Tag::Comment).is_none());
+
+        let tag = "ZB"
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let value = data.get(&tag).transpose()?.expect("missing ZB");
+        let values = value.as_uint16_array().expect("expected u16 array");
+        assert_eq!(values.as_bytes().as_ptr(), raw_data[15..].as_ptr());
+        assert_eq!(values.to_vec(), [8, 13]);
+
+        Ok(())
+    }
 }
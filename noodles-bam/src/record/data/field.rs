@@ -1,8 +1,9 @@
 //! BAM record data field and values.
 
 pub mod value;
+pub mod value_ref;
 
-pub use self::value::Value;
+pub use self::{value::Value, value_ref::ValueRef};
 
 use noodles_sam::record::data::field::Tag;
 
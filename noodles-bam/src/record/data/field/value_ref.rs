@@ -0,0 +1,334 @@
+//! Borrowed BAM record data field value.
+
+pub mod array;
+
+pub use self::array::Array;
+
+use std::{convert::TryFrom, io, str};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use super::{
+    value::{Subtype, Type},
+    Value,
+};
+
+/// A borrowed BAM record data field value.
+///
+/// This is returned by [`crate::record::Data::get`]. Scalar values are decoded from the raw
+/// field, strings are borrowed, and arrays are typed views over the raw data.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValueRef<'a> {
+    /// A character (`A`).
+    Char(char),
+    /// An 8-bit integer (`c`).
+    Int8(i8),
+    /// An 8-bit unsigned integer (`C`).
+    UInt8(u8),
+    /// A 16-bit integer (`s`).
+    Int16(i16),
+    /// A 16-bit unsigned integer (`S`).
+    UInt16(u16),
+    /// A 32-bit integer (`i`).
+    Int32(i32),
+    /// A 32-bit unsigned integer (`I`).
+    UInt32(u32),
+    /// A single-precision floating-point (`f`).
+    Float(f32),
+    /// A string (`Z`).
+    String(&'a str),
+    /// A hex string (`H`).
+    Hex(&'a str),
+    /// An 8-bit integer array (`Bc`).
+    Int8Array(Array<'a, i8>),
+    /// An 8-bit unsigned integer array (`BC`).
+    UInt8Array(Array<'a, u8>),
+    /// A 16-bit integer array (`Bs`).
+    Int16Array(Array<'a, i16>),
+    /// A 16-bit unsigned integer array (`BS`).
+    UInt16Array(Array<'a, u16>),
+    /// A 32-bit integer array (`Bi`).
+    Int32Array(Array<'a, i32>),
+    /// A 32-bit unsigned integer array (`BI`).
+    UInt32Array(Array<'a, u32>),
+    /// A single-precision floating-point array (`Bf`).
+    FloatArray(Array<'a, f32>),
+}
+
+impl<'a> ValueRef<'a> {
+    /// Returns the type of the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::record::data::field::{value::Type, ValueRef};
+    /// assert_eq!(ValueRef::Int32(0).ty(), Type::Int32);
+    /// ```
+    pub fn ty(&self) -> Type {
+        match self {
+            Self::Char(_) => Type::Char,
+            Self::Int8(_) => Type::Int8,
+            Self::UInt8(_) => Type::UInt8,
+            Self::Int16(_) => Type::Int16,
+            Self::UInt16(_) => Type::UInt16,
+            Self::Int32(_) => Type::Int32,
+            Self::UInt32(_) => Type::UInt32,
+            Self::Float(_) => Type::Float,
+            Self::String(_) => Type::String,
+            Self::Hex(_) => Type::Hex,
+            _ => Type::Array,
+        }
+    }
+
+    /// Returns the subtype of the value, if the value is an array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::record::data::field::ValueRef;
+    /// assert!(ValueRef::Int32(0).subtype().is_none());
+    /// ```
+    pub fn subtype(&self) -> Option<Subtype> {
+        match self {
+            Self::Int8Array(_) => Some(Subtype::Int8),
+            Self::UInt8Array(_) => Some(Subtype::UInt8),
+            Self::Int16Array(_) => Some(Subtype::Int16),
+            Self::UInt16Array(_) => Some(Subtype::UInt16),
+            Self::Int32Array(_) => Some(Subtype::Int32),
+            Self::UInt32Array(_) => Some(Subtype::UInt32),
+            Self::FloatArray(_) => Some(Subtype::Float),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a character if it is a character.
+    pub fn as_char(&self) -> Option<char> {
+        match *self {
+            Self::Char(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a 64-bit integer if it is any integer type.
+    ///
+    /// Integer data fields, e.g., `NM` or `AS`, may be stored using any integer type. This
+    /// widens them to a common type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::record::data::field::ValueRef;
+    /// assert_eq!(ValueRef::UInt8(8).as_int(), Some(8));
+    /// assert_eq!(ValueRef::Int16(-2).as_int(), Some(-2));
+    /// assert_eq!(ValueRef::Float(0.0).as_int(), None);
+    /// ```
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            Self::Int8(n) => Some(i64::from(n)),
+            Self::UInt8(n) => Some(i64::from(n)),
+            Self::Int16(n) => Some(i64::from(n)),
+            Self::UInt16(n) => Some(i64::from(n)),
+            Self::Int32(n) => Some(i64::from(n)),
+            Self::UInt32(n) => Some(i64::from(n)),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a single-precision floating-point if it is a single-precision
+    /// floating-point.
+    pub fn as_float(&self) -> Option<f32> {
+        match *self {
+            Self::Float(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a string slice if it is a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::record::data::field::ValueRef;
+    /// assert_eq!(ValueRef::String("noodles").as_str(), Some("noodles"));
+    /// assert_eq!(ValueRef::Int32(0).as_str(), None);
+    /// ```
+    pub fn as_str(&self) -> Option<&'a str> {
+        match *self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a hex string slice if it is a hex string.
+    pub fn as_hex(&self) -> Option<&'a str> {
+        match *self {
+            Self::Hex(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an 8-bit integer array if it is an 8-bit integer array.
+    pub fn as_int8_array(&self) -> Option<Array<'a, i8>> {
+        match *self {
+            Self::Int8Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an 8-bit unsigned integer array if it is an 8-bit unsigned integer
+    /// array.
+    pub fn as_uint8_array(&self) -> Option<Array<'a, u8>> {
+        match *self {
+            Self::UInt8Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a 16-bit integer array if it is a 16-bit integer array.
+    pub fn as_int16_array(&self) -> Option<Array<'a, i16>> {
+        match *self {
+            Self::Int16Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a 16-bit unsigned integer array if it is a 16-bit unsigned integer
+    /// array.
+    pub fn as_uint16_array(&self) -> Option<Array<'a, u16>> {
+        match *self {
+            Self::UInt16Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a 32-bit integer array if it is a 32-bit integer array.
+    pub fn as_int32_array(&self) -> Option<Array<'a, i32>> {
+        match *self {
+            Self::Int32Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a 32-bit unsigned integer array if it is a 32-bit unsigned integer
+    /// array.
+    pub fn as_uint32_array(&self) -> Option<Array<'a, u32>> {
+        match *self {
+            Self::UInt32Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a single-precision floating-point array if it is a
+    /// single-precision floating-point array.
+    pub fn as_float_array(&self) -> Option<Array<'a, f32>> {
+        match *self {
+            Self::FloatArray(a) => Some(a),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<ValueRef<'a>> for Value {
+    fn from(value: ValueRef<'a>) -> Self {
+        match value {
+            ValueRef::Char(c) => Self::Char(c),
+            ValueRef::Int8(n) => Self::Int8(n),
+            ValueRef::UInt8(n) => Self::UInt8(n),
+            ValueRef::Int16(n) => Self::Int16(n),
+            ValueRef::UInt16(n) => Self::UInt16(n),
+            ValueRef::Int32(n) => Self::Int32(n),
+            ValueRef::UInt32(n) => Self::UInt32(n),
+            ValueRef::Float(n) => Self::Float(n),
+            ValueRef::String(s) => Self::String(s.into()),
+            ValueRef::Hex(s) => Self::Hex(s.into()),
+            ValueRef::Int8Array(a) => Self::Int8Array(a.to_vec()),
+            ValueRef::UInt8Array(a) => Self::UInt8Array(a.to_vec()),
+            ValueRef::Int16Array(a) => Self::Int16Array(a.to_vec()),
+            ValueRef::UInt16Array(a) => Self::UInt16Array(a.to_vec()),
+            ValueRef::Int32Array(a) => Self::Int32Array(a.to_vec()),
+            ValueRef::UInt32Array(a) => Self::UInt32Array(a.to_vec()),
+            ValueRef::FloatArray(a) => Self::FloatArray(a.to_vec()),
+        }
+    }
+}
+
+// Decodes a value from a raw field (tag, type, and value), as located by
+// `crate::record::data::find_field`.
+pub(crate) fn decode(field: &[u8]) -> io::Result<ValueRef<'_>> {
+    let ty = Type::try_from(field[2]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let buf = &field[3..];
+
+    match ty {
+        Type::Char => Ok(ValueRef::Char(char::from(buf[0]))),
+        Type::Int8 => Ok(ValueRef::Int8(buf[0] as i8)),
+        Type::UInt8 => Ok(ValueRef::UInt8(buf[0])),
+        Type::Int16 => Ok(ValueRef::Int16(LittleEndian::read_i16(buf))),
+        Type::UInt16 => Ok(ValueRef::UInt16(LittleEndian::read_u16(buf))),
+        Type::Int32 => Ok(ValueRef::Int32(LittleEndian::read_i32(buf))),
+        Type::UInt32 => Ok(ValueRef::UInt32(LittleEndian::read_u32(buf))),
+        Type::Float => Ok(ValueRef::Float(LittleEndian::read_f32(buf))),
+        Type::String => decode_str(buf).map(ValueRef::String),
+        Type::Hex => decode_str(buf).map(ValueRef::Hex),
+        Type::Array => {
+            let subtype = Subtype::try_from(buf[0])
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            // subtype (1) + count (4)
+            let src = &buf[5..];
+
+            let value = match subtype {
+                Subtype::Int8 => ValueRef::Int8Array(Array::new(src)),
+                Subtype::UInt8 => ValueRef::UInt8Array(Array::new(src)),
+                Subtype::Int16 => ValueRef::Int16Array(Array::new(src)),
+                Subtype::UInt16 => ValueRef::UInt16Array(Array::new(src)),
+                Subtype::Int32 => ValueRef::Int32Array(Array::new(src)),
+                Subtype::UInt32 => ValueRef::UInt32Array(Array::new(src)),
+                Subtype::Float => ValueRef::FloatArray(Array::new(src)),
+            };
+
+            Ok(value)
+        }
+    }
+}
+
+fn decode_str(buf: &[u8]) -> io::Result<&str> {
+    // Discards the NUL terminator.
+    let len = buf.len() - 1;
+    str::from_utf8(&buf[..len]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() -> io::Result<()> {
+        assert_eq!(decode(b"XAAn")?, ValueRef::Char('n'));
+        assert_eq!(decode(&[b'X', b'A', b'c', 0xfe])?, ValueRef::Int8(-2));
+        assert_eq!(
+            decode(&[b'X', b'A', b'I', 0x08, 0x00, 0x00, 0x00])?,
+            ValueRef::UInt32(8)
+        );
+        assert_eq!(decode(b"XAZndls\0")?, ValueRef::String("ndls"));
+        assert_eq!(decode(b"XAHCAFE\0")?, ValueRef::Hex("CAFE"));
+
+        let value = decode(&[
+            b'X', b'A', b'B', b'S', 0x02, 0x00, 0x00, 0x00, 0x08, 0x00, 0x0d, 0x00,
+        ])?;
+        let values = value.as_uint16_array().expect("expected u16 array");
+        assert_eq!(values.to_vec(), [8, 13]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_value_ref_for_value() {
+        let src = [0xff, 0x02];
+        let value = ValueRef::Int8Array(Array::new(&src));
+        assert_eq!(Value::from(value), Value::Int8Array(vec![-1, 2]));
+
+        assert_eq!(
+            Value::from(ValueRef::String("ndls")),
+            Value::String(String::from("ndls"))
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Borrowed BAM record data field array value.
+
+use std::{fmt, iter::FusedIterator, marker::PhantomData, mem};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A BAM record data field array element type.
+///
+/// This is implemented for the types that can be stored in a BAM data array: `i8`, `u8`, `i16`,
+/// `u16`, `i32`, `u32`, and `f32`.
+pub trait Element: private::Sealed + Copy {
+    /// Decodes an element from the start of the given little-endian buffer.
+    fn read(buf: &[u8]) -> Self;
+}
+
+macro_rules! impl_element {
+    ($ty:ty, $read:expr) => {
+        impl private::Sealed for $ty {}
+
+        impl Element for $ty {
+            fn read(buf: &[u8]) -> Self {
+                $read(buf)
+            }
+        }
+    };
+}
+
+impl_element!(i8, |buf: &[u8]| buf[0] as i8);
+impl_element!(u8, |buf: &[u8]| buf[0]);
+impl_element!(i16, LittleEndian::read_i16);
+impl_element!(u16, LittleEndian::read_u16);
+impl_element!(i32, LittleEndian::read_i32);
+impl_element!(u32, LittleEndian::read_u32);
+impl_element!(f32, LittleEndian::read_f32);
+
+/// A borrowed BAM record data field array value.
+///
+/// This is a typed view over the raw little-endian array data. No values are copied or decoded
+/// until they are accessed.
+#[derive(Clone, Copy)]
+pub struct Array<'a, T> {
+    src: &'a [u8],
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T> Array<'a, T>
+where
+    T: Element,
+{
+    // The length of `src` must be a multiple of the element size.
+    pub(crate) fn new(src: &'a [u8]) -> Self {
+        debug_assert_eq!(src.len() % mem::size_of::<T>(), 0);
+
+        Self {
+            src,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements in the array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::record::Data;
+    /// use noodles_sam::record::data::field::Tag;
+    ///
+    /// // ZB:B:s,-1,8
+    /// let raw_data = [
+    ///     0x5a, 0x42, 0x42, 0x73, 0x02, 0x00, 0x00, 0x00, 0xff, 0xff, 0x08, 0x00,
+    /// ];
+    /// let data = Data::new(&raw_data);
+    ///
+    /// let tag = "ZB".parse().unwrap();
+    /// let value = data.get(&tag).transpose()?.unwrap();
+    /// assert_eq!(value.as_int16_array().map(|values| values.len()), Some(2));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn len(&self) -> usize {
+        self.src.len() / mem::size_of::<T>()
+    }
+
+    /// Returns whether the array has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.src.is_empty()
+    }
+
+    /// Returns the element at the given index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::record::Data;
+    ///
+    /// // ZB:B:s,-1,8
+    /// let raw_data = [
+    ///     0x5a, 0x42, 0x42, 0x73, 0x02, 0x00, 0x00, 0x00, 0xff, 0xff, 0x08, 0x00,
+    /// ];
+    /// let data = Data::new(&raw_data);
+    ///
+    /// let tag = "ZB".parse().unwrap();
+    /// let value = data.get(&tag).transpose()?.unwrap();
+    /// let values = value.as_int16_array().unwrap();
+    ///
+    /// assert_eq!(values.get(0), Some(-1));
+    /// assert_eq!(values.get(1), Some(8));
+    /// assert_eq!(values.get(2), None);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn get(&self, i: usize) -> Option<T> {
+        let size = mem::size_of::<T>();
+        let start = i.checked_mul(size)?;
+        let end = start.checked_add(size)?;
+        self.src.get(start..end).map(T::read)
+    }
+
+    /// Returns an iterator over the elements of the array.
+    pub fn iter(&self) -> Iter<'a, T> {
+        Iter {
+            chunks: self.src.chunks_exact(mem::size_of::<T>()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the raw little-endian bytes of the array.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.src
+    }
+
+    /// Decodes all elements into an owned vector.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+impl<'a, T> fmt::Debug for Array<'a, T>
+where
+    T: Element + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> PartialEq for Array<'a, T>
+where
+    T: Element + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<'a, T> IntoIterator for Array<'a, T>
+where
+    T: Element,
+{
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a borrowed BAM record data field array value.
+pub struct Iter<'a, T> {
+    chunks: std::slice::ChunksExact<'a, u8>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: Element,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(T::read)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T>
+where
+    T: Element,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks.next_back().map(T::read)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> where T: Element {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> where T: Element {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let src = [0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x00, 0x40];
+        let array: Array<'_, f32> = Array::new(&src);
+
+        assert_eq!(array.len(), 2);
+        assert_eq!(array.get(0), Some(1.0));
+        assert_eq!(array.get(1), Some(2.0));
+        assert_eq!(array.get(2), None);
+    }
+
+    #[test]
+    fn test_iter() {
+        let src = [0xff, 0xff, 0xff, 0xff, 0x08, 0x00, 0x00, 0x00];
+        let array: Array<'_, i32> = Array::new(&src);
+
+        assert_eq!(array.to_vec(), [-1, 8]);
+        assert_eq!(array.iter().rev().collect::<Vec<_>>(), [8, -1]);
+        assert_eq!(array.iter().len(), 2);
+    }
+}
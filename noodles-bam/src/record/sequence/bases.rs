@@ -5,24 +5,17 @@ use super::{Base, Sequence};
 /// This is created by calling [`Sequence::bases`].
 pub struct Bases<'a> {
     sequence: &'a Sequence<'a>,
+    // The remaining bases are in the half-open interval [head, tail).
     head: usize,
     tail: usize,
-    remaining: usize,
 }
 
 impl<'a> Bases<'a> {
     pub(crate) fn new(sequence: &'a Sequence<'_>) -> Self {
-        let tail = if sequence.is_empty() {
-            0
-        } else {
-            sequence.base_count() - 1
-        };
-
         Self {
             sequence,
             head: 0,
-            tail,
-            remaining: sequence.base_count(),
+            tail: sequence.base_count(),
         }
     }
 }
@@ -31,34 +24,63 @@ impl<'a> Iterator for Bases<'a> {
     type Item = Base;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.remaining == 0 {
+        if self.head >= self.tail {
             return None;
         }
 
         let symbol = self.sequence.get(self.head).copied();
-
         self.head += 1;
-        self.remaining -= 1;
-
         symbol
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.remaining, Some(self.remaining))
+        let remaining = self.tail - self.head;
+        (remaining, Some(remaining))
     }
 }
 
 impl<'a> DoubleEndedIterator for Bases<'a> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        if self.remaining == 0 {
+        if self.head >= self.tail {
             return None;
         }
 
-        let symbol = self.sequence.get(self.tail).copied();
-
         self.tail -= 1;
-        self.remaining -= 1;
+        self.sequence.get(self.tail).copied()
+    }
+}
 
-        symbol
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_back() {
+        let data = [0x18, 0x40];
+        let sequence = Sequence::new(&data, 3);
+
+        let mut bases = sequence.bases();
+        assert_eq!(bases.next_back(), Some(Base::G));
+        assert_eq!(bases.next_back(), Some(Base::T));
+        assert_eq!(bases.next_back(), Some(Base::A));
+        assert_eq!(bases.next_back(), None);
+        assert_eq!(bases.next(), None);
+
+        let sequence = Sequence::new(&data, 1);
+        assert_eq!(sequence.bases().rev().collect::<Vec<_>>(), [Base::A]);
+    }
+
+    #[test]
+    fn test_next_and_next_back() {
+        let data = [0x18, 0x40];
+        let sequence = Sequence::new(&data, 3);
+
+        let mut bases = sequence.bases();
+        assert_eq!(bases.next(), Some(Base::A));
+        assert_eq!(bases.next_back(), Some(Base::G));
+        assert_eq!(bases.size_hint(), (1, Some(1)));
+        assert_eq!(bases.next_back(), Some(Base::T));
+        assert_eq!(bases.next(), None);
+        assert_eq!(bases.next_back(), None);
     }
 }
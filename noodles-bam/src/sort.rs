@@ -0,0 +1,696 @@
+//! BAM record sorting.
+//!
+//! Records are sorted using an external merge sort. Up to a maximum amount of records are
+//! buffered in memory, sorted, and spilled to temporary BGZF-compressed files, which are then
+//! k-way merged into the output.
+//!
+//! At most a fixed number of temporary files are merged at once (see
+//! [`Builder::set_max_merge_width`]). When more runs are spilled, groups of them are first merged
+//! into larger intermediate runs, which bounds the number of files that are open at the same time
+//! in a merge.
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+    mem,
+    path::PathBuf,
+    vec,
+};
+
+use noodles_bgzf::writer::CompressionLevel;
+use noodles_sam::{self as sam, header::header::SortOrder as SamSortOrder};
+
+use crate::{bai, Reader, Record, Writer};
+
+/// A BAM sort order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SortOrder {
+    /// Records are sorted by reference sequence ID, position, and strand.
+    ///
+    /// Records with no reference sequence ID are placed last.
+    Coordinate,
+    /// Records are sorted by read name, then by segment (read 1 before read 2).
+    ///
+    /// Read names are compared using natural ordering, i.e., runs of digits are compared by
+    /// numeric value. This matches the ordering used by `samtools sort -n`.
+    QueryName,
+}
+
+impl From<SortOrder> for SamSortOrder {
+    fn from(sort_order: SortOrder) -> Self {
+        match sort_order {
+            SortOrder::Coordinate => Self::Coordinate,
+            SortOrder::QueryName => Self::QueryName,
+        }
+    }
+}
+
+/// A BAM sorter.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bam::{self as bam, sort::{SortOrder, Sorter}};
+/// use noodles_sam as sam;
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// let mut header: sam::Header = reader
+///     .read_header()?
+///     .parse()
+///     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+/// reader.read_reference_sequences()?;
+///
+/// let sorter = Sorter::builder(SortOrder::Coordinate)
+///     .set_build_index(true)
+///     .build();
+///
+/// let writer = File::create("sample.sorted.bam")?;
+/// let (_, index) = sorter.sort(&mut reader, &mut header, writer)?;
+///
+/// if let Some(index) = index {
+///     bam::bai::write("sample.sorted.bam.bai", &index)?;
+/// }
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Sorter {
+    sort_order: SortOrder,
+    max_memory: usize,
+    temp_dir: Option<PathBuf>,
+    build_index: bool,
+    max_merge_width: usize,
+}
+
+impl Sorter {
+    /// Creates a BAM sorter with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::sort::{SortOrder, Sorter};
+    /// let sorter = Sorter::new(SortOrder::Coordinate);
+    /// ```
+    pub fn new(sort_order: SortOrder) -> Self {
+        Builder::new(sort_order).build()
+    }
+
+    /// Creates a BAM sorter builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::sort::{SortOrder, Sorter};
+    /// let sorter = Sorter::builder(SortOrder::QueryName).build();
+    /// ```
+    pub fn builder(sort_order: SortOrder) -> Builder {
+        Builder::new(sort_order)
+    }
+
+    /// Sorts the remaining records of a BAM reader and writes them as BAM to the given writer.
+    ///
+    /// The reader is expected to be positioned at the start of the records, i.e., after the
+    /// header and reference sequences have been read. The sort order (`@HD SO`) of the given
+    /// header is set to the sort order of this sorter before it is written to the output.
+    ///
+    /// This returns the underlying writer and, if enabled, the BAM index of the sorted output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::{self as bam, sort::{SortOrder, Sorter}};
+    /// use noodles_sam as sam;
+    ///
+    /// let mut header = sam::Header::default();
+    ///
+    /// let mut writer = bam::Writer::new(Vec::new());
+    /// writer.write_header(&header)?;
+    /// writer.write_reference_sequences(header.reference_sequences())?;
+    /// let (data, _) = writer.finish()?;
+    ///
+    /// let mut reader = bam::Reader::new(&data[..]);
+    /// reader.read_header()?;
+    /// reader.read_reference_sequences()?;
+    ///
+    /// let sorter = Sorter::new(SortOrder::QueryName);
+    /// let (output, index) = sorter.sort(&mut reader, &mut header, Vec::new())?;
+    ///
+    /// assert!(!output.is_empty());
+    /// assert!(index.is_none());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn sort<R, W>(
+        &self,
+        reader: &mut Reader<R>,
+        header: &mut sam::Header,
+        writer: W,
+    ) -> io::Result<(W, Option<bai::Index>)>
+    where
+        R: Read,
+        W: Write,
+    {
+        if self.build_index && self.sort_order != SortOrder::Coordinate {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "an index can only be built for coordinate-sorted output",
+            ));
+        }
+
+        let runs = self.build_runs(reader)?;
+
+        let sort_order = Some(SamSortOrder::from(self.sort_order));
+        *header
+            .header_mut()
+            .get_or_insert_with(sam::header::header::Header::default)
+            .sort_order_mut() = sort_order;
+
+        let mut writer = Writer::builder(writer)
+            .set_build_index(self.build_index)
+            .build();
+
+        writer.write_header(header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+
+        merge(self.sort_order, runs, |record| writer.write_record(&record))?;
+
+        writer.finish()
+    }
+
+    // Reads all records, spilling sorted runs to temporary files when the memory limit is
+    // exceeded. The final run is kept in memory.
+    //
+    // Spilled runs are grouped into levels. When a level reaches the maximum merge width, its
+    // runs are merged into a single run in the next level. Runs are returned in input order to
+    // keep the final merge stable.
+    fn build_runs<R>(&self, reader: &mut Reader<R>) -> io::Result<Vec<Run>>
+    where
+        R: Read,
+    {
+        let mut levels: Vec<Vec<File>> = Vec::new();
+        let mut buf = Vec::new();
+        let mut buf_size = 0;
+
+        loop {
+            let mut record = Record::default();
+
+            if reader.read_record(&mut record)? == 0 {
+                break;
+            }
+
+            if self.sort_order == SortOrder::QueryName {
+                record
+                    .read_name()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            }
+
+            buf_size += mem::size_of::<Record>() + record.len();
+            buf.push(record);
+
+            if buf_size >= self.max_memory {
+                sort_records(self.sort_order, &mut buf);
+                let file = self.spill(&buf)?;
+                self.push_file(&mut levels, file)?;
+
+                buf.clear();
+                buf_size = 0;
+            }
+        }
+
+        // Older runs are in higher levels.
+        let mut files: Vec<_> = levels.into_iter().rev().flatten().collect();
+
+        // The in-memory run takes one slot in the final merge.
+        while files.len() >= self.max_merge_width {
+            let rest = files.split_off(self.max_merge_width);
+            let file = self.merge_files(files)?;
+            files = Some(file).into_iter().chain(rest).collect();
+        }
+
+        let mut runs: Vec<_> = files
+            .into_iter()
+            .map(|file| Run::File(Reader::new(file)))
+            .collect();
+
+        sort_records(self.sort_order, &mut buf);
+        runs.push(Run::Memory(buf.into_iter()));
+
+        Ok(runs)
+    }
+
+    fn push_file(&self, levels: &mut Vec<Vec<File>>, mut file: File) -> io::Result<()> {
+        for i in 0.. {
+            if i == levels.len() {
+                levels.push(Vec::new());
+            }
+
+            levels[i].push(file);
+
+            if levels[i].len() < self.max_merge_width {
+                break;
+            }
+
+            let files = mem::take(&mut levels[i]);
+            file = self.merge_files(files)?;
+        }
+
+        Ok(())
+    }
+
+    fn spill(&self, records: &[Record]) -> io::Result<File> {
+        let mut writer = self.create_temp_writer()?;
+
+        for record in records {
+            writer.write_record(record)?;
+        }
+
+        finish_temp_writer(writer)
+    }
+
+    fn merge_files(&self, files: Vec<File>) -> io::Result<File> {
+        let runs = files
+            .into_iter()
+            .map(|file| Run::File(Reader::new(file)))
+            .collect();
+
+        let mut writer = self.create_temp_writer()?;
+        merge(self.sort_order, runs, |record| writer.write_record(&record))?;
+        finish_temp_writer(writer)
+    }
+
+    fn create_temp_writer(&self) -> io::Result<Writer<File>> {
+        let file = match self.temp_dir {
+            Some(ref temp_dir) => tempfile::tempfile_in(temp_dir)?,
+            None => tempfile::tempfile()?,
+        };
+
+        Ok(Writer::builder(file)
+            .set_compression_level(CompressionLevel::FAST)
+            .build())
+    }
+}
+
+fn finish_temp_writer(writer: Writer<File>) -> io::Result<File> {
+    let (mut file, _) = writer.finish()?;
+    file.seek(SeekFrom::Start(0))?;
+    Ok(file)
+}
+
+// A sorted run of records.
+enum Run {
+    Memory(vec::IntoIter<Record>),
+    File(Reader<File>),
+}
+
+// A source of sorted records for a k-way merge.
+pub(crate) trait RecordSource {
+    fn next_record(&mut self) -> io::Result<Option<Record>>;
+}
+
+impl RecordSource for Run {
+    fn next_record(&mut self) -> io::Result<Option<Record>> {
+        match self {
+            Self::Memory(records) => Ok(records.next()),
+            Self::File(reader) => {
+                let mut record = Record::default();
+
+                match reader.read_record(&mut record)? {
+                    0 => Ok(None),
+                    _ => Ok(Some(record)),
+                }
+            }
+        }
+    }
+}
+
+// The head record of a run during a k-way merge.
+//
+// Ties are broken by run index to keep the merge stable.
+struct Head {
+    sort_order: SortOrder,
+    record: Record,
+    run_index: usize,
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head {}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare(self.sort_order, &self.record, &other.record)
+            .then_with(|| self.run_index.cmp(&other.run_index))
+    }
+}
+
+// Merges sorted sources of records, calling `f` with each record in sort order.
+pub(crate) fn merge<S, F>(sort_order: SortOrder, mut runs: Vec<S>, mut f: F) -> io::Result<()>
+where
+    S: RecordSource,
+    F: FnMut(Record) -> io::Result<()>,
+{
+    let mut heap = BinaryHeap::with_capacity(runs.len());
+
+    for (run_index, run) in runs.iter_mut().enumerate() {
+        if let Some(record) = run.next_record()? {
+            heap.push(Reverse(Head {
+                sort_order,
+                record,
+                run_index,
+            }));
+        }
+    }
+
+    while let Some(Reverse(head)) = heap.pop() {
+        let run_index = head.run_index;
+        f(head.record)?;
+
+        if let Some(record) = runs[run_index].next_record()? {
+            heap.push(Reverse(Head {
+                sort_order,
+                record,
+                run_index,
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+fn sort_records(sort_order: SortOrder, records: &mut [Record]) {
+    records.sort_by(|a, b| compare(sort_order, a, b));
+}
+
+fn compare(sort_order: SortOrder, a: &Record, b: &Record) -> Ordering {
+    match sort_order {
+        SortOrder::Coordinate => coordinate_key(a).cmp(&coordinate_key(b)),
+        SortOrder::QueryName => {
+            let a_name = a.read_name().map(|s| s.to_bytes()).unwrap_or_default();
+            let b_name = b.read_name().map(|s| s.to_bytes()).unwrap_or_default();
+
+            compare_names(a_name, b_name).then_with(|| segment_key(a).cmp(&segment_key(b)))
+        }
+    }
+}
+
+// (reference sequence ID, position, is reverse complemented)
+//
+// Records with no reference sequence ID are placed last, and records with no position are placed
+// first within their reference sequence.
+fn coordinate_key(record: &Record) -> (u32, i32, bool) {
+    let reference_sequence_id = record
+        .reference_sequence_id()
+        .map(|id| i32::from(id) as u32)
+        .unwrap_or(u32::MAX);
+
+    let position = record.position().map(i32::from).unwrap_or(0);
+    let is_reverse_complemented = record.flags().is_reverse_complemented();
+
+    (reference_sequence_id, position, is_reverse_complemented)
+}
+
+// The read 1 (0x40) and read 2 (0x80) flags.
+fn segment_key(record: &Record) -> u16 {
+    u16::from(record.flags()) & 0xc0
+}
+
+// Compares two read names using natural ordering.
+//
+// This is a port of `strnum_cmp` from samtools. Runs of digits are compared by numeric value,
+// ignoring leading zeros, and all other bytes are compared by value.
+fn compare_names(a: &[u8], b: &[u8]) -> Ordering {
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        if !a[i].is_ascii_digit() || !b[j].is_ascii_digit() {
+            if a[i] != b[j] {
+                return a[i].cmp(&b[j]);
+            }
+
+            i += 1;
+            j += 1;
+        } else {
+            while a.get(i) == Some(&b'0') {
+                i += 1;
+            }
+
+            while b.get(j) == Some(&b'0') {
+                j += 1;
+            }
+
+            while i < a.len() && j < b.len() && a[i].is_ascii_digit() && a[i] == b[j] {
+                i += 1;
+                j += 1;
+            }
+
+            let diff = a.get(i).cmp(&b.get(j));
+
+            while i < a.len() && j < b.len() && a[i].is_ascii_digit() && b[j].is_ascii_digit() {
+                i += 1;
+                j += 1;
+            }
+
+            let a_has_digit = a.get(i).map(u8::is_ascii_digit).unwrap_or(false);
+            let b_has_digit = b.get(j).map(u8::is_ascii_digit).unwrap_or(false);
+
+            if a_has_digit {
+                return Ordering::Greater;
+            } else if b_has_digit {
+                return Ordering::Less;
+            } else if diff != Ordering::Equal {
+                return diff;
+            }
+        }
+    }
+
+    (a.len() - i).cmp(&(b.len() - j))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, ffi::CString};
+
+    use noodles_sam::{
+        header::ReferenceSequence,
+        record::{Flags, Position},
+    };
+
+    use crate::record::ReferenceSequenceId;
+
+    use super::*;
+
+    fn build_record(
+        read_name: &str,
+        reference_sequence_id: Option<i32>,
+        position: Option<i32>,
+        flags: u16,
+    ) -> io::Result<Record> {
+        let mut record = Record::default();
+
+        let read_name =
+            CString::new(read_name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        record.set_read_name(&read_name)?;
+
+        let reference_sequence_id = reference_sequence_id
+            .map(ReferenceSequenceId::try_from)
+            .transpose()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        record.set_reference_sequence_id(reference_sequence_id);
+
+        let position = position
+            .map(Position::try_from)
+            .transpose()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        record.set_position(position)?;
+        record.set_flags(Flags::from(flags));
+
+        Ok(record)
+    }
+
+    fn build_input(header: &sam::Header, records: &[Record]) -> io::Result<Vec<u8>> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header(header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+
+        for record in records {
+            writer.write_record(record)?;
+        }
+
+        writer.finish().map(|(data, _)| data)
+    }
+
+    fn sort(
+        sorter: &Sorter,
+        header: &mut sam::Header,
+        input: &[u8],
+    ) -> io::Result<(String, Vec<String>)> {
+        let mut reader = Reader::new(input);
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        let (output, _) = sorter.sort(&mut reader, header, Vec::new())?;
+
+        let mut reader = Reader::new(&output[..]);
+        let raw_header = reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        let names = reader
+            .records()
+            .map(|result| {
+                result.map(|record| {
+                    let read_name = record.read_name().expect("invalid read name");
+                    read_name.to_string_lossy().into_owned()
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok((raw_header, names))
+    }
+
+    fn build_header() -> sam::Header {
+        sam::Header::builder()
+            .set_header(sam::header::header::Header::default())
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 1000))
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq1"), 1000))
+            .build()
+    }
+
+    #[test]
+    fn test_sort_by_coordinate() -> io::Result<()> {
+        let mut header = build_header();
+
+        let records = [
+            build_record("r0", None, None, 0x04)?,
+            build_record("r1", Some(1), Some(5), 0x00)?,
+            build_record("r2", Some(0), Some(8), 0x10)?,
+            build_record("r3", Some(0), Some(8), 0x00)?,
+            build_record("r4", Some(0), Some(3), 0x00)?,
+        ];
+        let input = build_input(&header, &records)?;
+
+        let expected = ["r4", "r3", "r2", "r1", "r0"];
+
+        let sorter = Sorter::new(SortOrder::Coordinate);
+        let (raw_header, names) = sort(&sorter, &mut header, &input)?;
+        assert_eq!(
+            raw_header,
+            "@HD\tVN:1.6\tSO:coordinate\n@SQ\tSN:sq0\tLN:1000\n@SQ\tSN:sq1\tLN:1000\n"
+        );
+        assert_eq!(names, expected);
+
+        // Spills every record to its own run.
+        let sorter = Sorter::builder(SortOrder::Coordinate)
+            .set_max_memory(1)
+            .build();
+        let (_, names) = sort(&sorter, &mut header, &input)?;
+        assert_eq!(names, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_with_max_merge_width() -> io::Result<()> {
+        let mut header = build_header();
+
+        let records: Vec<_> = (0..23)
+            .map(|i| build_record(&format!("r{}", i), Some(0), Some((i * 7) % 5 + 1), 0x00))
+            .collect::<io::Result<_>>()?;
+        let input = build_input(&header, &records)?;
+
+        let sorter = Sorter::new(SortOrder::Coordinate);
+        let (_, expected) = sort(&sorter, &mut header, &input)?;
+
+        for max_merge_width in 2..=4 {
+            let sorter = Sorter::builder(SortOrder::Coordinate)
+                .set_max_memory(1)
+                .set_max_merge_width(max_merge_width)
+                .build();
+            let (_, names) = sort(&sorter, &mut header, &input)?;
+            assert_eq!(names, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_by_query_name() -> io::Result<()> {
+        let mut header = build_header();
+
+        let records = [
+            build_record("r10", Some(0), Some(1), 0x41)?,
+            build_record("r9", Some(0), Some(2), 0x81)?,
+            build_record("r9", Some(0), Some(3), 0x41)?,
+            build_record("q2", Some(1), Some(4), 0x00)?,
+        ];
+        let input = build_input(&header, &records)?;
+
+        let sorter = Sorter::builder(SortOrder::QueryName)
+            .set_max_memory(64)
+            .build();
+        let (raw_header, names) = sort(&sorter, &mut header, &input)?;
+
+        assert!(raw_header.starts_with("@HD\tVN:1.6\tSO:queryname\n"));
+        assert_eq!(names, ["q2", "r9", "r9", "r10"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_with_build_index() -> io::Result<()> {
+        let mut header = build_header();
+        let records = [
+            build_record("r0", Some(1), Some(5), 0x00)?,
+            build_record("r1", Some(0), Some(8), 0x00)?,
+        ];
+        let input = build_input(&header, &records)?;
+
+        let mut reader = Reader::new(&input[..]);
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        let sorter = Sorter::builder(SortOrder::Coordinate)
+            .set_build_index(true)
+            .build();
+        let (_, index) = sorter.sort(&mut reader, &mut header, Vec::new())?;
+        let index = index.expect("missing index");
+        assert_eq!(index.reference_sequences().len(), 2);
+
+        let sorter = Sorter::builder(SortOrder::QueryName)
+            .set_build_index(true)
+            .build();
+        assert!(matches!(
+            sorter.sort(&mut reader, &mut header, Vec::new()),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_names() {
+        assert_eq!(compare_names(b"r1", b"r1"), Ordering::Equal);
+        assert_eq!(compare_names(b"r2", b"r10"), Ordering::Less);
+        assert_eq!(compare_names(b"r10", b"r9"), Ordering::Greater);
+        assert_eq!(compare_names(b"r007", b"r7"), Ordering::Equal);
+        assert_eq!(compare_names(b"r12a", b"r12b"), Ordering::Less);
+        assert_eq!(compare_names(b"r1:5", b"r1:40"), Ordering::Less);
+        assert_eq!(compare_names(b"r1", b"r1a"), Ordering::Less);
+        assert_eq!(compare_names(b"a", b"B"), Ordering::Greater);
+    }
+}
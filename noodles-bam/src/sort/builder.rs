@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use super::{SortOrder, Sorter};
+
+// samtools sort uses 768 MiB per thread by default.
+const DEFAULT_MAX_MEMORY: usize = 768 * 1024 * 1024;
+
+const DEFAULT_MAX_MERGE_WIDTH: usize = 64;
+const MIN_MAX_MERGE_WIDTH: usize = 2;
+
+/// A BAM sorter builder.
+pub struct Builder {
+    sort_order: SortOrder,
+    max_memory: usize,
+    temp_dir: Option<PathBuf>,
+    build_index: bool,
+    max_merge_width: usize,
+}
+
+impl Builder {
+    pub(crate) fn new(sort_order: SortOrder) -> Self {
+        Self {
+            sort_order,
+            max_memory: DEFAULT_MAX_MEMORY,
+            temp_dir: None,
+            build_index: false,
+            max_merge_width: DEFAULT_MAX_MERGE_WIDTH,
+        }
+    }
+
+    /// Sets the approximate maximum number of bytes of records to hold in memory.
+    ///
+    /// When the buffered records exceed this limit, they are sorted and spilled to a temporary
+    /// file.
+    ///
+    /// By default, this is 768 MiB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::sort::{SortOrder, Sorter};
+    /// let sorter = Sorter::builder(SortOrder::Coordinate)
+    ///     .set_max_memory(64 * 1024 * 1024)
+    ///     .build();
+    /// ```
+    pub fn set_max_memory(mut self, max_memory: usize) -> Self {
+        self.max_memory = max_memory;
+        self
+    }
+
+    /// Sets the directory in which temporary files are created.
+    ///
+    /// By default, this is the system temporary directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::env;
+    /// use noodles_bam::sort::{SortOrder, Sorter};
+    /// let sorter = Sorter::builder(SortOrder::Coordinate)
+    ///     .set_temp_dir(env::temp_dir())
+    ///     .build();
+    /// ```
+    pub fn set_temp_dir<P>(mut self, temp_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.temp_dir = Some(temp_dir.into());
+        self
+    }
+
+    /// Sets whether to build a BAM index (BAI) of the sorted output.
+    ///
+    /// This is only valid for coordinate sorting.
+    ///
+    /// By default, no index is built.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::sort::{SortOrder, Sorter};
+    /// let sorter = Sorter::builder(SortOrder::Coordinate)
+    ///     .set_build_index(true)
+    ///     .build();
+    /// ```
+    pub fn set_build_index(mut self, build_index: bool) -> Self {
+        self.build_index = build_index;
+        self
+    }
+
+    /// Sets the maximum number of sorted runs that are merged at once.
+    ///
+    /// This bounds the number of temporary files that are read at the same time. When more runs
+    /// are spilled, they are merged in groups into intermediate temporary files. Values less
+    /// than 2 are treated as 2.
+    ///
+    /// By default, this is 64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::sort::{SortOrder, Sorter};
+    /// let sorter = Sorter::builder(SortOrder::Coordinate)
+    ///     .set_max_merge_width(16)
+    ///     .build();
+    /// ```
+    pub fn set_max_merge_width(mut self, max_merge_width: usize) -> Self {
+        self.max_merge_width = max_merge_width.max(MIN_MAX_MERGE_WIDTH);
+        self
+    }
+
+    /// Builds a BAM sorter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::sort::{SortOrder, Sorter};
+    /// let sorter = Sorter::builder(SortOrder::QueryName).build();
+    /// ```
+    pub fn build(self) -> Sorter {
+        Sorter {
+            sort_order: self.sort_order,
+            max_memory: self.max_memory,
+            temp_dir: self.temp_dir,
+            build_index: self.build_index,
+            max_merge_width: self.max_merge_width,
+        }
+    }
+}
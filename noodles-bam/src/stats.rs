@@ -0,0 +1,343 @@
+//! BAM alignment statistics.
+//!
+//! [`Stats`] accumulates flag statistics (`samtools flagstat`), per-reference sequence record
+//! counts (`samtools idxstats`), and distributions similar to those of `samtools stats` from a
+//! stream of records. Statistics of separate streams, e.g., from parallel shards, can be combined
+//! using [`Stats::merge`].
+
+pub mod flag_stats;
+pub mod index_stats;
+
+pub use self::{flag_stats::FlagStats, index_stats::IndexStats};
+
+use std::{collections::BTreeMap, io};
+
+use noodles_sam::record::cigar::op::Kind;
+
+use crate::Record;
+
+const KIND_COUNT: usize = 9;
+const MISSING_QUALITY_SCORE: u8 = 0xff;
+
+/// Alignment statistics of a set of records.
+///
+/// Flag statistics and index statistics include all records. The remaining distributions only
+/// include primary records, i.e., records that are neither secondary nor supplementary.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_bam::{self as bam, stats::Stats};
+///
+/// let mut stats = Stats::default();
+/// stats.add_record(&bam::Record::default())?;
+///
+/// assert_eq!(stats.flag_stats().qc_pass().read(), 1);
+/// assert_eq!(stats.index_stats().unplaced_unmapped_record_count(), 1);
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stats {
+    flag_stats: FlagStats,
+    index_stats: IndexStats,
+    insert_sizes: BTreeMap<u32, u64>,
+    mapping_qualities: Vec<u64>,
+    base_qualities: Vec<u64>,
+    cigar_op_counts: [u64; KIND_COUNT],
+    cigar_op_lens: [u64; KIND_COUNT],
+}
+
+impl Stats {
+    /// Returns the flag statistics.
+    pub fn flag_stats(&self) -> &FlagStats {
+        &self.flag_stats
+    }
+
+    /// Returns the per-reference sequence record counts.
+    pub fn index_stats(&self) -> &IndexStats {
+        &self.index_stats
+    }
+
+    /// Returns the insert size histogram.
+    ///
+    /// This maps an insert size to the number of read pairs with that insert size. Each pair is
+    /// counted once, from the mapped primary record with a positive template length whose mate
+    /// is also mapped.
+    pub fn insert_sizes(&self) -> &BTreeMap<u32, u64> {
+        &self.insert_sizes
+    }
+
+    /// Returns the mapping quality distribution of mapped primary records.
+    ///
+    /// This is indexed by mapping quality, where 255 is a missing mapping quality.
+    pub fn mapping_qualities(&self) -> &[u64] {
+        &self.mapping_qualities
+    }
+
+    /// Returns the base quality score distribution of primary records.
+    ///
+    /// This is indexed by quality score. Records with missing quality scores are not counted.
+    pub fn base_qualities(&self) -> &[u64] {
+        &self.base_qualities
+    }
+
+    /// Returns the number of CIGAR operations of the given kind in mapped primary records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::stats::Stats;
+    /// use noodles_sam::record::cigar::op::Kind;
+    ///
+    /// let stats = Stats::default();
+    /// assert_eq!(stats.cigar_op_count(Kind::Match), 0);
+    /// ```
+    pub fn cigar_op_count(&self, kind: Kind) -> u64 {
+        self.cigar_op_counts[kind_index(kind)]
+    }
+
+    /// Returns the total length of CIGAR operations of the given kind in mapped primary records.
+    pub fn cigar_op_len(&self, kind: Kind) -> u64 {
+        self.cigar_op_lens[kind_index(kind)]
+    }
+
+    /// Adds a record to the statistics.
+    ///
+    /// This returns an error if the record CIGAR is invalid.
+    pub fn add_record(&mut self, record: &Record) -> io::Result<()> {
+        self.flag_stats.add_record(record);
+        self.index_stats.add_record(record);
+
+        let flags = record.flags();
+
+        if flags.is_secondary() || flags.is_supplementary() {
+            return Ok(());
+        }
+
+        for &score in record.quality_scores().iter() {
+            if score == MISSING_QUALITY_SCORE {
+                break;
+            }
+
+            self.base_qualities[usize::from(score)] += 1;
+        }
+
+        if flags.is_unmapped() {
+            return Ok(());
+        }
+
+        let mapping_quality = u8::from(record.mapping_quality());
+        self.mapping_qualities[usize::from(mapping_quality)] += 1;
+
+        for result in record.cigar().ops() {
+            let op = result?;
+            let i = kind_index(op.kind());
+            self.cigar_op_counts[i] += 1;
+            self.cigar_op_lens[i] += u64::from(op.len());
+        }
+
+        let template_length = record.template_length();
+
+        if flags.is_paired() && !flags.is_mate_unmapped() && template_length > 0 {
+            *self.insert_sizes.entry(template_length as u32).or_insert(0) += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Merges the statistics of another set of records into these statistics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::{self as bam, stats::Stats};
+    ///
+    /// let mut a = Stats::default();
+    /// a.add_record(&bam::Record::default())?;
+    ///
+    /// let mut b = Stats::default();
+    /// b.add_record(&bam::Record::default())?;
+    ///
+    /// a.merge(&b);
+    /// assert_eq!(a.flag_stats().qc_pass().read(), 2);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        self.flag_stats.merge(&other.flag_stats);
+        self.index_stats.merge(&other.index_stats);
+
+        for (&insert_size, &count) in &other.insert_sizes {
+            *self.insert_sizes.entry(insert_size).or_insert(0) += count;
+        }
+
+        add_counts(&mut self.mapping_qualities, &other.mapping_qualities);
+        add_counts(&mut self.base_qualities, &other.base_qualities);
+        add_counts(&mut self.cigar_op_counts, &other.cigar_op_counts);
+        add_counts(&mut self.cigar_op_lens, &other.cigar_op_lens);
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            flag_stats: FlagStats::default(),
+            index_stats: IndexStats::default(),
+            insert_sizes: BTreeMap::new(),
+            mapping_qualities: vec![0; usize::from(u8::MAX) + 1],
+            base_qualities: vec![0; usize::from(u8::MAX) + 1],
+            cigar_op_counts: [0; KIND_COUNT],
+            cigar_op_lens: [0; KIND_COUNT],
+        }
+    }
+}
+
+fn add_counts(dst: &mut [u64], src: &[u64]) {
+    for (a, b) in dst.iter_mut().zip(src) {
+        *a += b;
+    }
+}
+
+// The index of the kind in BAM CIGAR operation code order (`MIDNSHP=X`).
+fn kind_index(kind: Kind) -> usize {
+    match kind {
+        Kind::Match => 0,
+        Kind::Insertion => 1,
+        Kind::Deletion => 2,
+        Kind::Skip => 3,
+        Kind::SoftClip => 4,
+        Kind::HardClip => 5,
+        Kind::Pad => 6,
+        Kind::SeqMatch => 7,
+        Kind::SeqMismatch => 8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use noodles_sam::record::{Flags, MappingQuality, Position};
+
+    use crate::record::ReferenceSequenceId;
+
+    use super::*;
+
+    fn build_record(
+        flags: u16,
+        reference_sequence_id: Option<i32>,
+        mapping_quality: u8,
+        template_length: i32,
+    ) -> Result<Record, Box<dyn std::error::Error>> {
+        let mut record = Record::default();
+
+        record.set_flags(Flags::from(flags));
+
+        if let Some(id) = reference_sequence_id {
+            record.set_reference_sequence_id(Some(ReferenceSequenceId::try_from(id)?));
+            record.set_mate_reference_sequence_id(Some(ReferenceSequenceId::try_from(id)?));
+            record.set_position(Some(Position::try_from(8)?))?;
+        }
+
+        record.set_mapping_quality(MappingQuality::from(mapping_quality));
+        record.set_template_length(template_length);
+        record.set_cigar(&"2S4M1I".parse()?)?;
+        record.set_sequence(&"ACGTACG".parse()?)?;
+        record.set_quality_scores(&"NDLSNDL".parse()?)?;
+
+        Ok(record)
+    }
+
+    fn build_records() -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+        Ok(vec![
+            // paired, proper pair, read 1
+            build_record(0x43, Some(0), 60, 100)?,
+            // paired, proper pair, read 2, reverse complemented
+            build_record(0x93, Some(0), 60, -100)?,
+            // secondary
+            build_record(0x100, Some(1), 0, 0)?,
+            // QC fail
+            build_record(0x200, Some(1), 30, 0)?,
+            // unmapped, unplaced
+            build_record(0x04, None, 255, 0)?,
+        ])
+    }
+
+    #[test]
+    fn test_add_record() -> Result<(), Box<dyn std::error::Error>> {
+        let mut stats = Stats::default();
+
+        for record in build_records()? {
+            stats.add_record(&record)?;
+        }
+
+        let qc_pass = stats.flag_stats().qc_pass();
+        assert_eq!(qc_pass.read(), 4);
+        assert_eq!(qc_pass.mapped(), 3);
+        assert_eq!(qc_pass.secondary(), 1);
+        assert_eq!(qc_pass.paired(), 2);
+        assert_eq!(qc_pass.read_1(), 1);
+        assert_eq!(qc_pass.read_2(), 1);
+        assert_eq!(qc_pass.proper_pair(), 2);
+        assert_eq!(qc_pass.mate_mapped(), 2);
+        assert_eq!(stats.flag_stats().qc_fail().read(), 1);
+
+        let index_stats = stats.index_stats();
+        let counts = index_stats.reference_sequences();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].mapped_record_count(), 2);
+        assert_eq!(counts[1].mapped_record_count(), 2);
+        assert_eq!(index_stats.unplaced_unmapped_record_count(), 1);
+
+        assert_eq!(stats.insert_sizes().get(&100), Some(&1));
+        assert_eq!(stats.insert_sizes().len(), 1);
+
+        assert_eq!(stats.mapping_qualities()[60], 2);
+        assert_eq!(stats.mapping_qualities()[30], 1);
+        assert_eq!(stats.mapping_qualities()[255], 0);
+
+        // NDLS => 45, 35, 43, 50 over 4 primary records
+        assert_eq!(stats.base_qualities()[45], 8);
+        assert_eq!(stats.base_qualities()[50], 4);
+
+        assert_eq!(stats.cigar_op_count(Kind::Match), 3);
+        assert_eq!(stats.cigar_op_len(Kind::Match), 12);
+        assert_eq!(stats.cigar_op_len(Kind::SoftClip), 6);
+        assert_eq!(stats.cigar_op_count(Kind::Insertion), 3);
+        assert_eq!(stats.cigar_op_count(Kind::Deletion), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge() -> Result<(), Box<dyn std::error::Error>> {
+        let records = build_records()?;
+
+        let mut expected = Stats::default();
+
+        for record in &records {
+            expected.add_record(record)?;
+        }
+
+        let (left, right) = records.split_at(2);
+
+        let mut actual = Stats::default();
+
+        for record in left {
+            actual.add_record(record)?;
+        }
+
+        let mut shard = Stats::default();
+
+        for record in right {
+            shard.add_record(record)?;
+        }
+
+        actual.merge(&shard);
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+//! BAM record flag statistics.
+
+use std::fmt;
+
+use crate::Record;
+
+const MIN_HQ_MAPPING_QUALITY: u8 = 5;
+
+/// Flag statistics counts of a set of records.
+///
+/// The categories match those of `samtools flagstat`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Counts {
+    read: u64,
+    secondary: u64,
+    supplementary: u64,
+    duplicate: u64,
+    mapped: u64,
+    paired: u64,
+    read_1: u64,
+    read_2: u64,
+    proper_pair: u64,
+    mate_mapped: u64,
+    singleton: u64,
+    mate_reference_sequence_id_mismatch: u64,
+    mate_reference_sequence_id_mismatch_hq: u64,
+}
+
+impl Counts {
+    /// Returns the number of records.
+    pub fn read(&self) -> u64 {
+        self.read
+    }
+
+    /// Returns the number of secondary records.
+    pub fn secondary(&self) -> u64 {
+        self.secondary
+    }
+
+    /// Returns the number of supplementary records.
+    pub fn supplementary(&self) -> u64 {
+        self.supplementary
+    }
+
+    /// Returns the number of duplicate records.
+    pub fn duplicate(&self) -> u64 {
+        self.duplicate
+    }
+
+    /// Returns the number of mapped records.
+    pub fn mapped(&self) -> u64 {
+        self.mapped
+    }
+
+    /// Returns the number of primary records that are paired in sequencing.
+    pub fn paired(&self) -> u64 {
+        self.paired
+    }
+
+    /// Returns the number of primary paired records that are read 1.
+    pub fn read_1(&self) -> u64 {
+        self.read_1
+    }
+
+    /// Returns the number of primary paired records that are read 2.
+    pub fn read_2(&self) -> u64 {
+        self.read_2
+    }
+
+    /// Returns the number of primary mapped records that are properly paired.
+    pub fn proper_pair(&self) -> u64 {
+        self.proper_pair
+    }
+
+    /// Returns the number of primary mapped records whose mates are also mapped.
+    pub fn mate_mapped(&self) -> u64 {
+        self.mate_mapped
+    }
+
+    /// Returns the number of primary mapped records whose mates are unmapped.
+    pub fn singleton(&self) -> u64 {
+        self.singleton
+    }
+
+    /// Returns the number of primary mapped records whose mates are mapped to a different
+    /// reference sequence.
+    pub fn mate_reference_sequence_id_mismatch(&self) -> u64 {
+        self.mate_reference_sequence_id_mismatch
+    }
+
+    /// Returns the number of primary mapped records whose mates are mapped to a different
+    /// reference sequence and have a mapping quality of at least 5.
+    pub fn mate_reference_sequence_id_mismatch_hq(&self) -> u64 {
+        self.mate_reference_sequence_id_mismatch_hq
+    }
+
+    fn add_record(&mut self, record: &Record) {
+        let flags = record.flags();
+
+        self.read += 1;
+
+        if !flags.is_unmapped() {
+            self.mapped += 1;
+        }
+
+        if flags.is_duplicate() {
+            self.duplicate += 1;
+        }
+
+        if flags.is_secondary() {
+            self.secondary += 1;
+        } else if flags.is_supplementary() {
+            self.supplementary += 1;
+        } else if flags.is_paired() {
+            self.paired += 1;
+
+            if flags.is_read_1() {
+                self.read_1 += 1;
+            }
+
+            if flags.is_read_2() {
+                self.read_2 += 1;
+            }
+
+            if !flags.is_unmapped() {
+                if flags.is_proper_pair() {
+                    self.proper_pair += 1;
+                }
+
+                if flags.is_mate_unmapped() {
+                    self.singleton += 1;
+                } else {
+                    self.mate_mapped += 1;
+
+                    if record.mate_reference_sequence_id() != record.reference_sequence_id() {
+                        self.mate_reference_sequence_id_mismatch += 1;
+
+                        if u8::from(record.mapping_quality()) >= MIN_HQ_MAPPING_QUALITY {
+                            self.mate_reference_sequence_id_mismatch_hq += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.read += other.read;
+        self.secondary += other.secondary;
+        self.supplementary += other.supplementary;
+        self.duplicate += other.duplicate;
+        self.mapped += other.mapped;
+        self.paired += other.paired;
+        self.read_1 += other.read_1;
+        self.read_2 += other.read_2;
+        self.proper_pair += other.proper_pair;
+        self.mate_mapped += other.mate_mapped;
+        self.singleton += other.singleton;
+        self.mate_reference_sequence_id_mismatch += other.mate_reference_sequence_id_mismatch;
+        self.mate_reference_sequence_id_mismatch_hq += other.mate_reference_sequence_id_mismatch_hq;
+    }
+}
+
+/// Flag statistics of a set of records, split by QC pass and QC fail.
+///
+/// The display format matches the output of `samtools flagstat`.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_bam::{self as bam, stats::FlagStats};
+///
+/// let mut flag_stats = FlagStats::default();
+/// flag_stats.add_record(&bam::Record::default());
+///
+/// assert_eq!(flag_stats.qc_pass().read(), 1);
+/// assert_eq!(flag_stats.qc_pass().mapped(), 0);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FlagStats {
+    qc_pass: Counts,
+    qc_fail: Counts,
+}
+
+impl FlagStats {
+    /// Returns the counts of records that pass quality control.
+    pub fn qc_pass(&self) -> &Counts {
+        &self.qc_pass
+    }
+
+    /// Returns the counts of records that fail quality control.
+    pub fn qc_fail(&self) -> &Counts {
+        &self.qc_fail
+    }
+
+    /// Adds a record to the statistics.
+    pub fn add_record(&mut self, record: &Record) {
+        if record.flags().is_qc_fail() {
+            self.qc_fail.add_record(record);
+        } else {
+            self.qc_pass.add_record(record);
+        }
+    }
+
+    /// Merges the statistics of another set of records into these statistics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::{self as bam, stats::FlagStats};
+    ///
+    /// let mut a = FlagStats::default();
+    /// a.add_record(&bam::Record::default());
+    ///
+    /// let mut b = FlagStats::default();
+    /// b.add_record(&bam::Record::default());
+    ///
+    /// a.merge(&b);
+    /// assert_eq!(a.qc_pass().read(), 2);
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        self.qc_pass.merge(&other.qc_pass);
+        self.qc_fail.merge(&other.qc_fail);
+    }
+}
+
+struct PercentageFormat(u64, u64);
+
+impl fmt::Display for PercentageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.1 == 0 {
+            f.write_str("N/A")
+        } else {
+            let (a, b) = (self.0 as f64, self.1 as f64);
+            write!(f, "{:.2}%", a / b * 100.0)
+        }
+    }
+}
+
+impl fmt::Display for FlagStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (pass, fail) = (&self.qc_pass, &self.qc_fail);
+
+        writeln!(
+            f,
+            "{} + {} in total (QC-passed reads + QC-failed reads)",
+            pass.read, fail.read
+        )?;
+        writeln!(f, "{} + {} secondary", pass.secondary, fail.secondary)?;
+        writeln!(
+            f,
+            "{} + {} supplementary",
+            pass.supplementary, fail.supplementary
+        )?;
+        writeln!(f, "{} + {} duplicates", pass.duplicate, fail.duplicate)?;
+        writeln!(
+            f,
+            "{} + {} mapped ({} : {})",
+            pass.mapped,
+            fail.mapped,
+            PercentageFormat(pass.mapped, pass.read),
+            PercentageFormat(fail.mapped, fail.read)
+        )?;
+        writeln!(f, "{} + {} paired in sequencing", pass.paired, fail.paired)?;
+        writeln!(f, "{} + {} read1", pass.read_1, fail.read_1)?;
+        writeln!(f, "{} + {} read2", pass.read_2, fail.read_2)?;
+        writeln!(
+            f,
+            "{} + {} properly paired ({} : {})",
+            pass.proper_pair,
+            fail.proper_pair,
+            PercentageFormat(pass.proper_pair, pass.paired),
+            PercentageFormat(fail.proper_pair, fail.paired)
+        )?;
+        writeln!(
+            f,
+            "{} + {} with itself and mate mapped",
+            pass.mate_mapped, fail.mate_mapped
+        )?;
+        writeln!(
+            f,
+            "{} + {} singletons ({} : {})",
+            pass.singleton,
+            fail.singleton,
+            PercentageFormat(pass.singleton, pass.paired),
+            PercentageFormat(fail.singleton, fail.paired)
+        )?;
+        writeln!(
+            f,
+            "{} + {} with mate mapped to a different chr",
+            pass.mate_reference_sequence_id_mismatch, fail.mate_reference_sequence_id_mismatch
+        )?;
+        writeln!(
+            f,
+            "{} + {} with mate mapped to a different chr (mapQ>=5)",
+            pass.mate_reference_sequence_id_mismatch_hq,
+            fail.mate_reference_sequence_id_mismatch_hq
+        )
+    }
+}
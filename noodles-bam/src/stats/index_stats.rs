@@ -0,0 +1,134 @@
+//! BAM reference sequence record count statistics.
+
+use crate::{bai, Record};
+
+/// Mapped and unmapped record counts of a reference sequence.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ReferenceSequenceCounts {
+    mapped_record_count: u64,
+    unmapped_record_count: u64,
+}
+
+impl ReferenceSequenceCounts {
+    /// Returns the number of mapped records.
+    pub fn mapped_record_count(&self) -> u64 {
+        self.mapped_record_count
+    }
+
+    /// Returns the number of unmapped records that are placed on the reference sequence.
+    pub fn unmapped_record_count(&self) -> u64 {
+        self.unmapped_record_count
+    }
+}
+
+/// Per-reference sequence record counts.
+///
+/// These are the statistics reported by `samtools idxstats`. They can be read directly from the
+/// metadata pseudo-bins of a BAM index or accumulated from records.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_bam::{self as bam, stats::IndexStats};
+///
+/// let mut index_stats = IndexStats::default();
+/// index_stats.add_record(&bam::Record::default());
+///
+/// assert!(index_stats.reference_sequences().is_empty());
+/// assert_eq!(index_stats.unplaced_unmapped_record_count(), 1);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IndexStats {
+    reference_sequences: Vec<ReferenceSequenceCounts>,
+    unplaced_unmapped_record_count: u64,
+}
+
+impl IndexStats {
+    /// Returns the record counts of each reference sequence, indexed by reference sequence ID.
+    ///
+    /// When accumulated from records, this only extends to the last reference sequence that has
+    /// a record.
+    pub fn reference_sequences(&self) -> &[ReferenceSequenceCounts] {
+        &self.reference_sequences
+    }
+
+    /// Returns the number of unmapped records that are not placed on a reference sequence.
+    pub fn unplaced_unmapped_record_count(&self) -> u64 {
+        self.unplaced_unmapped_record_count
+    }
+
+    /// Adds a record to the statistics.
+    pub fn add_record(&mut self, record: &Record) {
+        let reference_sequence_id = match record.reference_sequence_id() {
+            Some(id) => i32::from(id) as usize,
+            None => {
+                self.unplaced_unmapped_record_count += 1;
+                return;
+            }
+        };
+
+        if reference_sequence_id >= self.reference_sequences.len() {
+            self.reference_sequences.resize(
+                reference_sequence_id + 1,
+                ReferenceSequenceCounts::default(),
+            );
+        }
+
+        let counts = &mut self.reference_sequences[reference_sequence_id];
+
+        if record.flags().is_unmapped() {
+            counts.unmapped_record_count += 1;
+        } else {
+            counts.mapped_record_count += 1;
+        }
+    }
+
+    /// Merges the statistics of another set of records into these statistics.
+    pub fn merge(&mut self, other: &Self) {
+        if other.reference_sequences.len() > self.reference_sequences.len() {
+            self.reference_sequences.resize(
+                other.reference_sequences.len(),
+                ReferenceSequenceCounts::default(),
+            );
+        }
+
+        for (counts, other_counts) in self
+            .reference_sequences
+            .iter_mut()
+            .zip(&other.reference_sequences)
+        {
+            counts.mapped_record_count += other_counts.mapped_record_count;
+            counts.unmapped_record_count += other_counts.unmapped_record_count;
+        }
+
+        self.unplaced_unmapped_record_count += other.unplaced_unmapped_record_count;
+    }
+}
+
+impl From<&bai::Index> for IndexStats {
+    /// Reads the statistics from the metadata pseudo-bins of a BAM index.
+    ///
+    /// Reference sequences without metadata have counts of 0.
+    fn from(index: &bai::Index) -> Self {
+        let reference_sequences = index
+            .reference_sequences()
+            .iter()
+            .map(|reference_sequence| {
+                reference_sequence
+                    .metadata()
+                    .map(|metadata| ReferenceSequenceCounts {
+                        mapped_record_count: metadata.mapped_record_count(),
+                        unmapped_record_count: metadata.unmapped_record_count(),
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        Self {
+            reference_sequences,
+            unplaced_unmapped_record_count: index
+                .unplaced_unmapped_read_count()
+                .unwrap_or_default(),
+        }
+    }
+}
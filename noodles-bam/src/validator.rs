@@ -0,0 +1,273 @@
+//! BAM record validation.
+
+mod error;
+
+pub use self::error::ValidationError;
+
+use std::{collections::HashSet, io};
+
+use noodles_sam::{self as sam, record::data::field::Tag, validator::is_valid_flags};
+
+use crate::{
+    record::{data::field::ValueRef, ReferenceSequenceId},
+    Record,
+};
+
+/// A BAM record validator.
+///
+/// A validator checks records against the SAM specification and a SAM header. A record is valid
+/// when
+///
+///   * its reference sequence ID and mate reference sequence ID, if set, are indices of the header
+///     reference sequences;
+///   * its position and mate position, if set, are within their reference sequence lengths
+///     (`@SQ LN`);
+///   * its CIGAR read length, if the CIGAR and sequence are set, is equal to the sequence length;
+///   * its bin is equal to the bin calculated from its position and CIGAR;
+///   * its flags do not set mate or segment flags on an unpaired read; and
+///   * its read group (`RG`) data field, if set, references a header read group.
+///
+/// Unlike SAM, the number of quality scores in a BAM record is always the sequence length.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bam::{self as bam, validator::Validator};
+/// use noodles_sam as sam;
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// let header: sam::Header = reader
+///     .read_header()?
+///     .parse()
+///     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+/// reader.read_reference_sequences()?;
+///
+/// let validator = Validator::new(&header);
+///
+/// for result in reader.records() {
+///     let record = result?;
+///
+///     for e in validator.validate(&record)? {
+///         eprintln!("{:?}: {}", record.read_name(), e);
+///     }
+/// }
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Validator {
+    reference_sequence_lens: Vec<i32>,
+    read_group_ids: HashSet<String>,
+}
+
+impl Validator {
+    /// Creates a validator for records described by the given header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::validator::Validator;
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    /// let validator = Validator::new(&header);
+    /// ```
+    pub fn new(header: &sam::Header) -> Self {
+        Self {
+            reference_sequence_lens: header
+                .reference_sequences()
+                .values()
+                .map(|reference_sequence| reference_sequence.len())
+                .collect(),
+            read_group_ids: header.read_groups().keys().cloned().collect(),
+        }
+    }
+
+    /// Validates a record and returns all of its errors.
+    ///
+    /// The returned list is empty if the record is valid. An I/O error is returned if the record
+    /// cannot be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::{self as bam, validator::Validator};
+    /// use noodles_sam as sam;
+    ///
+    /// let header = sam::Header::default();
+    /// let validator = Validator::new(&header);
+    ///
+    /// let record = bam::Record::default();
+    /// assert!(validator.validate(&record)?.is_empty());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn validate(&self, record: &Record) -> io::Result<Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if let Some(id) = record.reference_sequence_id() {
+            match self.reference_sequence_len(id) {
+                Some(reference_sequence_len) => {
+                    if let Some(position) = record.position().map(i32::from) {
+                        if position > reference_sequence_len {
+                            errors.push(ValidationError::PositionOutOfRange {
+                                position,
+                                reference_sequence_len,
+                            });
+                        }
+                    }
+                }
+                None => errors.push(ValidationError::InvalidReferenceSequenceId(i32::from(id))),
+            }
+        }
+
+        if let Some(id) = record.mate_reference_sequence_id() {
+            match self.reference_sequence_len(id) {
+                Some(reference_sequence_len) => {
+                    if let Some(mate_position) = record.mate_position().map(i32::from) {
+                        if mate_position > reference_sequence_len {
+                            errors.push(ValidationError::MatePositionOutOfRange {
+                                mate_position,
+                                reference_sequence_len,
+                            });
+                        }
+                    }
+                }
+                None => errors.push(ValidationError::InvalidMateReferenceSequenceId(i32::from(
+                    id,
+                ))),
+            }
+        }
+
+        let cigar = record.cigar();
+        let sequence_len = record.sequence().base_count() as u32;
+
+        if !cigar.is_empty() && sequence_len > 0 {
+            let cigar_read_len = cigar.read_len()?;
+
+            if cigar_read_len != sequence_len {
+                errors.push(ValidationError::CigarReadLengthMismatch {
+                    cigar_read_len,
+                    sequence_len,
+                });
+            }
+        }
+
+        let actual_bin = record.bin();
+        let expected_bin = record.calculate_bin()?;
+
+        if actual_bin != expected_bin {
+            errors.push(ValidationError::InvalidBin {
+                actual: actual_bin,
+                expected: expected_bin,
+            });
+        }
+
+        let flags = record.flags();
+
+        if !is_valid_flags(flags) {
+            errors.push(ValidationError::InvalidFlags(flags));
+        }
+
+        if let Some(result) = record.data().get(&Tag::ReadGroup) {
+            match result? {
+                ValueRef::String(id) if self.read_group_ids.contains(id) => {}
+                ValueRef::String(id) => {
+                    errors.push(ValidationError::InvalidReadGroup(id.into()));
+                }
+                value => errors.push(ValidationError::InvalidReadGroup(format!("{:?}", value))),
+            }
+        }
+
+        Ok(errors)
+    }
+
+    fn reference_sequence_len(&self, id: ReferenceSequenceId) -> Option<i32> {
+        let i = i32::from(id) as usize;
+        self.reference_sequence_lens.get(i).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use noodles_sam::{
+        header::{ReadGroup, ReferenceSequence},
+        record::{Flags, Position},
+    };
+
+    use crate::record::data::{field::Value, Field};
+
+    use super::*;
+
+    fn build_header() -> sam::Header {
+        sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .add_read_group(ReadGroup::new(String::from("rg0")))
+            .build()
+    }
+
+    fn build_record(
+        flags: Flags,
+        position: i32,
+        cigar: &str,
+        sequence: &str,
+        read_group: &str,
+    ) -> Result<Record, Box<dyn std::error::Error>> {
+        let mut record = Record::default();
+        record.set_flags(flags);
+        record.set_reference_sequence_id(Some(ReferenceSequenceId::try_from(0)?));
+        record.set_position(Some(Position::try_from(position)?))?;
+        record.set_cigar(&cigar.parse()?)?;
+        record.set_sequence(&sequence.parse()?)?;
+        record.insert_data_field(Field::new(
+            Tag::ReadGroup,
+            Value::String(String::from(read_group)),
+        ))?;
+        Ok(record)
+    }
+
+    #[test]
+    fn test_validate() -> Result<(), Box<dyn std::error::Error>> {
+        let validator = Validator::new(&build_header());
+
+        let record = build_record(Flags::PAIRED | Flags::READ_1, 7, "2M", "AC", "rg0")?;
+        assert!(validator.validate(&record)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_invalid_record() -> Result<(), Box<dyn std::error::Error>> {
+        let validator = Validator::new(&build_header());
+
+        let mut record = build_record(Flags::READ_2, 9, "3M", "AC", "rg1")?;
+        record.set_mate_reference_sequence_id(Some(ReferenceSequenceId::try_from(1)?));
+        record[10] = 0;
+        record[11] = 0;
+
+        assert_eq!(
+            validator.validate(&record)?,
+            [
+                ValidationError::PositionOutOfRange {
+                    position: 9,
+                    reference_sequence_len: 8,
+                },
+                ValidationError::InvalidMateReferenceSequenceId(1),
+                ValidationError::CigarReadLengthMismatch {
+                    cigar_read_len: 3,
+                    sequence_len: 2,
+                },
+                ValidationError::InvalidBin {
+                    actual: 0,
+                    expected: 4681,
+                },
+                ValidationError::InvalidFlags(Flags::READ_2),
+                ValidationError::InvalidReadGroup(String::from("rg1")),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use std::{error, fmt};
+
+use noodles_sam::record::Flags;
+
+/// An error describing an invalid BAM record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+    /// The reference sequence ID is not an index of the header reference sequences.
+    InvalidReferenceSequenceId(i32),
+    /// The mate reference sequence ID is not an index of the header reference sequences.
+    InvalidMateReferenceSequenceId(i32),
+    /// The position is past the end of the reference sequence.
+    PositionOutOfRange {
+        /// The 1-based position.
+        position: i32,
+        /// The length of the reference sequence.
+        reference_sequence_len: i32,
+    },
+    /// The mate position is past the end of the mate reference sequence.
+    MatePositionOutOfRange {
+        /// The 1-based mate position.
+        mate_position: i32,
+        /// The length of the mate reference sequence.
+        reference_sequence_len: i32,
+    },
+    /// The read length calculated from the CIGAR does not match the sequence length.
+    CigarReadLengthMismatch {
+        /// The read length calculated from the CIGAR.
+        cigar_read_len: u32,
+        /// The sequence length.
+        sequence_len: u32,
+    },
+    /// The bin does not match the bin calculated from the position and CIGAR.
+    InvalidBin {
+        /// The stored bin.
+        actual: u16,
+        /// The calculated bin.
+        expected: u16,
+    },
+    /// The flags are inconsistent, e.g., mate flags are set on an unpaired read.
+    InvalidFlags(Flags),
+    /// The read group (`RG`) data field does not reference a header read group.
+    InvalidReadGroup(String),
+}
+
+impl error::Error for ValidationError {}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidReferenceSequenceId(id) => {
+                write!(f, "invalid reference sequence ID: {}", id)
+            }
+            Self::InvalidMateReferenceSequenceId(id) => {
+                write!(f, "invalid mate reference sequence ID: {}", id)
+            }
+            Self::PositionOutOfRange {
+                position,
+                reference_sequence_len,
+            } => write!(
+                f,
+                "position out of range: expected <= {}, got {}",
+                reference_sequence_len, position
+            ),
+            Self::MatePositionOutOfRange {
+                mate_position,
+                reference_sequence_len,
+            } => write!(
+                f,
+                "mate position out of range: expected <= {}, got {}",
+                reference_sequence_len, mate_position
+            ),
+            Self::CigarReadLengthMismatch {
+                cigar_read_len,
+                sequence_len,
+            } => write!(
+                f,
+                "CIGAR read length mismatch: expected {}, got {}",
+                sequence_len, cigar_read_len
+            ),
+            Self::InvalidBin { actual, expected } => {
+                write!(f, "invalid bin: expected {}, got {}", expected, actual)
+            }
+            Self::InvalidFlags(flags) => write!(f, "invalid flags: {:?}", flags),
+            Self::InvalidReadGroup(id) => write!(f, "invalid read group: {}", id),
+        }
+    }
+}
@@ -1,5 +1,10 @@
+//! BAM writer.
+
+mod builder;
 pub(crate) mod record;
 
+pub use self::builder::Builder;
+
 use std::{
     ffi::CString,
     io::{self, Write},
@@ -12,7 +17,11 @@ use noodles_sam::{
     header::{ReferenceSequence, ReferenceSequences},
 };
 
-use super::{Record, MAGIC_NUMBER};
+use super::{
+    bai::{self, index::reference_sequence::bin::Chunk},
+    validator::Validator,
+    Record, MAGIC_NUMBER,
+};
 
 /// A BAM writer.
 ///
@@ -41,6 +50,10 @@ where
     W: Write,
 {
     inner: bgzf::Writer<W>,
+    reference_sequence_count: usize,
+    index_builder: Option<bai::index::Builder>,
+    last_sort_key: Option<SortKey>,
+    validator: Option<Validator>,
 }
 
 impl<W> Writer<W>
@@ -58,9 +71,19 @@ where
     /// let writer = bam::Writer::new(Vec::new());
     /// ```
     pub fn new(writer: W) -> Self {
-        Self {
-            inner: bgzf::Writer::new(writer),
-        }
+        Builder::new(writer).build()
+    }
+
+    /// Creates a BAM writer builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let writer = bam::Writer::builder(Vec::new()).set_build_index(true).build();
+    /// ```
+    pub fn builder(writer: W) -> Builder<W> {
+        Builder::new(writer)
     }
 
     /// Returns a reference to the underlying writer.
@@ -76,6 +99,23 @@ where
         self.inner.get_ref()
     }
 
+    /// Returns the current virtual position of the underlying BGZF writer.
+    ///
+    /// This is the virtual position at which the next record will start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let writer = bam::Writer::new(Vec::new());
+    /// let virtual_position = writer.virtual_position();
+    /// assert_eq!(virtual_position.compressed(), 0);
+    /// assert_eq!(virtual_position.uncompressed(), 0);
+    /// ```
+    pub fn virtual_position(&self) -> bgzf::VirtualPosition {
+        self.inner.virtual_position()
+    }
+
     /// Attempts to finish the output stream.
     ///
     /// This is typically only manually called if the underlying stream is needed before the writer
@@ -94,6 +134,42 @@ where
         self.inner.try_finish()
     }
 
+    /// Finishes the output stream and returns the underlying writer and, if enabled, the BAM
+    /// index built from the written records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam as bam;
+    /// use noodles_sam as sam;
+    ///
+    /// let mut writer = bam::Writer::builder(Vec::new()).set_build_index(true).build();
+    ///
+    /// let header = sam::Header::builder()
+    ///     .add_reference_sequence(sam::header::ReferenceSequence::new(String::from("sq0"), 8))
+    ///     .build();
+    ///
+    /// writer.write_header(&header)?;
+    /// writer.write_reference_sequences(header.reference_sequences())?;
+    ///
+    /// let (_, index) = writer.finish()?;
+    /// let index = index.unwrap();
+    /// assert_eq!(index.reference_sequences().len(), 1);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn finish(self) -> io::Result<(W, Option<bai::Index>)> {
+        let reference_sequence_count = self.reference_sequence_count;
+
+        let index = self
+            .index_builder
+            .map(|builder| builder.build(reference_sequence_count));
+
+        let inner = self.inner.finish()?;
+
+        Ok((inner, index))
+    }
+
     /// Writes a SAM header.
     ///
     /// # Examples
@@ -110,6 +186,10 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn write_header(&mut self, header: &sam::Header) -> io::Result<()> {
+        if let Some(validator) = self.validator.as_mut() {
+            *validator = Validator::new(header);
+        }
+
         self.inner.write_all(MAGIC_NUMBER)?;
 
         let text = header.to_string();
@@ -148,6 +228,8 @@ where
         &mut self,
         reference_sequences: &ReferenceSequences,
     ) -> io::Result<()> {
+        self.reference_sequence_count = reference_sequences.len();
+
         let n_ref = reference_sequences.len() as i32;
         self.inner.write_i32::<LittleEndian>(n_ref)?;
 
@@ -160,6 +242,10 @@ where
 
     /// Writes a BAM record.
     ///
+    /// If the writer is building an index, the record is added to it, and an error is returned if
+    /// the record is not in coordinate-sorted order. In strict mode, an error is returned if the
+    /// record is invalid.
+    ///
     /// # Examples
     ///
     /// ```
@@ -171,9 +257,40 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        if let Some(validator) = self.validator.as_ref() {
+            if let Some(e) = validator.validate(record)?.into_iter().next() {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, e));
+            }
+        }
+
+        if self.index_builder.is_some() {
+            let sort_key = SortKey::from(record);
+
+            if let Some(last_sort_key) = self.last_sort_key {
+                if sort_key < last_sort_key {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "records must be coordinate-sorted to build an index",
+                    ));
+                }
+            }
+
+            self.last_sort_key = Some(sort_key);
+        }
+
+        let start_position = self.inner.virtual_position();
+
         let block_size = record.len() as u32;
         self.inner.write_u32::<LittleEndian>(block_size)?;
-        self.inner.write_all(record)
+        self.inner.write_all(record)?;
+
+        if let Some(index_builder) = self.index_builder.as_mut() {
+            let end_position = self.inner.virtual_position();
+            let chunk = Chunk::new(start_position, end_position);
+            index_builder.add_record(record, chunk)?;
+        }
+
+        Ok(())
     }
 
     /// Writes a SAM record.
@@ -197,7 +314,29 @@ where
         reference_sequences: &ReferenceSequences,
         record: &sam::Record,
     ) -> io::Result<()> {
-        record::write_sam_record(&mut self.inner, reference_sequences, record)
+        if self.index_builder.is_some() || self.validator.is_some() {
+            let record = Record::try_from_sam_record(reference_sequences, record)?;
+            self.write_record(&record)
+        } else {
+            record::write_sam_record(&mut self.inner, reference_sequences, record)
+        }
+    }
+}
+
+// Records are coordinate-sorted by reference sequence ID and position. Records with no reference
+// sequence ID are placed last.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct SortKey(bool, i32, i32);
+
+impl From<&Record> for SortKey {
+    fn from(record: &Record) -> Self {
+        match record.reference_sequence_id() {
+            Some(id) => {
+                let position = record.position().map(i32::from).unwrap_or_default();
+                Self(false, i32::from(id), position)
+            }
+            None => Self(true, 0, 0),
+        }
     }
 }
 
@@ -447,4 +586,132 @@ mod tests {
 
         Ok(())
     }
+
+    fn build_indexed_header() -> sam::Header {
+        sam::Header::builder()
+            .add_reference_sequence(sam::header::ReferenceSequence::new(String::from("sq0"), 8))
+            .add_reference_sequence(sam::header::ReferenceSequence::new(String::from("sq1"), 13))
+            .build()
+    }
+
+    fn build_record(
+        reference_sequence_name: &str,
+        position: i32,
+    ) -> Result<sam::Record, Box<dyn std::error::Error>> {
+        use std::convert::TryFrom;
+
+        Ok(sam::Record::builder()
+            .set_flags(sam::record::Flags::empty())
+            .set_reference_sequence_name(reference_sequence_name.parse()?)
+            .set_position(sam::record::Position::try_from(position)?)
+            .set_cigar("4M".parse()?)
+            .build())
+    }
+
+    #[test]
+    fn test_finish_with_index() -> Result<(), Box<dyn std::error::Error>> {
+        let header = build_indexed_header();
+        let reference_sequences = header.reference_sequences();
+
+        let mut writer = Writer::builder(Vec::new()).set_build_index(true).build();
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(reference_sequences)?;
+
+        writer.write_sam_record(reference_sequences, &build_record("sq0", 1)?)?;
+        writer.write_sam_record(reference_sequences, &build_record("sq0", 3)?)?;
+        writer.write_sam_record(reference_sequences, &build_record("sq1", 5)?)?;
+        writer.write_sam_record(reference_sequences, &sam::Record::default())?;
+
+        let (data, index) = writer.finish()?;
+        let actual = index.expect("missing index");
+
+        // Build the expected index by rereading the written BAM.
+        let mut reader = Reader::new(&data[..]);
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        let mut builder = bai::Index::builder();
+        let mut record = Record::default();
+        let mut start_position = reader.virtual_position();
+
+        while reader.read_record(&mut record)? != 0 {
+            let end_position = reader.virtual_position();
+            builder.add_record(&record, Chunk::new(start_position, end_position))?;
+            start_position = end_position;
+        }
+
+        let expected = builder.build(reference_sequences.len());
+
+        assert_eq!(actual, expected);
+        assert_eq!(actual.unplaced_unmapped_read_count(), Some(1));
+
+        let metadata = actual.reference_sequences()[0]
+            .metadata()
+            .expect("missing metadata");
+        assert_eq!(metadata.mapped_record_count(), 2);
+        assert_eq!(metadata.unmapped_record_count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finish_without_index() -> io::Result<()> {
+        let writer = Writer::new(Vec::new());
+        let (_, index) = writer.finish()?;
+        assert!(index.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_record_with_index_and_unsorted_records() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let header = build_indexed_header();
+        let reference_sequences = header.reference_sequences();
+
+        let mut writer = Writer::builder(Vec::new()).set_build_index(true).build();
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(reference_sequences)?;
+
+        writer.write_sam_record(reference_sequences, &build_record("sq1", 5)?)?;
+
+        assert!(matches!(
+            writer.write_sam_record(reference_sequences, &build_record("sq0", 1)?),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        writer.write_sam_record(reference_sequences, &sam::Record::default())?;
+
+        assert!(writer
+            .write_sam_record(reference_sequences, &build_record("sq1", 8)?)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_record_with_strict_mode() -> Result<(), Box<dyn std::error::Error>> {
+        let header = build_indexed_header();
+        let reference_sequences = header.reference_sequences();
+
+        let mut writer = Writer::builder(Vec::new()).set_strict(true).build();
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(reference_sequences)?;
+
+        writer.write_sam_record(reference_sequences, &build_record("sq1", 13)?)?;
+
+        assert!(matches!(
+            writer.write_sam_record(reference_sequences, &build_record("sq0", 9)?),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        let mut record = Record::default();
+        record.set_flags(sam::record::Flags::MATE_UNMAPPED);
+
+        assert!(matches!(
+            writer.write_record(&record),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,118 @@
+use std::io::Write;
+
+use noodles_bgzf::{self as bgzf, writer::CompressionLevel};
+
+use super::Writer;
+use crate::{bai, validator::Validator};
+
+/// A BAM writer builder.
+pub struct Builder<W> {
+    inner: W,
+    compression_level: CompressionLevel,
+    build_index: bool,
+    strict: bool,
+}
+
+impl<W> Builder<W>
+where
+    W: Write,
+{
+    pub(crate) fn new(inner: W) -> Self {
+        Self {
+            inner,
+            compression_level: CompressionLevel::default(),
+            build_index: false,
+            strict: false,
+        }
+    }
+
+    /// Sets the compression level of the BGZF stream.
+    ///
+    /// By default, the compression level is 6.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// use noodles_bgzf::writer::CompressionLevel;
+    ///
+    /// let writer = bam::Writer::builder(Vec::new())
+    ///     .set_compression_level(CompressionLevel::FAST)
+    ///     .build();
+    /// ```
+    pub fn set_compression_level(mut self, compression_level: CompressionLevel) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    /// Sets whether to build a BAM index (BAI) while writing records.
+    ///
+    /// When enabled, records must be written in coordinate-sorted order, and the index is
+    /// returned by [`Writer::finish`].
+    ///
+    /// By default, no index is built.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let writer = bam::Writer::builder(Vec::new()).set_build_index(true).build();
+    /// ```
+    pub fn set_build_index(mut self, build_index: bool) -> Self {
+        self.build_index = build_index;
+        self
+    }
+
+    /// Sets whether to validate records before writing them.
+    ///
+    /// When enabled, each record is checked by a [`Validator`] for the header passed to
+    /// [`Writer::write_header`], and an error is returned for an invalid record. If no header is
+    /// written, records are validated against an empty header.
+    ///
+    /// By default, records are not validated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let writer = bam::Writer::builder(Vec::new()).set_strict(true).build();
+    /// ```
+    pub fn set_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Builds a BAM writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let writer = bam::Writer::builder(Vec::new()).build();
+    /// ```
+    pub fn build(self) -> Writer<W> {
+        let inner = bgzf::Writer::builder(self.inner)
+            .set_compression_level(self.compression_level)
+            .build();
+
+        let index_builder = if self.build_index {
+            Some(bai::Index::builder())
+        } else {
+            None
+        };
+
+        let validator = if self.strict {
+            Some(Validator::default())
+        } else {
+            None
+        };
+
+        Writer {
+            inner,
+            reference_sequence_count: 0,
+            index_builder,
+            last_sort_key: None,
+            validator,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     cmp,
     ffi::CString,
     io::{self, Write},
@@ -9,10 +10,17 @@ use byteorder::{LittleEndian, WriteBytesExt};
 use noodles_sam::{
     self as sam,
     header::ReferenceSequences,
-    record::{Cigar, Data, QualityScores, Sequence},
+    record::{
+        cigar::{op::Kind, Op},
+        data::{
+            self,
+            field::{Tag, Value},
+        },
+        Cigar, Data, QualityScores, Sequence,
+    },
 };
 
-use crate::record::sequence::Base;
+use crate::record::{calculate_bin, sequence::Base};
 
 // § 4.2 The BAM format (2020-04-30)
 //
@@ -21,10 +29,10 @@ use crate::record::sequence::Base;
 const BLOCK_HEADER_SIZE: usize = 32;
 
 // § 4.2.1 BIN field calculation (2020-04-30)
-const UNMAPPED_BIN: u16 = 4680;
+pub(crate) const UNMAPPED_BIN: u16 = 4680;
 
 // § 4.2.3 SEQ and QUAL encoding (2020-04-30)
-const NULL_QUALITY_SCORE: u8 = 255;
+pub(crate) const NULL_QUALITY_SCORE: u8 = 255;
 
 pub fn write_sam_record<W>(
     writer: &mut W,
@@ -60,9 +68,11 @@ where
 
     let read_name = c_read_name.as_bytes_with_nul();
     let l_read_name = read_name.len() as u8;
-    let n_cigar_op = record.cigar().len() as u16;
     let l_seq = record.sequence().len() as i32;
-    let data_len = calculate_data_len(record.data()) as i32;
+
+    let (cigar, data) = encode_cigar(record);
+    let n_cigar_op = cigar.len() as u16;
+    let data_len = calculate_data_len(&data) as i32;
 
     let block_size = BLOCK_HEADER_SIZE as i32
         + i32::from(l_read_name)
@@ -89,13 +99,7 @@ where
 
     let bin = record
         .position()
-        .map(i32::from)
-        .map(|start| {
-            // 0-based, [start, end)
-            let reference_len = record.cigar().reference_len() as i32;
-            let end = start + reference_len;
-            region_to_bin(start, end) as u16
-        })
+        .map(|position| calculate_bin(position, record.cigar().reference_len()))
         .unwrap_or(UNMAPPED_BIN);
 
     writer.write_u16::<LittleEndian>(bin)?;
@@ -121,7 +125,7 @@ where
 
     writer.write_all(read_name)?;
 
-    write_cigar(writer, record.cigar())?;
+    write_cigar(writer, &cigar)?;
 
     // § 4.2.3 SEQ and QUAL encoding (2020-04-30)
     let sequence = record.sequence();
@@ -153,26 +157,58 @@ where
         }
     }
 
-    write_data(writer, record.data())?;
+    write_data(writer, &data)?;
 
     Ok(())
 }
 
-fn write_cigar<W>(writer: &mut W, cigar: &Cigar) -> io::Result<()>
+// § 4.2.2 `N_CIGAR_OP` field (2020-04-30)
+//
+// A CIGAR with more operations than can be counted by `n_cigar_op` is stored in a `CG` data
+// field. The CIGAR is then replaced with the placeholder `kSmN`, where `k` is the read length and
+// `m` is the reference length.
+fn encode_cigar(record: &sam::Record) -> (Cow<'_, Cigar>, Cow<'_, Data>) {
+    let cigar = record.cigar();
+
+    if cigar.len() <= usize::from(u16::MAX) {
+        return (Cow::Borrowed(cigar), Cow::Borrowed(record.data()));
+    }
+
+    let placeholder = Cigar::from(vec![
+        Op::new(Kind::SoftClip, record.sequence().len() as u32),
+        Op::new(Kind::Skip, cigar.reference_len()),
+    ]);
+
+    let ops = cigar.iter().copied().map(encode_cigar_op).collect();
+
+    let mut fields: Vec<_> = record
+        .data()
+        .iter()
+        .filter(|field| field.tag() != &Tag::Cigar)
+        .cloned()
+        .collect();
+
+    fields.push(data::Field::new(Tag::Cigar, Value::UInt32Array(ops)));
+
+    (Cow::Owned(placeholder), Cow::Owned(Data::from(fields)))
+}
+
+pub(crate) fn write_cigar<W>(writer: &mut W, cigar: &Cigar) -> io::Result<()>
 where
     W: Write,
 {
-    for op in cigar.iter() {
-        let len = op.len() as u32;
-        let kind = op.kind() as u32;
-        let value = len << 4 | kind;
-        writer.write_u32::<LittleEndian>(value)?;
+    for &op in cigar.iter() {
+        writer.write_u32::<LittleEndian>(encode_cigar_op(op))?;
     }
 
     Ok(())
 }
 
-fn write_seq<W>(writer: &mut W, sequence: &Sequence) -> io::Result<()>
+pub(crate) fn encode_cigar_op(op: Op) -> u32 {
+    op.len() << 4 | op.kind() as u32
+}
+
+pub(crate) fn write_seq<W>(writer: &mut W, sequence: &Sequence) -> io::Result<()>
 where
     W: Write,
 {
@@ -193,7 +229,7 @@ where
     Ok(())
 }
 
-fn write_qual<W>(writer: &mut W, quality_scores: &QualityScores) -> io::Result<()>
+pub(crate) fn write_qual<W>(writer: &mut W, quality_scores: &QualityScores) -> io::Result<()>
 where
     W: Write,
 {
@@ -369,6 +405,80 @@ where
     Ok(())
 }
 
+pub(crate) fn write_data_field<W>(
+    writer: &mut W,
+    field: &crate::record::data::Field,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    use crate::record::data::field::Value;
+
+    writer.write_all(field.tag().as_ref().as_bytes())?;
+
+    let value = field.value();
+
+    writer.write_u8(char::from(value.ty()) as u8)?;
+
+    if let Some(subtype) = value.subtype() {
+        writer.write_u8(char::from(subtype) as u8)?;
+    }
+
+    match value {
+        Value::Char(c) => writer.write_u8(*c as u8),
+        Value::Int8(n) => writer.write_i8(*n),
+        Value::UInt8(n) => writer.write_u8(*n),
+        Value::Int16(n) => writer.write_i16::<LittleEndian>(*n),
+        Value::UInt16(n) => writer.write_u16::<LittleEndian>(*n),
+        Value::Int32(n) => writer.write_i32::<LittleEndian>(*n),
+        Value::UInt32(n) => writer.write_u32::<LittleEndian>(*n),
+        Value::Float(n) => writer.write_f32::<LittleEndian>(*n),
+        Value::String(s) | Value::Hex(s) => {
+            let c_str = CString::new(s.as_bytes())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            writer.write_all(c_str.as_bytes_with_nul())
+        }
+        Value::Int8Array(values) => {
+            writer.write_u32::<LittleEndian>(values.len() as u32)?;
+            values.iter().try_for_each(|&n| writer.write_i8(n))
+        }
+        Value::UInt8Array(values) => {
+            writer.write_u32::<LittleEndian>(values.len() as u32)?;
+            writer.write_all(values)
+        }
+        Value::Int16Array(values) => {
+            writer.write_u32::<LittleEndian>(values.len() as u32)?;
+            values
+                .iter()
+                .try_for_each(|&n| writer.write_i16::<LittleEndian>(n))
+        }
+        Value::UInt16Array(values) => {
+            writer.write_u32::<LittleEndian>(values.len() as u32)?;
+            values
+                .iter()
+                .try_for_each(|&n| writer.write_u16::<LittleEndian>(n))
+        }
+        Value::Int32Array(values) => {
+            writer.write_u32::<LittleEndian>(values.len() as u32)?;
+            values
+                .iter()
+                .try_for_each(|&n| writer.write_i32::<LittleEndian>(n))
+        }
+        Value::UInt32Array(values) => {
+            writer.write_u32::<LittleEndian>(values.len() as u32)?;
+            values
+                .iter()
+                .try_for_each(|&n| writer.write_u32::<LittleEndian>(n))
+        }
+        Value::FloatArray(values) => {
+            writer.write_u32::<LittleEndian>(values.len() as u32)?;
+            values
+                .iter()
+                .try_for_each(|&n| writer.write_f32::<LittleEndian>(n))
+        }
+    }
+}
+
 fn write_data_i32_value<W>(writer: &mut W, n: i32) -> io::Result<()>
 where
     W: Write,
@@ -401,7 +511,7 @@ where
 // § 5.3 C source code for computing bin number and overlapping bins (2020-04-30)
 // 0-based, [start, end)
 #[allow(clippy::eq_op)]
-fn region_to_bin(start: i32, mut end: i32) -> i32 {
+pub(crate) fn region_to_bin(start: i32, mut end: i32) -> i32 {
     end -= 1;
 
     if start >> 14 == end >> 14 {
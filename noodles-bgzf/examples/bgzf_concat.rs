@@ -0,0 +1,20 @@
+//! Concatenates BGZF files without recompressing the data.
+//!
+//! The result is similar to the output of `cat <srcs...>`, except that interior EOF markers are
+//! dropped.
+
+use std::{env, fs::File, io};
+
+use noodles_bgzf as bgzf;
+
+fn main() -> io::Result<()> {
+    let readers = env::args()
+        .skip(1)
+        .map(File::open)
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+
+    bgzf::concat(readers, &mut writer)
+}
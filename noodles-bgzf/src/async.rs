@@ -0,0 +1,13 @@
+//! Async BGZF reader and writer.
+//!
+//! These are [tokio]-based counterparts to [`crate::Reader`] and [`crate::Writer`], implementing
+//! [`tokio::io::AsyncRead`]/[`tokio::io::AsyncBufRead`] and [`tokio::io::AsyncWrite`],
+//! respectively.
+//!
+//! [tokio]: https://tokio.rs/
+
+mod block_codec;
+pub mod reader;
+pub mod writer;
+
+pub use self::{reader::Reader, writer::Writer};
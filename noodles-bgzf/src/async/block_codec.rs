@@ -0,0 +1,76 @@
+use std::io;
+
+use byteorder::{ByteOrder, LittleEndian};
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::Decoder;
+
+use crate::{gz, BGZF_HEADER_SIZE};
+
+/// A decoder that splits a BGZF stream into raw blocks.
+///
+/// Each frame is an entire block, including its header and trailer.
+pub(crate) struct BlockCodec;
+
+impl Decoder for BlockCodec {
+    type Item = Bytes;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < BGZF_HEADER_SIZE {
+            src.reserve(BGZF_HEADER_SIZE - src.len());
+            return Ok(None);
+        }
+
+        let bsize = LittleEndian::read_u16(&src[16..18]);
+
+        // Add 1 because BSIZE is "total Block SIZE minus 1".
+        let block_size = usize::from(bsize) + 1;
+
+        if block_size < BGZF_HEADER_SIZE + gz::TRAILER_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid BGZF block size",
+            ));
+        }
+
+        if src.len() < block_size {
+            src.reserve(block_size - src.len());
+            return Ok(None);
+        }
+
+        Ok(Some(src.split_to(block_size).freeze()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::writer::BGZF_EOF;
+
+    use super::*;
+
+    #[test]
+    fn test_decode() -> io::Result<()> {
+        let mut codec = BlockCodec;
+
+        let mut src = BytesMut::from(&BGZF_EOF[..8]);
+        assert!(codec.decode(&mut src)?.is_none());
+
+        src.extend_from_slice(&BGZF_EOF[8..]);
+        src.extend_from_slice(&BGZF_EOF[..4]);
+        assert_eq!(codec.decode(&mut src)?, Some(Bytes::from_static(BGZF_EOF)));
+        assert_eq!(&src[..], &BGZF_EOF[..4]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_with_invalid_block_size() {
+        let mut data = BGZF_EOF.to_vec();
+        data[16] = 0x00;
+        let mut src = BytesMut::from(&data[..]);
+        assert!(matches!(
+            BlockCodec.decode(&mut src),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+}
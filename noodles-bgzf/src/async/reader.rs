@@ -0,0 +1,419 @@
+//! Async BGZF reader.
+
+use std::{
+    cmp,
+    convert::TryFrom,
+    io::{self, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use byteorder::{ByteOrder, LittleEndian};
+use futures::{ready, Stream};
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncSeekExt, ReadBuf};
+use tokio_util::codec::FramedRead;
+
+use super::block_codec::BlockCodec;
+use crate::{gz, gzi, reader::inflate_block, Block, VirtualPosition, BGZF_HEADER_SIZE};
+
+pin_project! {
+    /// An async BGZF reader.
+    ///
+    /// This implements [`tokio::io::AsyncRead`] and [`tokio::io::AsyncBufRead`], consuming
+    /// compressed data and emitting uncompressed data. Like [`crate::Reader`], it is internally
+    /// buffered by a single block and _cannot_ be double buffered.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// use noodles_bgzf as bgzf;
+    /// use tokio::{fs::File, io::AsyncReadExt};
+    ///
+    /// let mut reader = File::open("data.gz").await.map(bgzf::r#async::Reader::new)?;
+    ///
+    /// let mut data = Vec::new();
+    /// reader.read_to_end(&mut data).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub struct Reader<R> {
+        #[pin]
+        inner: FramedRead<R, BlockCodec>,
+        position: u64,
+        block: Block,
+    }
+}
+
+impl<R> Reader<R>
+where
+    R: AsyncRead,
+{
+    /// Creates an async BGZF reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::r#async::Reader::new(&data[..]);
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: FramedRead::new(inner, BlockCodec),
+            position: 0,
+            block: Block::default(),
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::r#async::Reader::new(&data[..]);
+    /// assert!(reader.get_ref().is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &R {
+        self.inner.get_ref()
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from the underlying reader directly is likely to corrupt the BGZF stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let mut reader = bgzf::r#async::Reader::new(&data[..]);
+    /// assert!(reader.get_mut().is_empty());
+    /// ```
+    pub fn get_mut(&mut self) -> &mut R {
+        self.inner.get_mut()
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::r#async::Reader::new(&data[..]);
+    /// assert!(reader.into_inner().is_empty());
+    /// ```
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+
+    /// Returns the current position of the stream.
+    ///
+    /// This is the position of the end of the last block read in the compressed stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::r#async::Reader::new(&data[..]);
+    /// assert_eq!(reader.position(), 0);
+    /// ```
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the current virtual position of the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::r#async::Reader::new(&data[..]);
+    /// assert_eq!(reader.virtual_position(), bgzf::VirtualPosition::from(0));
+    /// ```
+    pub fn virtual_position(&self) -> VirtualPosition {
+        self.block.virtual_position()
+    }
+
+    fn poll_read_block(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.project();
+
+        this.block.set_cpos(*this.position);
+
+        match ready!(this.inner.poll_next(cx)) {
+            Some(Ok(frame)) => {
+                inflate_frame(&frame, this.block)?;
+                *this.position += frame.len() as u64;
+            }
+            Some(Err(e)) => return Poll::Ready(Err(e)),
+            None => {
+                this.block.set_clen(0);
+                this.block.data_mut().clear();
+                this.block.set_upos(0);
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R> Reader<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    /// Seeks the stream to the given virtual position.
+    ///
+    /// The underlying stream's cursor is first moved the the compressed position. A block is read,
+    /// decompressed, and has its own cursor moved to the uncompressed position.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// use std::io::Cursor;
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut reader = bgzf::r#async::Reader::new(Cursor::new(Vec::new()));
+    /// let virtual_position = bgzf::VirtualPosition::from(102334155);
+    /// reader.seek(virtual_position).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn seek(&mut self, pos: VirtualPosition) -> io::Result<VirtualPosition> {
+        let (cpos, upos) = pos.into();
+
+        self.inner.get_mut().seek(SeekFrom::Start(cpos)).await?;
+        self.inner.read_buffer_mut().clear();
+        self.position = cpos;
+
+        futures::future::poll_fn(|cx| Pin::new(&mut *self).poll_read_block(cx)).await?;
+
+        self.block.set_upos(u32::from(upos));
+
+        Ok(pos)
+    }
+
+    /// Seeks the stream to the given position in the uncompressed stream.
+    ///
+    /// The position is converted to a virtual position using the given gzip index (GZI).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// use std::io::Cursor;
+    /// use noodles_bgzf::{self as bgzf, gzi};
+    ///
+    /// let mut reader = bgzf::r#async::Reader::new(Cursor::new(Vec::new()));
+    /// let index = gzi::Index::default();
+    /// reader.seek_by_uncompressed_position(&index, 13).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn seek_by_uncompressed_position(
+        &mut self,
+        index: &gzi::Index,
+        pos: u64,
+    ) -> io::Result<u64> {
+        let virtual_position = index.query(pos)?;
+        self.seek(virtual_position).await?;
+        Ok(pos)
+    }
+}
+
+impl<R> AsyncRead for Reader<R>
+where
+    R: AsyncRead,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let src = ready!(self.as_mut().poll_fill_buf(cx))?;
+
+        let amt = cmp::min(src.len(), buf.remaining());
+        buf.put_slice(&src[..amt]);
+
+        self.consume(amt);
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R> AsyncBufRead for Reader<R>
+where
+    R: AsyncRead,
+{
+    fn poll_fill_buf(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        if self.block.is_eof() {
+            ready!(self.as_mut().poll_read_block(cx))?;
+        }
+
+        Poll::Ready(Ok(self.project().block.fill_buf()))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let block = self.project().block;
+
+        let upos = u32::try_from(amt)
+            .ok()
+            .and_then(|n| block.upos().checked_add(n))
+            .unwrap_or(u32::MAX);
+
+        let upos = cmp::min(block.ulen(), upos);
+
+        block.set_upos(upos);
+    }
+}
+
+fn inflate_frame(frame: &[u8], block: &mut Block) -> io::Result<()> {
+    let clen = frame.len();
+    let cdata = &frame[BGZF_HEADER_SIZE..clen - gz::TRAILER_SIZE];
+
+    let trailer = &frame[clen - gz::TRAILER_SIZE..];
+    let ulen = LittleEndian::read_u32(&trailer[4..8]);
+
+    block.set_clen(clen as u64);
+
+    inflate_block(cdata, None, ulen, block)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+    use super::*;
+
+    fn build_data() -> io::Result<(Vec<u8>, u64)> {
+        let mut writer = crate::Writer::new(Vec::new());
+        writer.write_all(b"noodles\n")?;
+        writer.flush()?;
+        let second_block_position = writer.position();
+        writer.write_all(b"bgzf")?;
+        let data = writer.finish()?;
+        Ok((data, second_block_position))
+    }
+
+    #[tokio::test]
+    async fn test_read_to_end() -> io::Result<()> {
+        let (data, _) = build_data()?;
+        let mut reader = Reader::new(&data[..]);
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"noodles\nbgzf");
+
+        assert_eq!(reader.position(), data.len() as u64);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_line() -> io::Result<()> {
+        let (data, second_block_position) = build_data()?;
+        let mut reader = Reader::new(&data[..]);
+
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        assert_eq!(line, "noodles\n");
+        assert_eq!(
+            reader.virtual_position(),
+            VirtualPosition::try_from((second_block_position, 0)).unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_with_truncated_block() -> io::Result<()> {
+        let (data, second_block_position) = build_data()?;
+        let data = &data[..second_block_position as usize + 4];
+        let mut reader = Reader::new(data);
+
+        let mut buf = Vec::new();
+        let result = reader.read_to_end(&mut buf).await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_seek() -> io::Result<()> {
+        let (data, second_block_position) = build_data()?;
+        let mut reader = Reader::new(Cursor::new(data));
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+
+        let virtual_position = VirtualPosition::try_from((second_block_position, 1)).unwrap();
+        reader.seek(virtual_position).await?;
+        assert_eq!(reader.virtual_position(), virtual_position);
+
+        buf.clear();
+        reader.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"gzf");
+
+        reader.seek(VirtualPosition::from(3)).await?;
+
+        buf.clear();
+        reader.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"dles\nbgzf");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_from_file() -> io::Result<()> {
+        use tokio::{fs::File, io::AsyncWriteExt};
+
+        let (data, _) = build_data()?;
+
+        let path = std::env::temp_dir().join(format!(
+            "noodles-bgzf-async-reader-{}.gz",
+            std::process::id()
+        ));
+
+        let mut file = File::create(&path).await?;
+        file.write_all(&data).await?;
+        file.shutdown().await?;
+
+        let mut reader = File::open(&path).await.map(Reader::new)?;
+
+        let mut buf = Vec::new();
+        reader.seek(VirtualPosition::from(3)).await?;
+        reader.read_to_end(&mut buf).await?;
+
+        tokio::fs::remove_file(&path).await?;
+
+        assert_eq!(buf, b"dles\nbgzf");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_seek_by_uncompressed_position() -> io::Result<()> {
+        let (data, _) = build_data()?;
+        let index = gzi::index(&data[..])?;
+        let mut reader = Reader::new(Cursor::new(data));
+
+        reader.seek_by_uncompressed_position(&index, 9).await?;
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"gzf");
+
+        Ok(())
+    }
+}
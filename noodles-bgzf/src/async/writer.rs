@@ -0,0 +1,374 @@
+//! Async BGZF writer.
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{
+    cmp,
+    convert::TryFrom,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::ready;
+use pin_project_lite::pin_project;
+use tokio::io::AsyncWrite;
+
+use crate::{
+    writer::{deflate_block, CompressionLevel, BGZF_EOF, MAX_BUF_SIZE},
+    VirtualPosition,
+};
+
+pin_project! {
+    /// An async BGZF writer.
+    ///
+    /// This implements [`tokio::io::AsyncWrite`], consuming uncompressed data and emitting
+    /// compressed data.
+    ///
+    /// The final BGZF EOF block is written when the stream is shut down, e.g., using
+    /// [`tokio::io::AsyncWriteExt::shutdown`]. Unlike [`crate::Writer`], an async writer cannot
+    /// finish the stream when it is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// use noodles_bgzf as bgzf;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// let mut writer = bgzf::r#async::Writer::new(Vec::new());
+    /// writer.write_all(b"noodles-bgzf").await?;
+    /// writer.shutdown().await?;
+    ///
+    /// let data = writer.into_inner();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub struct Writer<W> {
+        #[pin]
+        inner: W,
+        position: u64,
+        buf: Vec<u8>,
+        cdata: Vec<u8>,
+        cdata_position: usize,
+        compression_level: CompressionLevel,
+        is_eof_written: bool,
+    }
+}
+
+impl<W> Writer<W>
+where
+    W: AsyncWrite,
+{
+    /// Creates an async BGZF writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let writer = bgzf::r#async::Writer::new(Vec::new());
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Builder::new(inner).build()
+    }
+
+    /// Creates an async BGZF writer builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::{self as bgzf, writer::CompressionLevel};
+    ///
+    /// let writer = bgzf::r#async::Writer::builder(Vec::new())
+    ///     .set_compression_level(CompressionLevel::BEST)
+    ///     .build();
+    /// ```
+    pub fn builder(inner: W) -> Builder<W> {
+        Builder::new(inner)
+    }
+
+    /// Returns a reference to the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let writer = bgzf::r#async::Writer::new(Vec::new());
+    /// assert!(writer.get_ref().is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to the underlying writer directly is likely to corrupt the BGZF stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let mut writer = bgzf::r#async::Writer::new(Vec::new());
+    /// assert!(writer.get_mut().is_empty());
+    /// ```
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the underlying writer.
+    ///
+    /// This does _not_ flush or finish the stream. Use [`tokio::io::AsyncWriteExt::shutdown`]
+    /// before taking the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let writer = bgzf::r#async::Writer::new(Vec::new());
+    /// assert!(writer.into_inner().is_empty());
+    /// ```
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Returns the current position of the stream.
+    ///
+    /// This is the number of compressed bytes in the stream, i.e., the position at which the next
+    /// block will start. It includes compressed blocks that are not yet written to the underlying
+    /// writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let writer = bgzf::r#async::Writer::new(Vec::new());
+    /// assert_eq!(writer.position(), 0);
+    /// ```
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the current virtual position of the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// use noodles_bgzf as bgzf;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// let mut writer = bgzf::r#async::Writer::new(Vec::new());
+    /// assert_eq!(writer.virtual_position(), bgzf::VirtualPosition::from(0));
+    ///
+    /// writer.write_all(b"noodles").await?;
+    /// assert_eq!(writer.virtual_position(), bgzf::VirtualPosition::from(7));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn virtual_position(&self) -> VirtualPosition {
+        // Full blocks are compressed eagerly, so the block buffer size is always less than
+        // `MAX_BUF_SIZE`, which fits in a `u16`.
+        let upos = self.buf.len() as u16;
+        VirtualPosition::try_from((self.position, upos)).unwrap()
+    }
+
+    /// Writes the pending compressed blocks to the underlying writer.
+    fn poll_write_cdata(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+
+        while *this.cdata_position < this.cdata.len() {
+            let n = ready!(this
+                .inner
+                .as_mut()
+                .poll_write(cx, &this.cdata[*this.cdata_position..]))?;
+
+            if n == 0 {
+                return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero)));
+            }
+
+            *this.cdata_position += n;
+        }
+
+        this.cdata.clear();
+        *this.cdata_position = 0;
+
+        Poll::Ready(Ok(()))
+    }
+
+    /// Compresses any buffered data and writes all pending blocks to the underlying writer.
+    fn poll_flush_blocks(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            ready!(self.as_mut().poll_write_cdata(cx))?;
+
+            if self.buf.is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+
+            self.as_mut().compress_block()?;
+        }
+    }
+
+    /// Compresses the buffered data as a pending block.
+    ///
+    /// The pending compressed blocks must be written before this is called.
+    fn compress_block(self: Pin<&mut Self>) -> io::Result<()> {
+        let this = self.project();
+
+        *this.cdata = deflate_block(this.buf, *this.compression_level)?;
+        *this.position += this.cdata.len() as u64;
+        this.buf.clear();
+
+        Ok(())
+    }
+}
+
+impl<W> AsyncWrite for Writer<W>
+where
+    W: AsyncWrite,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        ready!(self.as_mut().poll_write_cdata(cx))?;
+
+        let this = self.as_mut().project();
+
+        let n = cmp::min(MAX_BUF_SIZE - this.buf.len(), buf.len());
+        this.buf.extend_from_slice(&buf[..n]);
+
+        // Full blocks are compressed immediately so that the current virtual position always
+        // points into the current block.
+        if self.buf.len() >= MAX_BUF_SIZE {
+            self.compress_block()?;
+        }
+
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_flush_blocks(cx))?;
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_flush_blocks(cx))?;
+
+        if !self.is_eof_written {
+            let this = self.as_mut().project();
+            this.cdata.extend_from_slice(BGZF_EOF);
+            *this.position += BGZF_EOF.len() as u64;
+            *this.is_eof_written = true;
+
+            ready!(self.as_mut().poll_flush_blocks(cx))?;
+        }
+
+        self.project().inner.poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles").await?;
+        writer.flush().await?;
+        let second_block_position = writer.position();
+        writer.write_all(b"bgzf").await?;
+        writer.shutdown().await?;
+
+        let position = writer.position();
+        let data = writer.into_inner();
+        assert_eq!(position, data.len() as u64);
+
+        let eof_start = data.len() - BGZF_EOF.len();
+        assert_eq!(&data[eof_start..], BGZF_EOF);
+
+        let index = crate::gzi::index(&data[..])?;
+        assert_eq!(index.entries()[0], (second_block_position, 7));
+
+        let mut reader = crate::Reader::new(&data[..]);
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"noodlesbgzf");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_virtual_position() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles").await?;
+        assert_eq!(writer.virtual_position(), VirtualPosition::from(7));
+
+        writer.flush().await?;
+        let position = writer.get_ref().len() as u64;
+        assert_eq!(writer.position(), position);
+        assert_eq!(
+            writer.virtual_position(),
+            VirtualPosition::try_from((position, 0)).unwrap()
+        );
+
+        let data = vec![0; MAX_BUF_SIZE];
+        writer.write_all(&data).await?;
+        let position = writer.position();
+        assert!(position > writer.get_ref().len() as u64);
+        assert_eq!(
+            writer.virtual_position(),
+            VirtualPosition::try_from((position, 0)).unwrap()
+        );
+
+        writer.flush().await?;
+        assert_eq!(writer.get_ref().len() as u64, position);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_with_multiple_blocks() -> io::Result<()> {
+        let expected: Vec<u8> = (0..2 * MAX_BUF_SIZE + 13).map(|i| i as u8).collect();
+
+        let mut writer = Writer::builder(Vec::new())
+            .set_compression_level(CompressionLevel::NONE)
+            .build();
+        writer.write_all(&expected).await?;
+        writer.shutdown().await?;
+        let data = writer.into_inner();
+
+        let mut reader = crate::Reader::new(&data[..]);
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_self() -> io::Result<()> {
+        use tokio::io::AsyncReadExt;
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles-bgzf").await?;
+        writer.shutdown().await?;
+        let data = writer.into_inner();
+
+        let mut reader = crate::r#async::Reader::new(&data[..]);
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"noodles-bgzf");
+
+        Ok(())
+    }
+}
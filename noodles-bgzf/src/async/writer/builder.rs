@@ -0,0 +1,60 @@
+use tokio::io::AsyncWrite;
+
+use super::Writer;
+use crate::writer::{CompressionLevel, MAX_BUF_SIZE};
+
+/// An async BGZF writer builder.
+pub struct Builder<W> {
+    inner: W,
+    compression_level: CompressionLevel,
+}
+
+impl<W> Builder<W>
+where
+    W: AsyncWrite,
+{
+    pub(crate) fn new(inner: W) -> Self {
+        Self {
+            inner,
+            compression_level: CompressionLevel::default(),
+        }
+    }
+
+    /// Sets the compression level.
+    ///
+    /// By default, the compression level is 6.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::{self as bgzf, writer::CompressionLevel};
+    ///
+    /// let writer = bgzf::r#async::Writer::builder(Vec::new())
+    ///     .set_compression_level(CompressionLevel::FAST)
+    ///     .build();
+    /// ```
+    pub fn set_compression_level(mut self, compression_level: CompressionLevel) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    /// Builds an async BGZF writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let writer = bgzf::r#async::Writer::builder(Vec::new()).build();
+    /// ```
+    pub fn build(self) -> Writer<W> {
+        Writer {
+            inner: self.inner,
+            position: 0,
+            buf: Vec::with_capacity(MAX_BUF_SIZE),
+            cdata: Vec::new(),
+            cdata_position: 0,
+            compression_level: self.compression_level,
+            is_eof_written: false,
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::io::{self, BufRead, BufReader, Read};
+
+use flate2::bufread::MultiGzDecoder;
+
+use super::{detect_compression, Compression, Reader};
+
+/// A reader that transparently decompresses plain, gzip, or BGZF input.
+///
+/// The compression format is detected from the start of the stream (see
+/// [`crate::detect_compression`]). Uncompressed input is passed through as is; gzip input,
+/// including multi-member streams, is decompressed as a whole; and BGZF input is read using a
+/// [`crate::Reader`].
+///
+/// This implements [`std::io::BufRead`] and can be given directly to format readers that read
+/// from a buffered stream, e.g., the FASTA, FASTQ, GFF, SAM, and VCF readers.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io::{self, BufReader, Read}};
+/// use noodles_bgzf as bgzf;
+///
+/// let mut reader = File::open("sample.fq.gz")
+///     .map(BufReader::new)
+///     .and_then(bgzf::AutoReader::new)?;
+///
+/// let mut data = Vec::new();
+/// reader.read_to_end(&mut data)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub enum AutoReader<R> {
+    /// An uncompressed stream.
+    None(R),
+    /// A gzip stream.
+    Gzip(BufReader<MultiGzDecoder<R>>),
+    /// A BGZF stream.
+    Bgzf(Reader<R>),
+}
+
+impl<R> AutoReader<R>
+where
+    R: BufRead,
+{
+    /// Creates a reader by detecting the compression format of the given stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bgzf::{self as bgzf, Compression};
+    /// let reader = bgzf::AutoReader::new(&b"noodles"[..])?;
+    /// assert_eq!(reader.compression(), Compression::None);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        match detect_compression(&mut inner)? {
+            Compression::None => Ok(Self::None(inner)),
+            Compression::Gzip => Ok(Self::Gzip(BufReader::new(MultiGzDecoder::new(inner)))),
+            Compression::Bgzf => Ok(Self::Bgzf(Reader::new(inner))),
+        }
+    }
+
+    /// Returns the detected compression format of the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bgzf::{self as bgzf, Compression};
+    ///
+    /// let data = bgzf::Writer::new(Vec::new()).finish()?;
+    /// let reader = bgzf::AutoReader::new(&data[..])?;
+    ///
+    /// assert_eq!(reader.compression(), Compression::Bgzf);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn compression(&self) -> Compression {
+        match self {
+            Self::None(_) => Compression::None,
+            Self::Gzip(_) => Compression::Gzip,
+            Self::Bgzf(_) => Compression::Bgzf,
+        }
+    }
+}
+
+impl<R> Read for AutoReader<R>
+where
+    R: BufRead,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::None(inner) => inner.read(buf),
+            Self::Gzip(inner) => inner.read(buf),
+            Self::Bgzf(inner) => inner.read(buf),
+        }
+    }
+}
+
+impl<R> BufRead for AutoReader<R>
+where
+    R: BufRead,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self {
+            Self::None(inner) => inner.fill_buf(),
+            Self::Gzip(inner) => inner.fill_buf(),
+            Self::Bgzf(inner) => inner.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match self {
+            Self::None(inner) => inner.consume(amt),
+            Self::Gzip(inner) => inner.consume(amt),
+            Self::Bgzf(inner) => inner.consume(amt),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression as GzCompression};
+
+    use super::*;
+
+    fn read_to_end(data: &[u8], expected_compression: Compression) -> io::Result<Vec<u8>> {
+        let mut reader = AutoReader::new(data)?;
+        assert_eq!(reader.compression(), expected_compression);
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        Ok(buf)
+    }
+
+    #[test]
+    fn test_read() -> io::Result<()> {
+        const DATA: &[u8] = b">sq0\nACGT\n";
+
+        assert_eq!(read_to_end(DATA, Compression::None)?, DATA);
+
+        // multi-member gzip
+        let mut data = Vec::new();
+
+        for chunk in DATA.chunks(5) {
+            let mut encoder = GzEncoder::new(Vec::new(), GzCompression::default());
+            encoder.write_all(chunk)?;
+            data.extend(encoder.finish()?);
+        }
+
+        assert_eq!(read_to_end(&data, Compression::Gzip)?, DATA);
+
+        let mut writer = crate::Writer::new(Vec::new());
+        writer.write_all(DATA)?;
+        let data = writer.finish()?;
+        assert_eq!(read_to_end(&data, Compression::Bgzf)?, DATA);
+
+        Ok(())
+    }
+}
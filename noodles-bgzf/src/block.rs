@@ -5,9 +5,6 @@ use std::{
 
 use super::{virtual_position, VirtualPosition};
 
-/// The max size in bytes of a BGZF block.
-pub const MAX_LENGTH: usize = u16::MAX as usize; // bytes
-
 /// A BGZF block.
 ///
 /// A BGZF block is a gzip stream less than 64 KiB and contains an extra field describing the size
@@ -38,6 +35,11 @@ impl Block {
         self.upos >= self.ulen()
     }
 
+    /// Returns the position of this block in the compressed stream.
+    pub fn cpos(&self) -> u64 {
+        self.cpos
+    }
+
     /// Sets the compressed data length.
     pub fn set_clen(&mut self, clen: u64) {
         self.clen = clen;
@@ -0,0 +1,100 @@
+use std::io::{self, BufRead};
+
+use super::gz;
+
+// RFC 1952 § 2.3.1 (FLG.FEXTRA)
+const GZIP_FLG_FEXTRA: u8 = 0x04;
+
+// ID1 (1) + ID2 (1) + CM (1) + FLG (1) + MTIME (4) + XFL (1) + OS (1) + XLEN (2) + SI1 (1) +
+// SI2 (1) + SLEN (2)
+const BGZF_MAGIC_SIZE: usize = 16;
+
+/// The compression format of a stream.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    /// Uncompressed data.
+    None,
+    /// A gzip stream, possibly with multiple members.
+    Gzip,
+    /// BGZF, i.e., a gzip stream of BGZF blocks.
+    Bgzf,
+}
+
+/// Detects the compression format of a stream.
+///
+/// This inspects the magic numbers at the start of the stream without consuming any input. BGZF
+/// is identified by the gzip header with a `BC` extra subfield. If the buffered input is too short
+/// to check for the extra subfield, a gzip stream is reported as [`Compression::Gzip`], which can
+/// still be decompressed as a whole.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_bgzf::{self as bgzf, Compression};
+///
+/// let mut reader = &b"noodles"[..];
+/// assert_eq!(bgzf::detect_compression(&mut reader)?, Compression::None);
+///
+/// let data = bgzf::Writer::new(Vec::new()).finish()?;
+/// let mut reader = &data[..];
+/// assert_eq!(bgzf::detect_compression(&mut reader)?, Compression::Bgzf);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn detect_compression<R>(reader: &mut R) -> io::Result<Compression>
+where
+    R: BufRead,
+{
+    let src = reader.fill_buf()?;
+
+    if src.len() < gz::MAGIC_NUMBER.len() || src[..2] != gz::MAGIC_NUMBER {
+        return Ok(Compression::None);
+    }
+
+    if src.len() >= BGZF_MAGIC_SIZE
+        && src[3] & GZIP_FLG_FEXTRA != 0
+        && src[12] == b'B'
+        && src[13] == b'C'
+        && src[14..16] == [0x02, 0x00]
+    {
+        Ok(Compression::Bgzf)
+    } else {
+        Ok(Compression::Gzip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression as GzCompression};
+
+    use super::*;
+
+    #[test]
+    fn test_detect_compression() -> io::Result<()> {
+        let mut reader = &[][..];
+        assert_eq!(detect_compression(&mut reader)?, Compression::None);
+
+        let mut reader = &b"ACGT"[..];
+        assert_eq!(detect_compression(&mut reader)?, Compression::None);
+
+        let mut encoder = GzEncoder::new(Vec::new(), GzCompression::default());
+        encoder.write_all(b"ACGT")?;
+        let data = encoder.finish()?;
+        let mut reader = &data[..];
+        assert_eq!(detect_compression(&mut reader)?, Compression::Gzip);
+
+        let mut writer = crate::Writer::new(Vec::new());
+        writer.write_all(b"ACGT")?;
+        let data = writer.finish()?;
+        let mut reader = &data[..];
+        assert_eq!(detect_compression(&mut reader)?, Compression::Bgzf);
+
+        // A gzip header that is too short to contain the BGZF extra subfield.
+        let mut reader = &data[..8];
+        assert_eq!(detect_compression(&mut reader)?, Compression::Gzip);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::{self, Read, Write};
+
+use super::{writer::BGZF_EOF, RawBlocks};
+
+/// Concatenates BGZF streams.
+///
+/// Blocks are copied from each input to the output without being decompressed or recompressed.
+/// Empty blocks, e.g., the EOF marker at the end of each input, are dropped, and a single EOF
+/// marker is written at the end of the output.
+///
+/// This only joins the BGZF streams. The uncompressed data of the result is the concatenation of
+/// the uncompressed data of the inputs, i.e., format-specific headers (e.g., of BAM or VCF) are
+/// not handled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bgzf as bgzf;
+///
+/// let readers = vec![File::open("chr1.vcf.gz")?, File::open("chr2.vcf.gz")?];
+/// let mut writer = File::create("sample.vcf.gz")?;
+///
+/// bgzf::concat(readers, &mut writer)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn concat<I, R, W>(readers: I, writer: &mut W) -> io::Result<()>
+where
+    I: IntoIterator<Item = R>,
+    R: Read,
+    W: Write,
+{
+    for reader in readers {
+        for result in RawBlocks::new(reader) {
+            let block = result?;
+
+            if !block.is_empty() {
+                writer.write_all(block.data())?;
+            }
+        }
+    }
+
+    writer.write_all(BGZF_EOF)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Reader, Writer};
+
+    use super::*;
+
+    #[test]
+    fn test_concat() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        let a = writer.finish()?;
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"-bgzf")?;
+        let b = writer.finish()?;
+
+        let mut data = Vec::new();
+        concat(vec![&a[..], &b[..]], &mut data)?;
+
+        assert_eq!(data.len(), a.len() + b.len() - BGZF_EOF.len());
+        assert!(data.ends_with(BGZF_EOF));
+
+        let mut reader = Reader::new(&data[..]);
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        assert_eq!(buf, b"noodles-bgzf");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+//! Deflate backends.
+//!
+//! By default, deflate streams are encoded and decoded using flate2. When the `libdeflate` feature
+//! is enabled, libdeflate is used instead.
+
+use std::io;
+
+use super::writer::CompressionLevel;
+
+// § 4.1 "The BGZF compression format" (2021-06-03): "...the uncompressed data of each block is
+// limited to 2^16 bytes."
+pub(crate) const MAX_UNCOMPRESSED_DATA_LEN: usize = 1 << 16;
+
+#[cfg(not(feature = "libdeflate"))]
+pub(crate) fn encode(src: &[u8], compression_level: CompressionLevel) -> io::Result<Vec<u8>> {
+    use std::io::Write;
+
+    use flate2::{write::DeflateEncoder, Compression};
+
+    let level = Compression::new(u32::from(u8::from(compression_level)));
+    let mut encoder = DeflateEncoder::new(Vec::new(), level);
+    encoder.write_all(src)?;
+    encoder.finish()
+}
+
+#[cfg(feature = "libdeflate")]
+pub(crate) fn encode(src: &[u8], compression_level: CompressionLevel) -> io::Result<Vec<u8>> {
+    use std::cell::RefCell;
+
+    use libdeflater::{CompressionLvl, Compressor};
+
+    thread_local! {
+        // Compressors are relatively expensive to allocate, so one is kept per thread.
+        static COMPRESSOR: RefCell<Option<(CompressionLevel, Compressor)>> = const { RefCell::new(None) };
+    }
+
+    COMPRESSOR.with(|cell| {
+        let mut cell = cell.borrow_mut();
+
+        let compressor = match &mut *cell {
+            Some((level, compressor)) if *level == compression_level => compressor,
+            _ => {
+                let level =
+                    CompressionLvl::new(i32::from(u8::from(compression_level))).map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "invalid compression level")
+                    })?;
+
+                &mut cell.insert((compression_level, Compressor::new(level))).1
+            }
+        };
+
+        let mut dst = vec![0; compressor.deflate_compress_bound(src.len())];
+
+        let len = compressor
+            .deflate_compress(src, &mut dst)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        dst.truncate(len);
+
+        Ok(dst)
+    })
+}
+
+/// Decodes a raw deflate stream.
+///
+/// `len` is the expected length of the decoded data.
+#[cfg(not(feature = "libdeflate"))]
+pub(crate) fn decode(src: &[u8], len: usize, dst: &mut Vec<u8>) -> io::Result<()> {
+    use std::io::Read;
+
+    use flate2::read::DeflateDecoder;
+
+    validate_len(len)?;
+
+    dst.reserve(len);
+
+    // One more byte than expected is allowed to be read so that callers can detect an oversized
+    // stream without it being fully decoded.
+    let decoder = DeflateDecoder::new(src);
+    decoder.take(len as u64 + 1).read_to_end(dst)?;
+
+    Ok(())
+}
+
+/// Decodes a raw deflate stream.
+///
+/// `len` is the expected length of the decoded data.
+#[cfg(feature = "libdeflate")]
+pub(crate) fn decode(src: &[u8], len: usize, dst: &mut Vec<u8>) -> io::Result<()> {
+    use std::cell::RefCell;
+
+    use libdeflater::Decompressor;
+
+    validate_len(len)?;
+
+    thread_local! {
+        static DECOMPRESSOR: RefCell<Decompressor> = RefCell::new(Decompressor::new());
+    }
+
+    DECOMPRESSOR.with(|cell| {
+        let mut decompressor = cell.borrow_mut();
+
+        dst.resize(len, 0);
+
+        let n = decompressor
+            .deflate_decompress(src, dst)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        dst.truncate(n);
+
+        Ok(())
+    })
+}
+
+fn validate_len(len: usize) -> io::Result<()> {
+    if len > MAX_UNCOMPRESSED_DATA_LEN {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "invalid uncompressed data length: expected <= {}, got {}",
+                MAX_UNCOMPRESSED_DATA_LEN, len
+            ),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Calculates the CRC32 checksum of the given data.
+#[cfg(not(feature = "libdeflate"))]
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = flate2::Crc::new();
+    crc.update(data);
+    crc.sum()
+}
+
+/// Calculates the CRC32 checksum of the given data.
+#[cfg(feature = "libdeflate")]
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    libdeflater::crc32(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_and_decode() -> io::Result<()> {
+        let data = b"noodles-bgzf noodles-bgzf noodles-bgzf";
+
+        for &compression_level in &[
+            CompressionLevel::NONE,
+            CompressionLevel::FAST,
+            CompressionLevel::default(),
+            CompressionLevel::BEST,
+        ] {
+            let cdata = encode(data, compression_level)?;
+
+            let mut udata = Vec::new();
+            decode(&cdata, data.len(), &mut udata)?;
+
+            assert_eq!(udata, &data[..]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_with_invalid_len() -> io::Result<()> {
+        let cdata = encode(b"noodles", CompressionLevel::default())?;
+
+        let mut udata = Vec::new();
+        assert!(matches!(
+            decode(&cdata, u32::MAX as usize, &mut udata),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+        assert!(udata.capacity() < MAX_UNCOMPRESSED_DATA_LEN);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"noodles"), 0x802a58a1);
+    }
+}
@@ -0,0 +1,137 @@
+//! gzip index (GZI) and fields.
+//!
+//! A gzip index (GZI) maps compressed block positions to uncompressed positions in a BGZF file.
+//! This allows random access to the file using a position in the uncompressed stream rather than
+//! a [`virtual position`].
+//!
+//! [`virtual position`]: crate::VirtualPosition
+//!
+//! # Examples
+//!
+//! ## Reading a GZI
+//!
+//! ```no_run
+//! # use std::io;
+//! use noodles_bgzf::gzi;
+//! let index = gzi::read("sample.fa.gz.gzi")?;
+//! # Ok::<(), io::Error>(())
+//! ```
+
+mod index;
+mod reader;
+mod writer;
+
+pub use self::{index::Index, reader::Reader, writer::Writer};
+
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use crate::reader::read_frame;
+
+/// Reads the entire contents of a GZI.
+///
+/// This is a convenience function and is equivalent to opening the file at the given path and
+/// reading the index.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::io;
+/// use noodles_bgzf::gzi;
+/// let index = gzi::read("sample.fa.gz.gzi")?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn read<P>(src: P) -> io::Result<Index>
+where
+    P: AsRef<Path>,
+{
+    let mut reader = File::open(src).map(Reader::new)?;
+    reader.read_index()
+}
+
+/// Writes a GZI to a file.
+///
+/// This is a convenience function and is equivalent to creating a file at the given path and
+/// writing the index.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::io;
+/// use noodles_bgzf::gzi;
+/// let index = gzi::Index::default();
+/// gzi::write("sample.fa.gz.gzi", &index)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn write<P>(dst: P, index: &Index) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let mut writer = File::create(dst).map(Writer::new)?;
+    writer.write_index(index)
+}
+
+/// Builds a GZI from a BGZF stream.
+///
+/// The stream is expected to be at the start of the first block. Blocks are read but not
+/// decompressed; the uncompressed positions are calculated from each block's `ISIZE`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bgzf::gzi;
+/// let index = File::open("sample.fa.gz").and_then(gzi::index)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn index<R>(mut reader: R) -> io::Result<Index>
+where
+    R: Read,
+{
+    let mut entries = Vec::new();
+    let mut cdata = Vec::new();
+
+    let mut compressed_position = 0;
+    let mut uncompressed_position = 0;
+
+    while let Some((clen, _, ulen)) = read_frame(&mut reader, &mut cdata)? {
+        // The first block at (0, 0) is implicit.
+        if compressed_position > 0 {
+            entries.push((compressed_position, uncompressed_position));
+        }
+
+        compressed_position += clen as u64;
+        uncompressed_position += u64::from(ulen);
+    }
+
+    Ok(Index::from(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_index() -> io::Result<()> {
+        let mut writer = crate::Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        let second_block_position = writer.position();
+        writer.write_all(b"bgzf")?;
+        writer.flush()?;
+        let eof_block_position = writer.position();
+        let data = writer.finish()?;
+
+        let index = index(&data[..])?;
+
+        let expected = Index::from(vec![(second_block_position, 7), (eof_block_position, 11)]);
+        assert_eq!(index, expected);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::{convert::TryFrom, io};
+
+use crate::VirtualPosition;
+
+/// A gzip index (GZI).
+///
+/// A GZI is a list of `(compressed position, uncompressed position)` pairs, one for each block
+/// start except the first, which is implicitly at `(0, 0)`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Index(Vec<(u64, u64)>);
+
+impl Index {
+    /// Returns the list of `(compressed position, uncompressed position)` entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::gzi;
+    /// let index = gzi::Index::from(vec![(4668, 21294)]);
+    /// assert_eq!(index.entries(), [(4668, 21294)]);
+    /// ```
+    pub fn entries(&self) -> &[(u64, u64)] {
+        &self.0
+    }
+
+    /// Returns the virtual position of the given position in the uncompressed stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{convert::TryFrom, io};
+    /// use noodles_bgzf::{self as bgzf, gzi};
+    ///
+    /// let index = gzi::Index::from(vec![(4668, 21294), (9294, 42588)]);
+    ///
+    /// assert_eq!(index.query(0)?, bgzf::VirtualPosition::from(0));
+    /// assert_eq!(index.query(21293)?, bgzf::VirtualPosition::from(21293));
+    ///
+    /// let virtual_position = bgzf::VirtualPosition::try_from((9294, 8)).unwrap();
+    /// assert_eq!(index.query(42596)?, virtual_position);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn query(&self, pos: u64) -> io::Result<VirtualPosition> {
+        let i = self.0.partition_point(|&(_, upos)| upos <= pos);
+
+        let (compressed_position, uncompressed_position) =
+            if i == 0 { (0, 0) } else { self.0[i - 1] };
+
+        let block_upos = u16::try_from(pos - uncompressed_position)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        VirtualPosition::try_from((compressed_position, block_upos))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+}
+
+impl From<Vec<(u64, u64)>> for Index {
+    fn from(entries: Vec<(u64, u64)>) -> Self {
+        Self(entries)
+    }
+}
+
+impl From<Index> for Vec<(u64, u64)> {
+    fn from(index: Index) -> Self {
+        index.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query() -> io::Result<()> {
+        let index = Index::from(vec![(4668, 21294), (9294, 42588)]);
+
+        assert_eq!(index.query(0)?, VirtualPosition::from(0));
+        assert_eq!(
+            index.query(21294)?,
+            VirtualPosition::try_from((4668, 0)).unwrap()
+        );
+        assert_eq!(
+            index.query(50000)?,
+            VirtualPosition::try_from((9294, 7412)).unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_with_out_of_bounds_block_position() {
+        let index = Index::default();
+        assert!(index.query(65536).is_err());
+    }
+}
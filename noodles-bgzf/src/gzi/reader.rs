@@ -0,0 +1,103 @@
+use std::{
+    convert::TryFrom,
+    io::{self, Read},
+};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use super::Index;
+
+/// A gzip index (GZI) reader.
+///
+/// Consider using [`super::read`] to read the entire index at once.
+pub struct Reader<R> {
+    inner: R,
+}
+
+impl<R> Reader<R>
+where
+    R: Read,
+{
+    /// Creates a GZI reader.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_bgzf::gzi;
+    /// let reader = File::open("sample.fa.gz.gzi").map(gzi::Reader::new)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Reads a GZI.
+    ///
+    /// The position of the stream is expected to be at the start.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_bgzf::gzi;
+    /// let mut reader = File::open("sample.fa.gz.gzi").map(gzi::Reader::new)?;
+    /// let index = reader.read_index()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_index(&mut self) -> io::Result<Index> {
+        let len = self.inner.read_u64::<LittleEndian>()?;
+        let len =
+            usize::try_from(len).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // The entry count is untrusted, so the list is not preallocated from it.
+        let mut entries = Vec::new();
+
+        for _ in 0..len {
+            let compressed_position = self.inner.read_u64::<LittleEndian>()?;
+            let uncompressed_position = self.inner.read_u64::<LittleEndian>()?;
+            entries.push((compressed_position, uncompressed_position));
+        }
+
+        Ok(Index::from(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_index() -> io::Result<()> {
+        let data = [
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // len = 2
+            0x3c, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // compressed_position = 4668
+            0x2e, 0x53, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // uncompressed_position = 21294
+            0x4e, 0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // compressed_position = 9294
+            0x5c, 0xa6, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // uncompressed_position = 42588
+        ];
+
+        let mut reader = Reader::new(&data[..]);
+        let index = reader.read_index()?;
+
+        let expected = Index::from(vec![(4668, 21294), (9294, 42588)]);
+        assert_eq!(index, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_index_with_truncated_entries() {
+        let data = [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f, // len = 2^63 - 1
+            0x3c, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // compressed_position = 4668
+        ];
+
+        let mut reader = Reader::new(&data[..]);
+
+        assert!(matches!(
+            reader.read_index(),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::{self, Write};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use super::Index;
+
+/// A gzip index (GZI) writer.
+pub struct Writer<W> {
+    inner: W,
+}
+
+impl<W> Writer<W>
+where
+    W: Write,
+{
+    /// Creates a GZI writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::gzi;
+    /// let writer = gzi::Writer::new(Vec::new());
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::gzi;
+    /// let writer = gzi::Writer::new(Vec::new());
+    /// assert!(writer.get_ref().is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Writes a GZI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bgzf::gzi;
+    /// let index = gzi::Index::default();
+    /// let mut writer = gzi::Writer::new(Vec::new());
+    /// writer.write_index(&index)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_index(&mut self, index: &Index) -> io::Result<()> {
+        let len = index.entries().len() as u64;
+        self.inner.write_u64::<LittleEndian>(len)?;
+
+        for &(compressed_position, uncompressed_position) in index.entries() {
+            self.inner.write_u64::<LittleEndian>(compressed_position)?;
+            self.inner
+                .write_u64::<LittleEndian>(uncompressed_position)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_index() -> io::Result<()> {
+        let index = Index::from(vec![(4668, 21294), (9294, 42588)]);
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_index(&index)?;
+
+        let expected = [
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // len = 2
+            0x3c, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // compressed_position = 4668
+            0x2e, 0x53, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // uncompressed_position = 21294
+            0x4e, 0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // compressed_position = 9294
+            0x5c, 0xa6, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // uncompressed_position = 42588
+        ];
+
+        assert_eq!(writer.get_ref(), &expected);
+
+        Ok(())
+    }
+}
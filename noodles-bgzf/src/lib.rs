@@ -33,14 +33,68 @@
 //! writer.write_all(b"noodles-bgzf")?;
 //! # Ok::<(), io::Error>(())
 //! ```
+//!
+//! ## Write a BGZF file using multiple compression threads
+//!
+//! ```no_run
+//! # use std::{fs::File, io::{self, Write}};
+//! use noodles_bgzf as bgzf;
+//! let mut writer = File::create("data.gz").map(bgzf::MultithreadedWriter::new)?;
+//! writer.write_all(b"noodles-bgzf")?;
+//! writer.finish()?;
+//! # Ok::<(), io::Error>(())
+//! ```
+//!
+//! ## Read a plain, gzip, or BGZF file
+//!
+//! ```no_run
+//! # use std::{fs::File, io::{self, BufReader, Read}};
+//! use noodles_bgzf as bgzf;
+//! let mut reader = File::open("data.txt.gz")
+//!     .map(BufReader::new)
+//!     .and_then(bgzf::AutoReader::new)?;
+//! let mut data = Vec::new();
+//! reader.read_to_end(&mut data)?;
+//! # Ok::<(), io::Error>(())
+//! ```
+//!
+//! ## Async I/O
+//!
+//! With the `async` feature enabled, `bgzf::r#async::Reader` and `bgzf::r#async::Writer` provide
+//! [tokio]-based alternatives to the blocking reader and writer.
+//!
+//! [tokio]: https://tokio.rs/
 
+#[cfg(feature = "async")]
+pub mod r#async;
+
+mod auto_reader;
 mod block;
+mod compression;
+mod concat;
+mod deflate;
 mod gz;
-mod reader;
+pub mod gzi;
+pub mod multithreaded_reader;
+pub mod multithreaded_writer;
+mod raw_block;
+mod raw_blocks;
+pub mod reader;
 pub mod virtual_position;
-mod writer;
-
-pub use self::{reader::Reader, virtual_position::VirtualPosition, writer::Writer};
+pub mod writer;
+
+pub use self::{
+    auto_reader::AutoReader,
+    compression::{detect_compression, Compression},
+    concat::concat,
+    multithreaded_reader::MultithreadedReader,
+    multithreaded_writer::MultithreadedWriter,
+    raw_block::RawBlock,
+    raw_blocks::RawBlocks,
+    reader::Reader,
+    virtual_position::VirtualPosition,
+    writer::Writer,
+};
 
 use self::block::Block;
 
@@ -0,0 +1,543 @@
+//! Multithreaded BGZF reader.
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{
+    cmp,
+    convert::TryFrom,
+    io::{self, BufRead, Read, Seek, SeekFrom},
+    mem,
+    num::NonZeroUsize,
+    thread::{self, JoinHandle},
+};
+
+use crossbeam_channel::{Receiver, Sender};
+
+use super::{
+    gzi,
+    reader::{inflate_block, read_frame},
+    Block, VirtualPosition,
+};
+
+type BufferedTx = Sender<io::Result<Block>>;
+type BufferedRx = Receiver<io::Result<Block>>;
+type InflateTx = Sender<(Vec<u8>, Block, Option<u32>, u32, BufferedTx)>;
+type InflateRx = Receiver<(Vec<u8>, Block, Option<u32>, u32, BufferedTx)>;
+type ReadTx = Sender<BufferedRx>;
+type ReadRx = Receiver<BufferedRx>;
+
+enum State<R> {
+    Paused(R),
+    Running {
+        reader_handle: JoinHandle<R>,
+        inflater_handles: Vec<JoinHandle<()>>,
+        read_rx: ReadRx,
+    },
+    Done,
+}
+
+/// A multithreaded BGZF reader.
+///
+/// This is a multithreaded alternative to [`crate::Reader`]. A dedicated reader thread reads
+/// compressed blocks ahead of the current position, and a pool of workers decompresses them in
+/// parallel. Blocks are consumed in the same order they appear in the stream.
+///
+/// Like [`crate::Reader`], this implements both [`std::io::Read`] and [`std::io::BufRead`], is
+/// internally buffered by a single block, and _cannot_ be double buffered.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io::{self, Read}};
+/// use noodles_bgzf as bgzf;
+/// let mut reader = File::open("data.gz").map(bgzf::MultithreadedReader::new)?;
+/// let mut data = Vec::new();
+/// reader.read_to_end(&mut data)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct MultithreadedReader<R>
+where
+    R: Read + Send + 'static,
+{
+    worker_count: NonZeroUsize,
+    verify_checksums: bool,
+    state: State<R>,
+    position: u64,
+    block: Block,
+}
+
+impl<R> MultithreadedReader<R>
+where
+    R: Read + Send + 'static,
+{
+    /// Creates a multithreaded BGZF reader with a worker count equal to the available
+    /// parallelism.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_bgzf as bgzf;
+    /// let reader = bgzf::MultithreadedReader::new(io::empty());
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Builder::new(inner).build()
+    }
+
+    /// Creates a multithreaded BGZF reader builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let reader = bgzf::MultithreadedReader::builder(io::empty())
+    ///     .set_verify_checksums(true)
+    ///     .build();
+    /// ```
+    pub fn builder(inner: R) -> Builder<R> {
+        Builder::new(inner)
+    }
+
+    /// Creates a multithreaded BGZF reader with a given number of decompression workers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{io, num::NonZeroUsize};
+    /// use noodles_bgzf as bgzf;
+    /// let worker_count = NonZeroUsize::new(4).unwrap();
+    /// let reader = bgzf::MultithreadedReader::with_worker_count(worker_count, io::empty());
+    /// ```
+    pub fn with_worker_count(worker_count: NonZeroUsize, inner: R) -> Self {
+        Builder::new(inner).set_worker_count(worker_count).build()
+    }
+
+    fn from_parts(worker_count: NonZeroUsize, verify_checksums: bool, inner: R) -> Self {
+        Self {
+            worker_count,
+            verify_checksums,
+            state: State::Paused(inner),
+            position: 0,
+            block: Block::default(),
+        }
+    }
+
+    /// Returns the current virtual position of the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_bgzf as bgzf;
+    /// let reader = bgzf::MultithreadedReader::new(io::empty());
+    /// assert_eq!(reader.virtual_position(), bgzf::VirtualPosition::from(0));
+    /// ```
+    pub fn virtual_position(&self) -> VirtualPosition {
+        self.block.virtual_position()
+    }
+
+    fn resume(&mut self) {
+        if let State::Paused(_) = self.state {
+            if let State::Paused(inner) = mem::replace(&mut self.state, State::Done) {
+                let worker_count = self.worker_count.get();
+
+                // Bounding the queues limits the number of blocks read ahead.
+                let (inflate_tx, inflate_rx) = crossbeam_channel::bounded(worker_count);
+                let (read_tx, read_rx) = crossbeam_channel::bounded(worker_count);
+
+                let inflater_handles = spawn_inflaters(worker_count, inflate_rx);
+                let reader_handle = spawn_reader(
+                    inner,
+                    self.position,
+                    self.verify_checksums,
+                    inflate_tx,
+                    read_tx,
+                );
+
+                self.state = State::Running {
+                    reader_handle,
+                    inflater_handles,
+                    read_rx,
+                };
+            }
+        }
+    }
+
+    fn pause(&mut self) -> io::Result<()> {
+        if let State::Running { .. } = self.state {
+            if let State::Running {
+                reader_handle,
+                inflater_handles,
+                read_rx,
+            } = mem::replace(&mut self.state, State::Done)
+            {
+                // Dropping the receiver signals the reader thread to stop reading ahead.
+                drop(read_rx);
+
+                let inner = reader_handle
+                    .join()
+                    .map_err(|_| io::Error::other("reader thread panicked"))?;
+
+                // The inflaters are respawned on resume, so the reader can still be used if one
+                // of them panicked.
+                self.state = State::Paused(inner);
+
+                for handle in inflater_handles {
+                    handle
+                        .join()
+                        .map_err(|_| io::Error::other("inflater thread panicked"))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn next_block(&mut self) -> io::Result<Option<Block>> {
+        self.resume();
+
+        match &self.state {
+            State::Running { read_rx, .. } => match read_rx.recv() {
+                Ok(buffered_rx) => buffered_rx
+                    .recv()
+                    .map_err(|_| {
+                        io::Error::new(io::ErrorKind::BrokenPipe, "inflater thread stopped")
+                    })?
+                    .map(Some),
+                Err(_) => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+impl<R> MultithreadedReader<R>
+where
+    R: Read + Seek + Send + 'static,
+{
+    /// Seeks the stream to the given virtual position.
+    ///
+    /// Any blocks that were read ahead are discarded. The block at the compressed position is
+    /// read and decompressed on the calling thread, and reading ahead resumes from the following
+    /// block.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::{self, Cursor};
+    /// use noodles_bgzf as bgzf;
+    /// let mut reader = bgzf::MultithreadedReader::new(Cursor::new(Vec::new()));
+    /// let virtual_position = bgzf::VirtualPosition::from(102334155);
+    /// reader.seek(virtual_position)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn seek(&mut self, pos: VirtualPosition) -> io::Result<VirtualPosition> {
+        let (cpos, upos) = pos.into();
+
+        self.pause()?;
+
+        let inner = match &mut self.state {
+            State::Paused(inner) => inner,
+            _ => return Err(io::Error::other("invalid reader state")),
+        };
+
+        inner.seek(SeekFrom::Start(cpos))?;
+        self.position = cpos;
+
+        let mut cdata = Vec::new();
+
+        if let Some((clen, crc32, ulen)) = read_frame(inner, &mut cdata)? {
+            self.block.set_cpos(cpos);
+            self.block.set_clen(clen as u64);
+            let crc32 = if self.verify_checksums {
+                Some(crc32)
+            } else {
+                None
+            };
+            inflate_block(&cdata, crc32, ulen, &mut self.block)?;
+            self.position += clen as u64;
+        }
+
+        self.block.set_upos(u32::from(upos));
+
+        Ok(pos)
+    }
+
+    /// Seeks the stream to the given position in the uncompressed stream.
+    ///
+    /// The position is converted to a virtual position using the given gzip index (GZI).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::{self, Cursor};
+    /// use noodles_bgzf::{self as bgzf, gzi};
+    /// let mut reader = bgzf::MultithreadedReader::new(Cursor::new(Vec::new()));
+    /// let index = gzi::Index::default();
+    /// reader.seek_by_uncompressed_position(&index, 13)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn seek_by_uncompressed_position(
+        &mut self,
+        index: &gzi::Index,
+        pos: u64,
+    ) -> io::Result<u64> {
+        let virtual_position = index.query(pos)?;
+        self.seek(virtual_position)?;
+        Ok(pos)
+    }
+}
+
+impl<R> Drop for MultithreadedReader<R>
+where
+    R: Read + Send + 'static,
+{
+    fn drop(&mut self) {
+        // A worker thread panic cannot be reported from a drop.
+        let _ = self.pause();
+    }
+}
+
+impl<R> Read for MultithreadedReader<R>
+where
+    R: Read + Send + 'static,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = {
+            let mut remaining = self.fill_buf()?;
+            remaining.read(buf)?
+        };
+
+        self.consume(bytes_read);
+
+        Ok(bytes_read)
+    }
+}
+
+impl<R> BufRead for MultithreadedReader<R>
+where
+    R: Read + Send + 'static,
+{
+    fn consume(&mut self, amt: usize) {
+        let upos = u32::try_from(amt)
+            .ok()
+            .and_then(|n| self.block.upos().checked_add(n))
+            .unwrap_or(u32::MAX);
+
+        let upos = cmp::min(self.block.ulen(), upos);
+
+        self.block.set_upos(upos)
+    }
+
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.block.is_eof() {
+            if let Some(block) = self.next_block()? {
+                self.block = block;
+            }
+        }
+
+        Ok(self.block.fill_buf())
+    }
+}
+
+fn spawn_inflaters(worker_count: usize, inflate_rx: InflateRx) -> Vec<JoinHandle<()>> {
+    (0..worker_count)
+        .map(|_| {
+            let inflate_rx = inflate_rx.clone();
+
+            thread::spawn(move || {
+                while let Ok((cdata, mut block, crc32, ulen, buffered_tx)) = inflate_rx.recv() {
+                    let result = inflate_block(&cdata, crc32, ulen, &mut block).map(|_| block);
+                    buffered_tx.send(result).ok();
+                }
+            })
+        })
+        .collect()
+}
+
+fn spawn_reader<R>(
+    mut reader: R,
+    mut position: u64,
+    verify_checksums: bool,
+    inflate_tx: InflateTx,
+    read_tx: ReadTx,
+) -> JoinHandle<R>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        loop {
+            let mut cdata = Vec::new();
+            let result = read_frame(&mut reader, &mut cdata);
+
+            let (buffered_tx, buffered_rx) = crossbeam_channel::bounded(1);
+
+            match result {
+                Ok(Some((clen, crc32, ulen))) => {
+                    if read_tx.send(buffered_rx).is_err() {
+                        break;
+                    }
+
+                    let mut block = Block::default();
+                    block.set_cpos(position);
+                    block.set_clen(clen as u64);
+
+                    position += clen as u64;
+
+                    let crc32 = if verify_checksums { Some(crc32) } else { None };
+
+                    if inflate_tx
+                        .send((cdata, block, crc32, ulen, buffered_tx))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    buffered_tx.send(Err(e)).ok();
+                    read_tx.send(buffered_rx).ok();
+                    break;
+                }
+            }
+        }
+
+        reader
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use crate::{Reader, Writer};
+
+    use super::*;
+
+    fn build_data() -> io::Result<Vec<u8>> {
+        let mut writer = Writer::new(Vec::new());
+
+        for i in 0..1024 {
+            writeln!(writer, "noodles-bgzf {}", i)?;
+
+            if i % 64 == 0 {
+                writer.flush()?;
+            }
+        }
+
+        writer.finish()
+    }
+
+    #[test]
+    fn test_read_to_end() -> io::Result<()> {
+        let data = build_data()?;
+
+        let mut expected = Vec::new();
+        Reader::new(&data[..]).read_to_end(&mut expected)?;
+
+        let worker_count = NonZeroUsize::new(2).unwrap();
+        let mut reader = MultithreadedReader::with_worker_count(worker_count, Cursor::new(data));
+
+        let mut actual = Vec::new();
+        reader.read_to_end(&mut actual)?;
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_to_end_with_verify_checksums() -> io::Result<()> {
+        let mut data = build_data()?;
+
+        // Corrupt the CRC32 of the first block.
+        let bsize = usize::from(u16::from_le_bytes([data[16], data[17]])) + 1;
+        data[bsize - 8] ^= 0xff;
+
+        let mut reader = MultithreadedReader::new(Cursor::new(data.clone()));
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        let mut reader = MultithreadedReader::builder(Cursor::new(data.clone()))
+            .set_verify_checksums(true)
+            .build();
+        buf.clear();
+        let e = reader.read_to_end(&mut buf).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+        let mut reader = MultithreadedReader::builder(Cursor::new(data))
+            .set_verify_checksums(true)
+            .build();
+        assert!(matches!(
+            reader.seek(VirtualPosition::from(0)),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_virtual_position() -> io::Result<()> {
+        let data = build_data()?;
+
+        let mut expected_reader = Reader::new(&data[..]);
+        let mut reader = MultithreadedReader::new(Cursor::new(data.clone()));
+
+        loop {
+            assert_eq!(
+                reader.virtual_position(),
+                expected_reader.virtual_position()
+            );
+
+            let mut expected_line = String::new();
+            let mut actual_line = String::new();
+
+            let n = expected_reader.read_line(&mut expected_line)?;
+            assert_eq!(reader.read_line(&mut actual_line)?, n);
+            assert_eq!(actual_line, expected_line);
+
+            if n == 0 {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek() -> io::Result<()> {
+        let data = build_data()?;
+
+        let mut expected_reader = Reader::new(Cursor::new(data.clone()));
+        let mut reader = MultithreadedReader::new(Cursor::new(data));
+
+        let mut line = String::new();
+
+        for _ in 0..512 {
+            line.clear();
+            reader.read_line(&mut line)?;
+        }
+
+        let virtual_position = reader.virtual_position();
+
+        let mut expected = Vec::new();
+        expected_reader.seek(virtual_position)?;
+        expected_reader.read_to_end(&mut expected)?;
+
+        reader.seek(VirtualPosition::from(0))?;
+        reader.read_line(&mut line)?;
+        reader.seek(virtual_position)?;
+        assert_eq!(reader.virtual_position(), virtual_position);
+
+        let mut actual = Vec::new();
+        reader.read_to_end(&mut actual)?;
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::{io::Read, num::NonZeroUsize, thread};
+
+use super::MultithreadedReader;
+
+/// A multithreaded BGZF reader builder.
+pub struct Builder<R> {
+    inner: R,
+    worker_count: Option<NonZeroUsize>,
+    verify_checksums: bool,
+}
+
+impl<R> Builder<R>
+where
+    R: Read + Send + 'static,
+{
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            worker_count: None,
+            verify_checksums: false,
+        }
+    }
+
+    /// Sets the number of decompression workers.
+    ///
+    /// By default, the worker count is equal to the available parallelism.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{io, num::NonZeroUsize};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let reader = bgzf::MultithreadedReader::builder(io::empty())
+    ///     .set_worker_count(NonZeroUsize::new(4).unwrap())
+    ///     .build();
+    /// ```
+    pub fn set_worker_count(mut self, worker_count: NonZeroUsize) -> Self {
+        self.worker_count = Some(worker_count);
+        self
+    }
+
+    /// Sets whether to verify the checksum (`CRC32`) of each block.
+    ///
+    /// This is the same as [`crate::reader::Builder::set_verify_checksums`].
+    ///
+    /// By default, checksums are not verified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let reader = bgzf::MultithreadedReader::builder(io::empty())
+    ///     .set_verify_checksums(true)
+    ///     .build();
+    /// ```
+    pub fn set_verify_checksums(mut self, verify_checksums: bool) -> Self {
+        self.verify_checksums = verify_checksums;
+        self
+    }
+
+    /// Builds a multithreaded BGZF reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_bgzf as bgzf;
+    /// let reader = bgzf::MultithreadedReader::builder(io::empty()).build();
+    /// ```
+    pub fn build(self) -> MultithreadedReader<R> {
+        let worker_count = self.worker_count.unwrap_or_else(|| {
+            thread::available_parallelism().unwrap_or_else(|_| NonZeroUsize::new(1).unwrap())
+        });
+
+        MultithreadedReader::from_parts(worker_count, self.verify_checksums, self.inner)
+    }
+}
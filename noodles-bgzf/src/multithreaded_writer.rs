@@ -0,0 +1,467 @@
+//! Multithreaded BGZF writer.
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{
+    io::{self, Write},
+    mem,
+    num::NonZeroUsize,
+    thread::{self, JoinHandle},
+};
+
+use crossbeam_channel::{Receiver, Sender};
+
+use super::writer::{deflate_block, CompressionLevel, BGZF_EOF, MAX_BUF_SIZE};
+
+type BufferedTx = Sender<io::Result<Vec<u8>>>;
+type BufferedRx = Receiver<io::Result<Vec<u8>>>;
+type DeflateTx = Sender<(Vec<u8>, BufferedTx)>;
+type DeflateRx = Receiver<(Vec<u8>, BufferedTx)>;
+type WriteTx = Sender<BufferedRx>;
+type WriteRx = Receiver<BufferedRx>;
+
+/// A multithreaded BGZF writer.
+///
+/// This is a multithreaded alternative to [`crate::Writer`]. Uncompressed data is buffered into
+/// blocks, which are compressed in parallel by a pool of workers. A dedicated writer thread
+/// receives the compressed blocks in the order they were sent and writes them to the underlying
+/// stream.
+///
+/// Like [`crate::Writer`], this implements [`std::io::Write`], and the final BGZF EOF block is
+/// written when the stream is finished.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{io::{self, Write}, num::NonZeroUsize};
+/// use noodles_bgzf as bgzf;
+///
+/// let worker_count = NonZeroUsize::new(2).unwrap();
+/// let mut writer = bgzf::MultithreadedWriter::with_worker_count(worker_count, Vec::new());
+/// writer.write_all(b"noodles-bgzf")?;
+///
+/// let data = writer.finish()?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct MultithreadedWriter<W>
+where
+    W: Write + Send + 'static,
+{
+    inner: Option<W>,
+    writer_handle: Option<JoinHandle<io::Result<W>>>,
+    deflater_handles: Vec<JoinHandle<()>>,
+    write_tx: Option<WriteTx>,
+    deflate_tx: Option<DeflateTx>,
+    buf: Vec<u8>,
+    compression_level: CompressionLevel,
+    error: Option<io::Error>,
+}
+
+impl<W> MultithreadedWriter<W>
+where
+    W: Write + Send + 'static,
+{
+    /// Creates a multithreaded BGZF writer with a worker count equal to the available
+    /// parallelism.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let writer = bgzf::MultithreadedWriter::new(Vec::new());
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Builder::new(inner).build()
+    }
+
+    /// Creates a multithreaded BGZF writer builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::{self as bgzf, writer::CompressionLevel};
+    ///
+    /// let writer = bgzf::MultithreadedWriter::builder(Vec::new())
+    ///     .set_compression_level(CompressionLevel::BEST)
+    ///     .build();
+    /// ```
+    pub fn builder(inner: W) -> Builder<W> {
+        Builder::new(inner)
+    }
+
+    /// Creates a multithreaded BGZF writer with a given number of compression workers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroUsize;
+    /// use noodles_bgzf as bgzf;
+    /// let worker_count = NonZeroUsize::new(4).unwrap();
+    /// let writer = bgzf::MultithreadedWriter::with_worker_count(worker_count, Vec::new());
+    /// ```
+    pub fn with_worker_count(worker_count: NonZeroUsize, inner: W) -> Self {
+        Builder::new(inner).set_worker_count(worker_count).build()
+    }
+
+    fn spawn(worker_count: NonZeroUsize, compression_level: CompressionLevel, inner: W) -> Self {
+        let worker_count = worker_count.get();
+
+        // Bounding the queues limits the number of blocks held in memory.
+        let (deflate_tx, deflate_rx) = crossbeam_channel::bounded(worker_count);
+        let (write_tx, write_rx) = crossbeam_channel::bounded(worker_count);
+
+        let deflater_handles = spawn_deflaters(worker_count, compression_level, deflate_rx);
+        let writer_handle = spawn_writer(inner, write_rx);
+
+        Self {
+            inner: None,
+            writer_handle: Some(writer_handle),
+            deflater_handles,
+            write_tx: Some(write_tx),
+            deflate_tx: Some(deflate_tx),
+            buf: Vec::with_capacity(MAX_BUF_SIZE),
+            compression_level,
+            error: None,
+        }
+    }
+
+    /// Attempts to finish the output stream by flushing any remaining buffers.
+    ///
+    /// This waits for all pending blocks to be compressed and written and then appends the final
+    /// BGZF EOF block. The worker threads are shut down.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Write};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut writer = bgzf::MultithreadedWriter::new(Vec::new());
+    /// writer.write_all(b"noodles-bgzf")?;
+    ///
+    /// writer.try_finish()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn try_finish(&mut self) -> io::Result<()> {
+        if self.writer_handle.is_some() {
+            let result = self.flush();
+            self.shutdown();
+
+            if let Err(e) = result {
+                self.error.get_or_insert(e);
+            }
+        }
+
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the underlying writer after finishing the output stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Write};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut writer = bgzf::MultithreadedWriter::new(Vec::new());
+    /// writer.write_all(b"noodles-bgzf")?;
+    ///
+    /// let data = writer.finish()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn finish(mut self) -> io::Result<W> {
+        self.try_finish()?;
+        self.inner.take().ok_or_else(finished_error)
+    }
+
+    /// Returns the compression level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::{self as bgzf, writer::CompressionLevel};
+    ///
+    /// let writer = bgzf::MultithreadedWriter::builder(Vec::new())
+    ///     .set_compression_level(CompressionLevel::FAST)
+    ///     .build();
+    ///
+    /// assert_eq!(writer.compression_level(), CompressionLevel::FAST);
+    /// ```
+    pub fn compression_level(&self) -> CompressionLevel {
+        self.compression_level
+    }
+
+    fn send(&mut self) -> io::Result<()> {
+        let (buffered_tx, buffered_rx) = crossbeam_channel::bounded(1);
+
+        let write_tx = self.write_tx.as_ref().ok_or_else(finished_error)?;
+
+        if write_tx.send(buffered_rx).is_err() {
+            return Err(self.writer_error());
+        }
+
+        let buf = mem::replace(&mut self.buf, Vec::with_capacity(MAX_BUF_SIZE));
+
+        let deflate_tx = self.deflate_tx.as_ref().ok_or_else(finished_error)?;
+
+        if deflate_tx.send((buf, buffered_tx)).is_err() {
+            return Err(self.writer_error());
+        }
+
+        Ok(())
+    }
+
+    // Shuts down the writer after a worker stopped early.
+    //
+    // The original error is kept to be returned by `try_finish`, and a copy of it is returned.
+    fn writer_error(&mut self) -> io::Error {
+        self.shutdown();
+
+        match &self.error {
+            Some(e) => io::Error::new(e.kind(), e.to_string()),
+            None => io::Error::new(io::ErrorKind::BrokenPipe, "writer thread stopped"),
+        }
+    }
+
+    // Closes the channels and joins all worker threads.
+    //
+    // On success, the underlying writer is kept in `inner`. Otherwise, the first error is kept in
+    // `error`.
+    fn shutdown(&mut self) {
+        self.deflate_tx.take();
+
+        let mut deflater_panicked = false;
+
+        for handle in self.deflater_handles.drain(..) {
+            deflater_panicked |= handle.join().is_err();
+        }
+
+        self.write_tx.take();
+
+        if let Some(handle) = self.writer_handle.take() {
+            match handle.join() {
+                Ok(Ok(inner)) => self.inner = Some(inner),
+                Ok(Err(e)) => self.error = Some(e),
+                Err(_) => self.error = Some(io::Error::other("writer thread panicked")),
+            }
+        }
+
+        if deflater_panicked && self.error.is_none() {
+            self.error = Some(io::Error::other("deflater thread panicked"));
+        }
+    }
+}
+
+impl<W> Drop for MultithreadedWriter<W>
+where
+    W: Write + Send + 'static,
+{
+    fn drop(&mut self) {
+        let _ = self.try_finish();
+    }
+}
+
+impl<W> Write for MultithreadedWriter<W>
+where
+    W: Write + Send + 'static,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let remaining = MAX_BUF_SIZE - self.buf.len();
+        let n = remaining.min(buf.len());
+
+        self.buf.extend_from_slice(&buf[..n]);
+
+        if self.buf.len() >= MAX_BUF_SIZE {
+            self.send()?;
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            Ok(())
+        } else {
+            self.send()
+        }
+    }
+}
+
+fn finished_error() -> io::Error {
+    io::Error::other("writer is finished")
+}
+
+fn spawn_deflaters(
+    worker_count: usize,
+    compression_level: CompressionLevel,
+    deflate_rx: DeflateRx,
+) -> Vec<JoinHandle<()>> {
+    (0..worker_count)
+        .map(|_| {
+            let deflate_rx = deflate_rx.clone();
+
+            thread::spawn(move || {
+                while let Ok((src, buffered_tx)) = deflate_rx.recv() {
+                    let result = deflate_block(&src, compression_level);
+                    buffered_tx.send(result).ok();
+                }
+            })
+        })
+        .collect()
+}
+
+fn spawn_writer<W>(mut writer: W, write_rx: WriteRx) -> JoinHandle<io::Result<W>>
+where
+    W: Write + Send + 'static,
+{
+    thread::spawn(move || {
+        while let Ok(buffered_rx) = write_rx.recv() {
+            let block = buffered_rx.recv().map_err(|_| {
+                io::Error::new(io::ErrorKind::BrokenPipe, "deflater thread stopped")
+            })??;
+
+            writer.write_all(&block)?;
+        }
+
+        writer.write_all(BGZF_EOF)?;
+
+        Ok(writer)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use crate::Reader;
+
+    use super::*;
+
+    #[test]
+    fn test_self() -> io::Result<()> {
+        let worker_count = NonZeroUsize::new(2).unwrap();
+        let mut writer = MultithreadedWriter::with_worker_count(worker_count, Vec::new());
+
+        let expected: Vec<u8> = (0..3 * MAX_BUF_SIZE + 13).map(|i| i as u8).collect();
+        writer.write_all(&expected)?;
+
+        let data = writer.finish()?;
+
+        let mut reader = Reader::new(&data[..]);
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        assert_eq!(buf, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_compression_level() -> io::Result<()> {
+        let data: Vec<u8> = b"noodles-bgzf "
+            .iter()
+            .copied()
+            .cycle()
+            .take(4096)
+            .collect();
+
+        let mut writer = MultithreadedWriter::builder(Vec::new())
+            .set_worker_count(NonZeroUsize::new(2).unwrap())
+            .set_compression_level(CompressionLevel::NONE)
+            .build();
+        writer.write_all(&data)?;
+        let uncompressed = writer.finish()?;
+
+        let mut writer = MultithreadedWriter::builder(Vec::new())
+            .set_worker_count(NonZeroUsize::new(2).unwrap())
+            .set_compression_level(CompressionLevel::BEST)
+            .build();
+        writer.write_all(&data)?;
+        let compressed = writer.finish()?;
+
+        assert!(uncompressed.len() > data.len());
+        assert!(compressed.len() < uncompressed.len());
+
+        let mut reader = Reader::new(&uncompressed[..]);
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, data);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finish() -> io::Result<()> {
+        let mut writer = MultithreadedWriter::new(Vec::new());
+        writer.write_all(b"noodles")?;
+
+        let data = writer.finish()?;
+        let eof_start = data.len() - BGZF_EOF.len();
+
+        assert_eq!(&data[eof_start..], BGZF_EOF);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finish_with_failing_writer() {
+        struct FailingWriter;
+
+        impl Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::PermissionDenied, "failed"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let worker_count = NonZeroUsize::new(2).unwrap();
+        let mut writer = MultithreadedWriter::with_worker_count(worker_count, FailingWriter);
+
+        let data = vec![0; 8 * MAX_BUF_SIZE];
+
+        if let Err(e) = writer.write_all(&data) {
+            assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+        }
+
+        assert!(matches!(
+            writer.finish(),
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied
+        ));
+    }
+
+    #[test]
+    fn test_finish_with_panicking_writer() {
+        struct PanickingWriter;
+
+        impl Write for PanickingWriter {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                panic!("write");
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = MultithreadedWriter::new(PanickingWriter);
+        let _ = writer.write_all(b"noodles");
+
+        assert!(writer.finish().is_err());
+    }
+
+    #[test]
+    fn test_finish_with_no_data() -> io::Result<()> {
+        let writer = MultithreadedWriter::new(Vec::new());
+        let data = writer.finish()?;
+        assert_eq!(data, BGZF_EOF);
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use byteorder::{ByteOrder, LittleEndian};
+
+use super::gz;
+
+/// A raw BGZF block.
+///
+/// A raw block is a complete gzip member, i.e., the header, compressed data, and trailer, as it
+/// appears in the compressed stream. Its data is not decompressed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RawBlock {
+    position: u64,
+    data: Vec<u8>,
+}
+
+impl RawBlock {
+    pub(crate) fn new(position: u64, data: Vec<u8>) -> Self {
+        Self { position, data }
+    }
+
+    /// Returns the position of the block in the compressed stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let block = bgzf::RawBlock::default();
+    /// assert_eq!(block.position(), 0);
+    /// ```
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the raw data of the block, including the header and trailer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let block = bgzf::RawBlock::default();
+    /// assert!(block.data().is_empty());
+    /// ```
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the size of the block in the compressed stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let block = bgzf::RawBlock::default();
+    /// assert_eq!(block.size(), 0);
+    /// ```
+    pub fn size(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    /// Returns the size of the uncompressed data (`ISIZE`) of the block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let block = bgzf::RawBlock::default();
+    /// assert_eq!(block.uncompressed_size(), 0);
+    /// ```
+    pub fn uncompressed_size(&self) -> u32 {
+        if self.data.len() < gz::TRAILER_SIZE {
+            return 0;
+        }
+
+        let r#isize = &self.data[self.data.len() - 4..];
+        LittleEndian::read_u32(r#isize)
+    }
+
+    /// Returns whether the block has no uncompressed data.
+    ///
+    /// The BGZF EOF marker is an empty block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let block = bgzf::RawBlock::default();
+    /// assert!(block.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.uncompressed_size() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::writer::BGZF_EOF;
+
+    use super::*;
+
+    #[test]
+    fn test_uncompressed_size() {
+        let block = RawBlock::new(0, BGZF_EOF.to_vec());
+        assert_eq!(block.uncompressed_size(), 0);
+
+        let mut data = BGZF_EOF.to_vec();
+        let len = data.len();
+        data[len - 4..].copy_from_slice(&[0x08, 0x00, 0x00, 0x00]);
+        let block = RawBlock::new(0, data);
+        assert_eq!(block.uncompressed_size(), 8);
+    }
+}
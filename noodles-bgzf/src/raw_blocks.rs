@@ -0,0 +1,199 @@
+use std::io::{self, Read};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use super::{gz, RawBlock, BGZF_HEADER_SIZE};
+
+/// An iterator over raw blocks of a BGZF stream.
+///
+/// Blocks are read as-is and are not decompressed.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bgzf as bgzf;
+///
+/// let blocks = File::open("data.gz").map(bgzf::RawBlocks::new)?;
+///
+/// for result in blocks {
+///     let block = result?;
+///     println!("{}\t{}\t{}", block.position(), block.size(), block.uncompressed_size());
+/// }
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct RawBlocks<R> {
+    inner: R,
+    position: u64,
+}
+
+impl<R> RawBlocks<R>
+where
+    R: Read,
+{
+    /// Creates an iterator over raw blocks.
+    ///
+    /// The stream is expected to be at the start of a block, and block positions are relative to
+    /// this start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let mut blocks = bgzf::RawBlocks::new(&data[..]);
+    /// assert!(blocks.next().is_none());
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Self { inner, position: 0 }
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Iterator for RawBlocks<R>
+where
+    R: Read,
+{
+    type Item = io::Result<RawBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match read_raw_block(&mut self.inner) {
+            Ok(Some(data)) => {
+                let block = RawBlock::new(self.position, data);
+                self.position += block.size();
+                Some(Ok(block))
+            }
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+fn read_raw_block<R>(reader: &mut R) -> io::Result<Option<Vec<u8>>>
+where
+    R: Read,
+{
+    let mut header = [0; BGZF_HEADER_SIZE];
+
+    if !read_exact_or_eof(reader, &mut header)? {
+        return Ok(None);
+    }
+
+    if header[..2] != gz::MAGIC_NUMBER {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid BGZF header",
+        ));
+    }
+
+    let bsize = LittleEndian::read_u16(&header[16..]);
+
+    // Add 1 because BSIZE is "total Block SIZE minus 1".
+    let block_size = usize::from(bsize) + 1;
+
+    if block_size < BGZF_HEADER_SIZE + gz::TRAILER_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid BGZF block size",
+        ));
+    }
+
+    let mut data = vec![0; block_size];
+    data[..BGZF_HEADER_SIZE].copy_from_slice(&header);
+    reader.read_exact(&mut data[BGZF_HEADER_SIZE..])?;
+
+    Ok(Some(data))
+}
+
+// Fills the buffer, returning `false` if the stream is at EOF before any bytes are read.
+//
+// EOF after a partial read is an `UnexpectedEof` error.
+fn read_exact_or_eof<R>(reader: &mut R, buf: &mut [u8]) -> io::Result<bool>
+where
+    R: Read,
+{
+    let mut len = 0;
+
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) if len == 0 => return Ok(false),
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "unexpected EOF in BGZF header",
+                ))
+            }
+            Ok(n) => len += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::{writer::BGZF_EOF, Writer};
+
+    use super::*;
+
+    #[test]
+    fn test_next() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        let second_block_position = writer.position();
+        writer.write_all(b"bgzf")?;
+        writer.flush()?;
+        let eof_block_position = writer.position();
+        let data = writer.finish()?;
+
+        let blocks: Vec<_> = RawBlocks::new(&data[..]).collect::<Result<_, _>>()?;
+        assert_eq!(blocks.len(), 3);
+
+        assert_eq!(blocks[0].position(), 0);
+        assert_eq!(blocks[0].size(), second_block_position);
+        assert_eq!(blocks[0].uncompressed_size(), 7);
+
+        assert_eq!(blocks[1].position(), second_block_position);
+        assert_eq!(blocks[1].size(), eof_block_position - second_block_position);
+        assert_eq!(blocks[1].uncompressed_size(), 4);
+
+        assert_eq!(blocks[2].position(), eof_block_position);
+        assert_eq!(blocks[2].data(), BGZF_EOF);
+        assert!(blocks[2].is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_truncated_block() {
+        let data = &BGZF_EOF[..BGZF_EOF.len() - 1];
+        let mut blocks = RawBlocks::new(data);
+        assert!(matches!(blocks.next(), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_next_with_truncated_header() {
+        let data = &BGZF_EOF[..BGZF_HEADER_SIZE - 1];
+        let mut blocks = RawBlocks::new(data);
+
+        assert!(matches!(
+            blocks.next(),
+            Some(Err(e)) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn test_next_with_no_data() {
+        let mut blocks = RawBlocks::new(&[][..]);
+        assert!(blocks.next().is_none());
+    }
+}
@@ -1,3 +1,9 @@
+//! BGZF reader.
+
+mod builder;
+
+pub use self::builder::Builder;
+
 use std::{
     cmp,
     convert::TryFrom,
@@ -5,14 +11,14 @@ use std::{
 };
 
 use byteorder::{ByteOrder, LittleEndian};
-use flate2::read::DeflateDecoder;
 
-use super::{gz, Block, VirtualPosition, BGZF_HEADER_SIZE};
+use super::{deflate, gz, gzi, Block, VirtualPosition, BGZF_HEADER_SIZE};
 
 /// A BGZF reader.
 ///
-/// Due to the static structure of a BGZF block, gzip headers are mostly discarded. CRC32
-/// validation is also disabled when decompressing data.
+/// Due to the static structure of a BGZF block, gzip headers are mostly discarded. By default,
+/// CRC32 validation is also disabled when decompressing data, and a missing EOF marker is not
+/// considered an error. Use [`Builder`] to enable these checks.
 ///
 /// The reader implements both [`std::io::Read`] and [`std::io::BufRead`], consuming compressed
 /// data and emitting uncompressed data. It is internally buffered by a single block, and to
@@ -34,6 +40,8 @@ pub struct Reader<R> {
     position: u64,
     cdata: Vec<u8>,
     block: Block,
+    verify_checksums: bool,
+    require_eof_marker: bool,
 }
 
 impl<R> Reader<R>
@@ -50,12 +58,22 @@ where
     /// let reader = bgzf::Reader::new(&data[..]);
     /// ```
     pub fn new(inner: R) -> Self {
-        Self {
-            inner,
-            position: 0,
-            cdata: Vec::new(),
-            block: Block::default(),
-        }
+        Builder::new(inner).build()
+    }
+
+    /// Creates a BGZF reader builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::Reader::builder(&data[..])
+    ///     .set_verify_checksums(true)
+    ///     .build();
+    /// ```
+    pub fn builder(inner: R) -> Builder<R> {
+        Builder::new(inner)
     }
 
     /// Returns the current position of the stream.
@@ -112,12 +130,43 @@ where
         self.inner.seek(SeekFrom::Start(cpos))?;
         self.position = cpos;
 
-        read_block(&mut self.inner, &mut self.cdata, &mut self.block)?;
+        self.block.set_cpos(cpos);
+        let block_size = read_block(
+            &mut self.inner,
+            &mut self.cdata,
+            &mut self.block,
+            self.verify_checksums,
+        )?;
+        self.position += block_size as u64;
 
-        self.block.set_upos(upos as u32);
+        self.block.set_upos(u32::from(upos));
 
         Ok(pos)
     }
+
+    /// Seeks the stream to the given position in the uncompressed stream.
+    ///
+    /// The position is converted to a virtual position using the given gzip index (GZI).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::{self, Cursor};
+    /// use noodles_bgzf::{self as bgzf, gzi};
+    /// let mut reader = bgzf::Reader::new(Cursor::new(Vec::new()));
+    /// let index = gzi::Index::default();
+    /// reader.seek_by_uncompressed_position(&index, 13)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn seek_by_uncompressed_position(
+        &mut self,
+        index: &gzi::Index,
+        pos: u64,
+    ) -> io::Result<u64> {
+        let virtual_position = index.query(pos)?;
+        self.seek(virtual_position)?;
+        Ok(pos)
+    }
 }
 
 impl<R> Read for Reader<R>
@@ -159,11 +208,25 @@ where
 
     fn fill_buf(&mut self) -> io::Result<&[u8]> {
         if self.block.is_eof() {
-            match read_block(&mut self.inner, &mut self.cdata, &mut self.block) {
-                Ok(bs) => {
-                    self.block.set_cpos(self.position);
-                    self.position += bs as u64;
+            // The EOF marker is an empty block. If the stream ends after any other block (or
+            // before any block), the EOF marker is missing.
+            let is_missing_eof_marker = self.position == 0 || self.block.ulen() > 0;
+
+            self.block.set_cpos(self.position);
+
+            match read_block(
+                &mut self.inner,
+                &mut self.cdata,
+                &mut self.block,
+                self.verify_checksums,
+            ) {
+                Ok(0) if self.require_eof_marker && is_missing_eof_marker => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        format!("missing BGZF EOF marker at position {}", self.position),
+                    ));
                 }
+                Ok(bs) => self.position += bs as u64,
                 Err(e) => return Err(e),
             }
         }
@@ -201,8 +264,9 @@ where
 /// The position of the stream is expected to be at the start of the block trailer, i.e., 8 bytes
 /// from the end of the block.
 ///
-/// This returns the length of the uncompressed data (`ISIZE`).
-fn read_trailer<R>(reader: &mut R) -> io::Result<u32>
+/// This returns the checksum of the uncompressed data (`CRC32`) and the length of the uncompressed
+/// data (`ISIZE`).
+fn read_trailer<R>(reader: &mut R) -> io::Result<(u32, u32)>
 where
     R: Read,
 {
@@ -216,50 +280,114 @@ where
         ));
     }
 
-    let r#isize = &trailer[4..8];
-
-    Ok(LittleEndian::read_u32(r#isize))
-}
+    let crc32 = LittleEndian::read_u32(&trailer[0..4]);
+    let r#isize = LittleEndian::read_u32(&trailer[4..8]);
 
-fn inflate_data<R>(reader: R, writer: &mut Vec<u8>) -> io::Result<usize>
-where
-    R: Read,
-{
-    let mut decoder = DeflateDecoder::new(reader);
-    decoder.read_to_end(writer)
+    Ok((crc32, r#isize))
 }
 
-fn read_block<R>(reader: &mut R, cdata: &mut Vec<u8>, block: &mut Block) -> io::Result<usize>
+/// Reads the compressed data of a BGZF block.
+///
+/// If successful, the block size (`BSIZE` + 1), the checksum of the uncompressed data (`CRC32`),
+/// and the length of the uncompressed data (`ISIZE`) are returned. If the stream is at EOF, `None`
+/// is returned.
+pub(crate) fn read_frame<R>(
+    reader: &mut R,
+    cdata: &mut Vec<u8>,
+) -> io::Result<Option<(usize, u32, u32)>>
 where
     R: Read,
 {
     let clen = match read_header(reader) {
-        Ok(0) => return Ok(0),
+        Ok(0) => return Ok(None),
         Ok(bs) => bs as usize,
         Err(e) => return Err(e),
     };
 
+    if clen < BGZF_HEADER_SIZE + gz::TRAILER_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid BGZF block size",
+        ));
+    }
+
     let cdata_len = clen - BGZF_HEADER_SIZE - gz::TRAILER_SIZE;
     cdata.resize(cdata_len, Default::default());
     reader.read_exact(cdata)?;
 
-    let ulen = read_trailer(reader)?;
+    let (crc32, ulen) = read_trailer(reader)?;
+
+    Ok(Some((clen, crc32, ulen)))
+}
+
+/// Decompresses the compressed data of a BGZF block into the given block.
+///
+/// The length of the uncompressed data is always checked against `ISIZE`. If `crc32` is given, the
+/// checksum of the uncompressed data is also checked against it.
+pub(crate) fn inflate_block(
+    cdata: &[u8],
+    crc32: Option<u32>,
+    ulen: u32,
+    block: &mut Block,
+) -> io::Result<()> {
+    let cpos = block.cpos();
 
-    block.set_clen(clen as u64);
     block.set_upos(0);
 
     let udata = block.data_mut();
     udata.clear();
 
-    inflate_data(&cdata[..], udata)?;
+    deflate::decode(cdata, ulen as usize, udata)
+        .map_err(|e| io::Error::new(e.kind(), format!("BGZF block at position {}: {}", cpos, e)))?;
 
     if udata.len() != ulen as usize {
         return Err(io::Error::new(
             io::ErrorKind::InvalidData,
-            "BGZF block length not equal to isize",
+            format!(
+                "BGZF block at position {}: block length ({}) not equal to isize ({})",
+                cpos,
+                udata.len(),
+                ulen
+            ),
         ));
     }
 
+    if let Some(expected_crc32) = crc32 {
+        let actual_crc32 = deflate::crc32(udata);
+
+        if actual_crc32 != expected_crc32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "BGZF block at position {}: checksum mismatch (expected {:08x}, got {:08x})",
+                    cpos, expected_crc32, actual_crc32
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn read_block<R>(
+    reader: &mut R,
+    cdata: &mut Vec<u8>,
+    block: &mut Block,
+    verify_checksum: bool,
+) -> io::Result<usize>
+where
+    R: Read,
+{
+    let (clen, crc32, ulen) = match read_frame(reader, cdata)? {
+        Some(frame) => frame,
+        None => return Ok(0),
+    };
+
+    block.set_clen(clen as u64);
+
+    let crc32 = if verify_checksum { Some(crc32) } else { None };
+    inflate_block(cdata, crc32, ulen, block)?;
+
     Ok(clen)
 }
 
@@ -280,7 +408,8 @@ mod tests {
     #[test]
     fn test_read_trailer() -> io::Result<()> {
         let (_, mut reader) = BGZF_EOF.split_at(BGZF_EOF.len() - gz::TRAILER_SIZE);
-        let r#isize = read_trailer(&mut reader)?;
+        let (crc32, r#isize) = read_trailer(&mut reader)?;
+        assert_eq!(crc32, 0);
         assert_eq!(r#isize, 0);
         Ok(())
     }
@@ -292,13 +421,176 @@ mod tests {
         assert!(read_trailer(&mut reader).is_err());
     }
 
+    #[test]
+    fn test_seek() -> io::Result<()> {
+        use std::io::{Cursor, Write};
+
+        use crate::Writer;
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        let second_block_position = writer.position();
+        writer.write_all(b"bgzf")?;
+        let data = writer.finish()?;
+
+        let mut reader = Reader::new(Cursor::new(data));
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        let virtual_position = VirtualPosition::try_from((second_block_position, 1)).unwrap();
+        reader.seek(virtual_position)?;
+        assert_eq!(reader.virtual_position(), virtual_position);
+
+        buf.clear();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"gzf");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek_by_uncompressed_position() -> io::Result<()> {
+        use std::io::{Cursor, Write};
+
+        use crate::Writer;
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        writer.write_all(b"bgzf")?;
+        let data = writer.finish()?;
+
+        let index = gzi::index(&data[..])?;
+        let mut reader = Reader::new(Cursor::new(data));
+
+        let mut buf = Vec::new();
+
+        reader.seek_by_uncompressed_position(&index, 3)?;
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"dlesbgzf");
+
+        buf.clear();
+        reader.seek_by_uncompressed_position(&index, 8)?;
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"gzf");
+
+        Ok(())
+    }
+
+    // Returns the data of two blocks ("noodles" and "bgzf") and the end positions of each.
+    fn build_data() -> io::Result<(Vec<u8>, usize, usize)> {
+        use std::io::Write;
+
+        use crate::Writer;
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        let a = writer.position() as usize;
+        writer.write_all(b"bgzf")?;
+        writer.flush()?;
+        let b = writer.position() as usize;
+        let data = writer.finish()?;
+
+        Ok((data, a, b))
+    }
+
+    #[test]
+    fn test_read_with_verify_checksums() -> io::Result<()> {
+        let (mut data, a, b) = build_data()?;
+
+        // Corrupt the CRC32 of the second block.
+        data[b - 8] ^= 0xff;
+
+        let mut reader = Reader::new(&data[..]);
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"noodlesbgzf");
+
+        let mut reader = Reader::builder(&data[..])
+            .set_verify_checksums(true)
+            .build();
+        buf.clear();
+        let e = reader.read_to_end(&mut buf).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert!(e.to_string().contains(&format!("position {}", a)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_with_invalid_isize() -> io::Result<()> {
+        let (mut data, a, _) = build_data()?;
+
+        // Change the ISIZE of the first block.
+        data[a - 4] = 8;
+
+        let mut reader = Reader::new(&data[..]);
+        let mut buf = Vec::new();
+        let e = reader.read_to_end(&mut buf).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert!(e.to_string().contains("position 0"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_with_oversized_isize() -> io::Result<()> {
+        let (mut data, a, _) = build_data()?;
+
+        // Set the ISIZE of the first block to u32::MAX.
+        data[a - 4..a].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let mut reader = Reader::new(&data[..]);
+        let mut buf = Vec::new();
+        let e = reader.read_to_end(&mut buf).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_with_require_eof_marker() -> io::Result<()> {
+        let (data, _, _) = build_data()?;
+
+        let mut reader = Reader::builder(&data[..])
+            .set_require_eof_marker(true)
+            .build();
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"noodlesbgzf");
+
+        let truncated_data = &data[..data.len() - BGZF_EOF.len()];
+
+        let mut reader = Reader::new(truncated_data);
+        buf.clear();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"noodlesbgzf");
+
+        let mut reader = Reader::builder(truncated_data)
+            .set_require_eof_marker(true)
+            .build();
+        buf.clear();
+        let e = reader.read_to_end(&mut buf).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut reader = Reader::builder(&[][..])
+            .set_require_eof_marker(true)
+            .build();
+        assert!(reader.read_to_end(&mut buf).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_block() -> io::Result<()> {
         let mut reader = &BGZF_EOF[..];
         let mut cdata = Vec::new();
         let mut block = Block::default();
 
-        let block_size = read_block(&mut reader, &mut cdata, &mut block)?;
+        let block_size = read_block(&mut reader, &mut cdata, &mut block, true)?;
         assert_eq!(block_size, BGZF_EOF.len());
 
         Ok(())
@@ -0,0 +1,90 @@
+use std::io::Read;
+
+use super::Reader;
+use crate::Block;
+
+/// A BGZF reader builder.
+pub struct Builder<R> {
+    inner: R,
+    verify_checksums: bool,
+    require_eof_marker: bool,
+}
+
+impl<R> Builder<R>
+where
+    R: Read,
+{
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            verify_checksums: false,
+            require_eof_marker: false,
+        }
+    }
+
+    /// Sets whether to verify the checksum (`CRC32`) of each block.
+    ///
+    /// When enabled, the checksum of each block's uncompressed data is compared to the `CRC32`
+    /// value in the block trailer. A mismatch is returned as an [`std::io::ErrorKind::InvalidData`]
+    /// error that includes the block's position in the compressed stream.
+    ///
+    /// The length of the uncompressed data is always checked against `ISIZE`.
+    ///
+    /// By default, checksums are not verified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::Reader::builder(&data[..])
+    ///     .set_verify_checksums(true)
+    ///     .build();
+    /// ```
+    pub fn set_verify_checksums(mut self, verify_checksums: bool) -> Self {
+        self.verify_checksums = verify_checksums;
+        self
+    }
+
+    /// Sets whether the stream must end with a BGZF EOF marker.
+    ///
+    /// When enabled, reaching the end of the underlying stream without first reading an EOF
+    /// marker, e.g., due to a truncated file, is returned as an
+    /// [`std::io::ErrorKind::UnexpectedEof`] error.
+    ///
+    /// By default, a missing EOF marker is not considered an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::Reader::builder(&data[..])
+    ///     .set_require_eof_marker(true)
+    ///     .build();
+    /// ```
+    pub fn set_require_eof_marker(mut self, require_eof_marker: bool) -> Self {
+        self.require_eof_marker = require_eof_marker;
+        self
+    }
+
+    /// Builds a BGZF reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::Reader::builder(&data[..]).build();
+    /// ```
+    pub fn build(self) -> Reader<R> {
+        Reader {
+            inner: self.inner,
+            position: 0,
+            cdata: Vec::new(),
+            block: Block::default(),
+            verify_checksums: self.verify_checksums,
+            require_eof_marker: self.require_eof_marker,
+        }
+    }
+}
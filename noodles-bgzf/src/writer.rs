@@ -1,14 +1,26 @@
+//! BGZF writer.
+
+mod builder;
+mod compression_level;
+
+pub use self::{
+    builder::Builder,
+    compression_level::{CompressionLevel, TryFromU8Error},
+};
+
 use std::{
     cmp,
+    convert::TryFrom,
     io::{self, Write},
 };
 
 use byteorder::{LittleEndian, WriteBytesExt};
-use flate2::{write::DeflateEncoder, Compression, Crc};
 
-use super::{block, gz, BGZF_HEADER_SIZE};
+use super::{deflate, gz, VirtualPosition, BGZF_HEADER_SIZE};
 
-const MAX_COMPRESSED_DATA_LENGTH: usize = block::MAX_LENGTH + 1; // bytes
+// The max uncompressed size of a block that leaves room for the compressed data, header, and
+// trailer, even when the data is incompressible.
+pub(crate) const MAX_BUF_SIZE: usize = 0xff00; // bytes
 
 const BGZF_FLG: u8 = 0x04; // FEXTRA
 const BGZF_XFL: u8 = 0x00; // none
@@ -57,8 +69,9 @@ where
     W: Write,
 {
     inner: Option<W>,
-    encoder: DeflateEncoder<Vec<u8>>,
-    crc: Crc,
+    position: u64,
+    buf: Vec<u8>,
+    compression_level: CompressionLevel,
 }
 
 impl<W> Writer<W>
@@ -74,11 +87,22 @@ where
     /// let writer = bgzf::Writer::new(Vec::new());
     /// ```
     pub fn new(inner: W) -> Self {
-        Self {
-            inner: Some(inner),
-            encoder: DeflateEncoder::new(Vec::new(), Compression::default()),
-            crc: Crc::new(),
-        }
+        Builder::new(inner).build()
+    }
+
+    /// Creates a BGZF writer builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::{self as bgzf, writer::CompressionLevel};
+    ///
+    /// let writer = bgzf::Writer::builder(Vec::new())
+    ///     .set_compression_level(CompressionLevel::NONE)
+    ///     .build();
+    /// ```
+    pub fn builder(inner: W) -> Builder<W> {
+        Builder::new(inner)
     }
 
     /// Returns a reference to the underlying writer.
@@ -94,18 +118,66 @@ where
         self.inner.as_ref().unwrap()
     }
 
+    /// Returns the current position of the stream.
+    ///
+    /// This is the number of compressed bytes written to the underlying writer, i.e., the position
+    /// at which the next block will start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let writer = bgzf::Writer::new(Vec::new());
+    /// assert_eq!(writer.position(), 0);
+    /// ```
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the current virtual position of the stream.
+    ///
+    /// This is the virtual position of the next uncompressed byte to be written. The compressed
+    /// position is the start of the current (unwritten) block, and the uncompressed position is
+    /// the number of bytes buffered in that block.
+    ///
+    /// To guarantee a record starts at the beginning of a block, i.e., with an uncompressed
+    /// position of 0, call [`std::io::Write::flush`] before writing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{convert::TryFrom, io::{self, Write}};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut writer = bgzf::Writer::new(Vec::new());
+    /// assert_eq!(writer.virtual_position(), bgzf::VirtualPosition::from(0));
+    ///
+    /// writer.write_all(b"noodles")?;
+    /// assert_eq!(writer.virtual_position(), bgzf::VirtualPosition::from(7));
+    ///
+    /// writer.flush()?;
+    /// assert_eq!(
+    ///     writer.virtual_position(),
+    ///     bgzf::VirtualPosition::try_from((writer.get_ref().len() as u64, 0)).unwrap()
+    /// );
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn virtual_position(&self) -> VirtualPosition {
+        // Full blocks are flushed eagerly, so the block buffer size is always less than
+        // `MAX_BUF_SIZE`, which fits in a `u16`.
+        let upos = self.buf.len() as u16;
+        VirtualPosition::try_from((self.position, upos)).unwrap()
+    }
+
     fn flush_block(&mut self) -> io::Result<()> {
-        self.encoder.try_finish()?;
+        let block = deflate_block(&self.buf, self.compression_level)?;
 
         let inner = self.inner.as_mut().unwrap();
-        let data = self.encoder.get_ref();
+        inner.write_all(&block)?;
 
-        write_header(inner, data.len())?;
-        inner.write_all(&data[..])?;
-        write_trailer(inner, self.crc.sum(), self.crc.amount())?;
+        self.position += block.len() as u64;
 
-        self.encoder.reset(Vec::new())?;
-        self.crc.reset();
+        self.buf.clear();
 
         Ok(())
     }
@@ -128,8 +200,12 @@ where
     /// ```
     pub fn try_finish(&mut self) -> io::Result<()> {
         self.flush()?;
+
         let inner = self.inner.as_mut().unwrap();
-        inner.write_all(BGZF_EOF)
+        inner.write_all(BGZF_EOF)?;
+        self.position += BGZF_EOF.len() as u64;
+
+        Ok(())
     }
 
     /// Returns the underlying writer after finishing the output stream.
@@ -171,27 +247,24 @@ where
     W: Write,
 {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let total_uncompressed_bytes_written = self.crc.amount() as usize;
+        let bytes_to_be_written = cmp::min(MAX_BUF_SIZE - self.buf.len(), buf.len());
+        self.buf.extend_from_slice(&buf[..bytes_to_be_written]);
 
-        // Only the uncompressed size is tracked, and the assumption is that the uncompressed size
-        // will always be less than the compressed size.
-        if total_uncompressed_bytes_written >= MAX_COMPRESSED_DATA_LENGTH {
-            self.flush()?;
-            return Err(io::Error::from(io::ErrorKind::Interrupted));
+        // Full blocks are flushed immediately so that the current virtual position always points
+        // into the current block.
+        if self.buf.len() >= MAX_BUF_SIZE {
+            self.flush_block()?;
         }
 
-        let bytes_to_be_written = cmp::min(
-            (MAX_COMPRESSED_DATA_LENGTH - total_uncompressed_bytes_written) as usize,
-            buf.len(),
-        );
-        let bytes_written = self.encoder.write(&buf[..bytes_to_be_written])?;
-        self.crc.update(&buf[..bytes_written]);
-
-        Ok(bytes_written)
+        Ok(bytes_to_be_written)
     }
 
+    /// Compresses and writes the current block, if it is not empty.
+    ///
+    /// After a flush, the next byte written starts a new block. Note that the underlying writer is
+    /// not flushed.
     fn flush(&mut self) -> io::Result<()> {
-        if self.crc.amount() > 0 {
+        if !self.buf.is_empty() {
             self.flush_block()
         } else {
             Ok(())
@@ -199,6 +272,22 @@ where
     }
 }
 
+/// Compresses the given data as a complete BGZF block, i.e., with a header and trailer.
+pub(crate) fn deflate_block(
+    src: &[u8],
+    compression_level: CompressionLevel,
+) -> io::Result<Vec<u8>> {
+    let cdata = deflate::encode(src, compression_level)?;
+    let crc32 = deflate::crc32(src);
+
+    let mut dst = Vec::with_capacity(BGZF_HEADER_SIZE + cdata.len() + gz::TRAILER_SIZE);
+    write_header(&mut dst, cdata.len())?;
+    dst.extend_from_slice(&cdata);
+    write_trailer(&mut dst, crc32, src.len() as u32)?;
+
+    Ok(dst)
+}
+
 fn write_header<W>(writer: &mut W, cdata_len: usize) -> io::Result<()>
 where
     W: Write,
@@ -246,4 +335,56 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_virtual_position() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        assert_eq!(writer.virtual_position(), VirtualPosition::from(0));
+
+        writer.write_all(b"noodles")?;
+        assert_eq!(writer.virtual_position(), VirtualPosition::from(7));
+
+        writer.flush()?;
+        let position = writer.get_ref().len() as u64;
+        assert_eq!(writer.position(), position);
+        assert_eq!(
+            writer.virtual_position(),
+            VirtualPosition::try_from((position, 0)).unwrap()
+        );
+
+        let data = vec![0; MAX_BUF_SIZE];
+        writer.write_all(&data)?;
+        let position = writer.get_ref().len() as u64;
+        assert_eq!(
+            writer.virtual_position(),
+            VirtualPosition::try_from((position, 0)).unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_with_no_compression() -> io::Result<()> {
+        use std::io::Read;
+
+        use crate::Reader;
+
+        let data: Vec<u8> = (0..2 * MAX_BUF_SIZE + 13).map(|i| i as u8).collect();
+
+        let mut writer = Writer::builder(Vec::new())
+            .set_compression_level(CompressionLevel::NONE)
+            .build();
+        writer.write_all(&data)?;
+        let compressed_data = writer.finish()?;
+
+        assert!(compressed_data.len() > data.len());
+
+        let mut reader = Reader::new(&compressed_data[..]);
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        assert_eq!(buf, data);
+
+        Ok(())
+    }
 }
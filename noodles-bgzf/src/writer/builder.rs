@@ -0,0 +1,59 @@
+use std::io::Write;
+
+use super::{CompressionLevel, Writer, MAX_BUF_SIZE};
+
+/// A BGZF writer builder.
+pub struct Builder<W> {
+    inner: W,
+    compression_level: CompressionLevel,
+}
+
+impl<W> Builder<W>
+where
+    W: Write,
+{
+    pub(crate) fn new(inner: W) -> Self {
+        Self {
+            inner,
+            compression_level: CompressionLevel::default(),
+        }
+    }
+
+    /// Sets the compression level.
+    ///
+    /// A compression level of [`CompressionLevel::NONE`] writes uncompressed BGZF, which is
+    /// typically useful for intermediate files.
+    ///
+    /// By default, the compression level is 6.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::{self as bgzf, writer::CompressionLevel};
+    ///
+    /// let writer = bgzf::Writer::builder(Vec::new())
+    ///     .set_compression_level(CompressionLevel::FAST)
+    ///     .build();
+    /// ```
+    pub fn set_compression_level(mut self, compression_level: CompressionLevel) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    /// Builds a BGZF writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let writer = bgzf::Writer::builder(Vec::new()).build();
+    /// ```
+    pub fn build(self) -> Writer<W> {
+        Writer {
+            inner: Some(self.inner),
+            position: 0,
+            buf: Vec::with_capacity(MAX_BUF_SIZE),
+            compression_level: self.compression_level,
+        }
+    }
+}
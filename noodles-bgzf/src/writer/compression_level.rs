@@ -0,0 +1,85 @@
+use std::{convert::TryFrom, error, fmt};
+
+const MAX: u8 = 9;
+
+/// A BGZF compression level.
+///
+/// A compression level is between 0 (no compression) and 9 (best compression), inclusive. The
+/// default is 6.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct CompressionLevel(u8);
+
+impl CompressionLevel {
+    /// No compression.
+    ///
+    /// Blocks are written as stored (uncompressed) deflate blocks.
+    pub const NONE: Self = Self(0);
+
+    /// The fastest compression level.
+    pub const FAST: Self = Self(1);
+
+    /// The best compression level.
+    pub const BEST: Self = Self(MAX);
+}
+
+impl Default for CompressionLevel {
+    fn default() -> Self {
+        Self(6)
+    }
+}
+
+/// An error returned when a raw compression level fails to convert.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TryFromU8Error(u8);
+
+impl error::Error for TryFromU8Error {}
+
+impl fmt::Display for TryFromU8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid compression level: expected <= {}, got {}",
+            MAX, self.0
+        )
+    }
+}
+
+impl TryFrom<u8> for CompressionLevel {
+    type Error = TryFromU8Error;
+
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        if n <= MAX {
+            Ok(Self(n))
+        } else {
+            Err(TryFromU8Error(n))
+        }
+    }
+}
+
+impl From<CompressionLevel> for u8 {
+    fn from(compression_level: CompressionLevel) -> Self {
+        compression_level.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default() {
+        assert_eq!(CompressionLevel::default(), CompressionLevel(6));
+    }
+
+    #[test]
+    fn test_try_from_u8_for_compression_level() {
+        assert_eq!(CompressionLevel::try_from(0), Ok(CompressionLevel::NONE));
+        assert_eq!(CompressionLevel::try_from(9), Ok(CompressionLevel::BEST));
+        assert_eq!(CompressionLevel::try_from(10), Err(TryFromU8Error(10)));
+    }
+
+    #[test]
+    fn test_from_compression_level_for_u8() {
+        assert_eq!(u8::from(CompressionLevel::FAST), 1);
+    }
+}
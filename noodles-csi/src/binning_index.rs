@@ -0,0 +1,26 @@
+use std::io;
+
+use super::index::reference_sequence::bin::Chunk;
+
+/// A binning index.
+///
+/// This is the common interface of indexes that use a hierarchical binning scheme to find the
+/// chunks of a BGZF-compressed file that may contain records overlapping a region, e.g., BAI,
+/// tabix, and CSI.
+pub trait BinningIndex {
+    /// Returns the list of merged chunks that may contain records that intersect the given
+    /// region.
+    ///
+    /// `reference_sequence_id` is the index of the reference sequence in the associated file.
+    /// `start` and `end` are 1-based, inclusive.
+    fn query(&self, reference_sequence_id: usize, start: i32, end: i32) -> io::Result<Vec<Chunk>>;
+
+    /// Returns the index of the reference sequence with the given name.
+    ///
+    /// This is only available for indexes that store reference sequence names, e.g., tabix and
+    /// CSIs of tabix-indexed files. Otherwise, this returns `None`, and the reference sequence
+    /// index must be resolved using the header of the associated file.
+    fn reference_sequence_id(&self, _name: &str) -> Option<usize> {
+        None
+    }
+}
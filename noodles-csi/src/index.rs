@@ -0,0 +1,297 @@
+//! Coordinate-sorted index (CSI) and fields.
+
+mod builder;
+mod indexer;
+pub mod reference_sequence;
+
+pub use self::{builder::Builder, indexer::Indexer, reference_sequence::ReferenceSequence};
+
+use std::{io, str};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use super::{index::reference_sequence::bin::Chunk, BinningIndex};
+
+// The default binning scheme is equivalent to the one used by BAI and tabix.
+pub(crate) const DEFAULT_MIN_SHIFT: u8 = 14;
+pub(crate) const DEFAULT_DEPTH: u8 = 5;
+
+// The largest depth whose bin IDs, including the metadata pseudo-bin, fit in a `u32`.
+pub(crate) const MAX_DEPTH: u8 = 10;
+
+// The largest `min_shift + 3 * depth` whose intervals can be computed without overflowing.
+const MAX_SHIFT: u32 = 62;
+
+// format (4) + col_seq (4) + col_beg (4) + col_end (4) + meta (4) + skip (4) + l_nm (4)
+const TABIX_AUX_HEADER_SIZE: usize = 28;
+
+/// A coordinate-sorted index (CSI).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Index {
+    min_shift: u8,
+    depth: u8,
+    aux: Vec<u8>,
+    reference_sequences: Vec<ReferenceSequence>,
+    unplaced_unmapped_record_count: Option<u64>,
+}
+
+impl Index {
+    /// Creates a CSI builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi as csi;
+    /// let builder = csi::Index::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns the number of bits for the minimum interval, i.e., the size of the smallest bin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::builder().set_min_shift(16).build()?;
+    /// assert_eq!(index.min_shift(), 16);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn min_shift(&self) -> u8 {
+        self.min_shift
+    }
+
+    /// Returns the depth of the binning index, i.e., the number of levels below the root bin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::builder().set_depth(6).build()?;
+    /// assert_eq!(index.depth(), 6);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Returns the auxiliary data.
+    ///
+    /// For tabix-indexed files, this is the tabix header, including the reference sequence names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::default();
+    /// assert!(index.aux().is_empty());
+    /// ```
+    pub fn aux(&self) -> &[u8] {
+        &self.aux
+    }
+
+    /// Returns the list of reference sequences.
+    ///
+    /// This list is parallel to the reference sequences defined in the associated file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::default();
+    /// assert!(index.reference_sequences().is_empty());
+    /// ```
+    pub fn reference_sequences(&self) -> &[ReferenceSequence] {
+        &self.reference_sequences
+    }
+
+    /// Returns the number of unplaced unmapped records in the associated file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    ///
+    /// let index = csi::Index::default();
+    /// assert!(index.unplaced_unmapped_record_count().is_none());
+    ///
+    /// let index = csi::Index::builder().set_unplaced_unmapped_record_count(13).build()?;
+    /// assert_eq!(index.unplaced_unmapped_record_count(), Some(13));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn unplaced_unmapped_record_count(&self) -> Option<u64> {
+        self.unplaced_unmapped_record_count
+    }
+
+    /// Returns the reference sequence names stored in a tabix-style auxiliary header.
+    ///
+    /// This returns `None` if the auxiliary data is not a tabix header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::default();
+    /// assert!(index.reference_sequence_names().is_none());
+    /// ```
+    pub fn reference_sequence_names(&self) -> Option<Vec<&str>> {
+        parse_aux_reference_sequence_names(&self.aux)
+    }
+}
+
+impl Default for Index {
+    fn default() -> Self {
+        Self {
+            min_shift: DEFAULT_MIN_SHIFT,
+            depth: DEFAULT_DEPTH,
+            aux: Vec::new(),
+            reference_sequences: Vec::new(),
+            unplaced_unmapped_record_count: None,
+        }
+    }
+}
+
+impl BinningIndex for Index {
+    fn query(&self, reference_sequence_id: usize, start: i32, end: i32) -> io::Result<Vec<Chunk>> {
+        let reference_sequence = self
+            .reference_sequences()
+            .get(reference_sequence_id)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "invalid reference sequence ID: {} >= {}",
+                        reference_sequence_id,
+                        self.reference_sequences().len()
+                    ),
+                )
+            })?;
+
+        let query_bins = reference_sequence.query(self.min_shift, self.depth, start, end);
+
+        let chunks: Vec<_> = query_bins
+            .iter()
+            .flat_map(|bin| bin.chunks())
+            .copied()
+            .collect();
+
+        let min_offset = reference_sequence.min_offset(self.min_shift, self.depth, start);
+
+        Ok(super::optimize_chunks(&chunks, min_offset))
+    }
+
+    fn reference_sequence_id(&self, name: &str) -> Option<usize> {
+        self.reference_sequence_names()
+            .and_then(|names| names.iter().position(|n| *n == name))
+    }
+}
+
+/// Validates a binning scheme.
+///
+/// The depth must be <= 10, and `min_shift + 3 * depth` must be < 63. Otherwise, an error of the
+/// given kind is returned.
+pub(crate) fn validate_binning_scheme(
+    min_shift: u8,
+    depth: u8,
+    kind: io::ErrorKind,
+) -> io::Result<()> {
+    if depth > MAX_DEPTH || u32::from(min_shift) + 3 * u32::from(depth) > MAX_SHIFT {
+        Err(io::Error::new(
+            kind,
+            format!(
+                "invalid binning scheme: min_shift = {}, depth = {}",
+                min_shift, depth
+            ),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn parse_aux_reference_sequence_names(aux: &[u8]) -> Option<Vec<&str>> {
+    if aux.len() < TABIX_AUX_HEADER_SIZE {
+        return None;
+    }
+
+    let l_nm = LittleEndian::read_i32(&aux[24..28]) as usize;
+    let names = aux.get(TABIX_AUX_HEADER_SIZE..TABIX_AUX_HEADER_SIZE + l_nm)?;
+
+    names
+        .split(|&b| b == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| str::from_utf8(name).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::WriteBytesExt;
+
+    use super::*;
+
+    #[test]
+    fn test_reference_sequence_names() -> io::Result<()> {
+        let mut aux = Vec::new();
+
+        for _ in 0..6 {
+            aux.write_i32::<LittleEndian>(0)?;
+        }
+
+        let names = b"sq0\x00sq1\x00";
+        aux.write_i32::<LittleEndian>(names.len() as i32)?;
+        aux.extend_from_slice(names);
+
+        let index = Index::builder().set_aux(aux).build()?;
+
+        assert_eq!(index.reference_sequence_names(), Some(vec!["sq0", "sq1"]));
+        assert_eq!(index.reference_sequence_id("sq1"), Some(1));
+        assert!(index.reference_sequence_id("sq2").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query() -> io::Result<()> {
+        use noodles_bgzf as bgzf;
+
+        let mut indexer = Indexer::new(14, 6)?;
+
+        let positions = [(1, 4), (8, 13), ((1 << 30) + 1, (1 << 30) + 5)];
+
+        for (i, &(start, end)) in positions.iter().enumerate() {
+            let chunk = Chunk::new(
+                bgzf::VirtualPosition::from(i as u64 * 55),
+                bgzf::VirtualPosition::from((i as u64 + 1) * 55),
+            );
+
+            indexer.add_record(Some(0), start, end, true, chunk)?;
+        }
+
+        let index = indexer.build(1);
+
+        assert_eq!(
+            index.query(0, 10, 12)?,
+            [Chunk::new(
+                bgzf::VirtualPosition::from(0),
+                bgzf::VirtualPosition::from(110)
+            )]
+        );
+
+        assert_eq!(
+            index.query(0, (1 << 30) + 2, (1 << 30) + 2)?,
+            [Chunk::new(
+                bgzf::VirtualPosition::from(110),
+                bgzf::VirtualPosition::from(165)
+            )]
+        );
+
+        assert!(index.query(0, 1 << 20, 1 << 21)?.is_empty());
+        assert!(index.query(1, 1, 8).is_err());
+
+        Ok(())
+    }
+}
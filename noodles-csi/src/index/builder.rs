@@ -0,0 +1,166 @@
+use std::io;
+
+use super::{validate_binning_scheme, Index, ReferenceSequence, DEFAULT_DEPTH, DEFAULT_MIN_SHIFT};
+
+/// A CSI builder.
+#[derive(Debug)]
+pub struct Builder {
+    min_shift: u8,
+    depth: u8,
+    aux: Vec<u8>,
+    reference_sequences: Vec<ReferenceSequence>,
+    unplaced_unmapped_record_count: Option<u64>,
+}
+
+impl Builder {
+    /// Sets the number of bits for the minimum interval.
+    ///
+    /// By default, this is 14, i.e., the smallest bins are 16 KiB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::builder().set_min_shift(16).build()?;
+    /// assert_eq!(index.min_shift(), 16);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_min_shift(mut self, min_shift: u8) -> Self {
+        self.min_shift = min_shift;
+        self
+    }
+
+    /// Sets the depth of the binning index.
+    ///
+    /// By default, this is 5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::builder().set_depth(6).build()?;
+    /// assert_eq!(index.depth(), 6);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_depth(mut self, depth: u8) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the auxiliary data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::builder().set_aux(b"ndls".to_vec()).build()?;
+    /// assert_eq!(index.aux(), b"ndls");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_aux(mut self, aux: Vec<u8>) -> Self {
+        self.aux = aux;
+        self
+    }
+
+    /// Sets the list of reference sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi::{self as csi, index::ReferenceSequence};
+    ///
+    /// let index = csi::Index::builder()
+    ///     .set_reference_sequences(vec![ReferenceSequence::new(Vec::new(), None)])
+    ///     .build()?;
+    ///
+    /// assert_eq!(index.reference_sequences().len(), 1);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_reference_sequences(mut self, reference_sequences: Vec<ReferenceSequence>) -> Self {
+        self.reference_sequences = reference_sequences;
+        self
+    }
+
+    /// Sets the number of unplaced unmapped records in the associated file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::builder().set_unplaced_unmapped_record_count(13).build()?;
+    /// assert_eq!(index.unplaced_unmapped_record_count(), Some(13));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_unplaced_unmapped_record_count(
+        mut self,
+        unplaced_unmapped_record_count: u64,
+    ) -> Self {
+        self.unplaced_unmapped_record_count = Some(unplaced_unmapped_record_count);
+        self
+    }
+
+    /// Builds a CSI.
+    ///
+    /// This returns an error if the binning scheme is invalid, i.e., the depth is > 10 or
+    /// `min_shift + 3 * depth` is >= 63.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    /// let index = csi::Index::builder().build()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn build(self) -> io::Result<Index> {
+        validate_binning_scheme(self.min_shift, self.depth, io::ErrorKind::InvalidInput)?;
+
+        Ok(Index {
+            min_shift: self.min_shift,
+            depth: self.depth,
+            aux: self.aux,
+            reference_sequences: self.reference_sequences,
+            unplaced_unmapped_record_count: self.unplaced_unmapped_record_count,
+        })
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            min_shift: DEFAULT_MIN_SHIFT,
+            depth: DEFAULT_DEPTH,
+            aux: Vec::new(),
+            reference_sequences: Vec::new(),
+            unplaced_unmapped_record_count: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_with_invalid_binning_scheme() {
+        assert!(Builder::default()
+            .set_min_shift(32)
+            .set_depth(10)
+            .build()
+            .is_ok());
+
+        assert!(matches!(
+            Builder::default().set_min_shift(0).set_depth(11).build(),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+        assert!(matches!(
+            Builder::default().set_min_shift(33).set_depth(10).build(),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+    }
+}
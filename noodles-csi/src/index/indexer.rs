@@ -0,0 +1,225 @@
+use std::io;
+
+use super::{
+    reference_sequence::{self, bin::Chunk},
+    validate_binning_scheme, Index, DEFAULT_DEPTH, DEFAULT_MIN_SHIFT,
+};
+
+/// A CSI indexer.
+///
+/// An indexer builds a CSI from the positions of records in a coordinate-sorted file.
+#[derive(Debug)]
+pub struct Indexer {
+    min_shift: u8,
+    depth: u8,
+    aux: Vec<u8>,
+    reference_sequence_builders: Vec<reference_sequence::Builder>,
+    unplaced_unmapped_record_count: u64,
+}
+
+impl Indexer {
+    /// Creates a CSI indexer with the given binning scheme.
+    ///
+    /// `min_shift` is the number of bits for the minimum interval, and `depth` is the number of
+    /// levels below the root bin. The maximum indexable position is 2^(`min_shift` + 3 *
+    /// `depth`).
+    ///
+    /// This returns an error if the binning scheme is invalid, i.e., the depth is > 10 or
+    /// `min_shift + 3 * depth` is >= 63.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi::index::Indexer;
+    /// let indexer = Indexer::new(14, 6)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new(min_shift: u8, depth: u8) -> io::Result<Self> {
+        validate_binning_scheme(min_shift, depth, io::ErrorKind::InvalidInput)?;
+
+        Ok(Self {
+            min_shift,
+            depth,
+            aux: Vec::new(),
+            reference_sequence_builders: Vec::new(),
+            unplaced_unmapped_record_count: 0,
+        })
+    }
+
+    /// Sets the auxiliary data of the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::Indexer;
+    /// let mut indexer = Indexer::default();
+    /// indexer.set_aux(b"ndls".to_vec());
+    /// ```
+    pub fn set_aux(&mut self, aux: Vec<u8>) {
+        self.aux = aux;
+    }
+
+    /// Adds a record.
+    ///
+    /// `reference_sequence_id` is the index of the reference sequence of the record, or `None` if
+    /// the record is unplaced. `start` and `end` are 1-based, inclusive. The record must have an
+    /// associated chunk denoting its start and end position in the file.
+    ///
+    /// Records must be added in coordinate-sorted order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_csi::index::{reference_sequence::bin::Chunk, Indexer};
+    ///
+    /// let mut indexer = Indexer::default();
+    ///
+    /// let chunk = Chunk::new(
+    ///     bgzf::VirtualPosition::from(233),
+    ///     bgzf::VirtualPosition::from(377),
+    /// );
+    ///
+    /// indexer.add_record(Some(0), 8, 13, true, chunk)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn add_record(
+        &mut self,
+        reference_sequence_id: Option<usize>,
+        start: i32,
+        end: i32,
+        is_mapped: bool,
+        chunk: Chunk,
+    ) -> io::Result<()> {
+        let id = match reference_sequence_id {
+            Some(id) => id,
+            None => {
+                self.unplaced_unmapped_record_count += 1;
+                return Ok(());
+            }
+        };
+
+        if start < 1 || end < start - 1 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid record interval: [{}, {}]", start, end),
+            ));
+        }
+
+        let max_position = 1i64 << (u32::from(self.min_shift) + 3 * u32::from(self.depth));
+
+        if i64::from(end) > max_position {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "record end ({}) exceeds the maximum position ({}) of the binning index",
+                    end, max_position
+                ),
+            ));
+        }
+
+        if id + 1 < self.reference_sequence_builders.len()
+            || self.unplaced_unmapped_record_count > 0
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "records must be coordinate-sorted",
+            ));
+        }
+
+        while self.reference_sequence_builders.len() <= id {
+            self.reference_sequence_builders
+                .push(reference_sequence::Builder::new(self.min_shift, self.depth));
+        }
+
+        self.reference_sequence_builders[id].add_record(start, end, is_mapped, chunk)
+    }
+
+    /// Builds a CSI.
+    ///
+    /// `reference_sequence_count` is the number of reference sequences in the associated file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::Indexer;
+    /// let index = Indexer::default().build(1);
+    /// assert_eq!(index.reference_sequences().len(), 1);
+    /// ```
+    pub fn build(mut self, reference_sequence_count: usize) -> Index {
+        while self.reference_sequence_builders.len() < reference_sequence_count {
+            self.reference_sequence_builders
+                .push(reference_sequence::Builder::new(self.min_shift, self.depth));
+        }
+
+        let reference_sequences = self
+            .reference_sequence_builders
+            .into_iter()
+            .map(|builder| builder.build())
+            .collect();
+
+        // The binning scheme was validated when the indexer was created.
+        Index {
+            min_shift: self.min_shift,
+            depth: self.depth,
+            aux: self.aux,
+            reference_sequences,
+            unplaced_unmapped_record_count: Some(self.unplaced_unmapped_record_count),
+        }
+    }
+}
+
+impl Default for Indexer {
+    fn default() -> Self {
+        Self {
+            min_shift: DEFAULT_MIN_SHIFT,
+            depth: DEFAULT_DEPTH,
+            aux: Vec::new(),
+            reference_sequence_builders: Vec::new(),
+            unplaced_unmapped_record_count: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert!(Indexer::new(32, 10).is_ok());
+        assert!(Indexer::new(62, 0).is_ok());
+
+        assert!(matches!(
+            Indexer::new(0, 11),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+        assert!(matches!(
+            Indexer::new(33, 10),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+        assert!(matches!(
+            Indexer::new(63, 0),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+    }
+
+    #[test]
+    fn test_add_record_at_max_shift() -> io::Result<()> {
+        use noodles_bgzf as bgzf;
+
+        let mut indexer = Indexer::new(32, 10)?;
+        let chunk = Chunk::new(
+            bgzf::VirtualPosition::from(0),
+            bgzf::VirtualPosition::from(55),
+        );
+        indexer.add_record(Some(0), 1, i32::MAX, true, chunk)?;
+
+        let index = indexer.build(1);
+        assert_eq!(index.depth(), 10);
+
+        Ok(())
+    }
+}
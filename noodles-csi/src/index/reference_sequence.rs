@@ -0,0 +1,138 @@
+//! CSI reference sequence and fields.
+
+pub mod bin;
+mod builder;
+mod metadata;
+
+pub(crate) use self::builder::Builder;
+
+pub use self::{bin::Bin, metadata::Metadata};
+
+use noodles_bgzf as bgzf;
+
+/// A reference sequence in a CSI.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReferenceSequence {
+    bins: Vec<Bin>,
+    metadata: Option<Metadata>,
+}
+
+impl ReferenceSequence {
+    /// Creates a CSI reference sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::ReferenceSequence;
+    /// let reference_sequence = ReferenceSequence::new(Vec::new(), None);
+    /// ```
+    pub fn new(bins: Vec<Bin>, metadata: Option<Metadata>) -> Self {
+        Self { bins, metadata }
+    }
+
+    /// Returns the list of bins in this reference sequence.
+    ///
+    /// This list does not include the metadata pseudo-bin. Use [`Self::metadata`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::ReferenceSequence;
+    /// let reference_sequence = ReferenceSequence::new(Vec::new(), None);
+    /// assert!(reference_sequence.bins().is_empty());
+    /// ```
+    pub fn bins(&self) -> &[Bin] {
+        &self.bins
+    }
+
+    /// Returns the metadata of this reference sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::ReferenceSequence;
+    /// let reference_sequence = ReferenceSequence::new(Vec::new(), None);
+    /// assert!(reference_sequence.metadata().is_none());
+    /// ```
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Returns the bins in this reference sequence that intersect the given range.
+    ///
+    /// `min_shift` and `depth` are the parameters of the binning index. `start` and `end` are
+    /// 1-based, inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_csi::index::{reference_sequence::Bin, ReferenceSequence};
+    ///
+    /// let bins = vec![
+    ///     Bin::new(0, bgzf::VirtualPosition::default(), Vec::new()),
+    ///     Bin::new(4681, bgzf::VirtualPosition::default(), Vec::new()),
+    ///     Bin::new(4682, bgzf::VirtualPosition::default(), Vec::new()),
+    /// ];
+    /// let reference_sequence = ReferenceSequence::new(bins, None);
+    ///
+    /// let query_bins = reference_sequence.query(14, 5, 8, 13);
+    /// let ids: Vec<_> = query_bins.iter().map(|bin| bin.id()).collect();
+    /// assert_eq!(ids, [0, 4681]);
+    /// ```
+    pub fn query(&self, min_shift: u8, depth: u8, start: i32, end: i32) -> Vec<&Bin> {
+        let start = (i64::from(start) - 1).max(0) as u64;
+        let end = i64::from(end).max(1) as u64 - 1;
+
+        self.bins()
+            .iter()
+            .filter(|b| {
+                let (bin_start, bin_end) = bin::interval(min_shift, depth, b.id());
+                bin_start <= end && start <= bin_end
+            })
+            .collect()
+    }
+
+    /// Finds the minimum start offset of records that can intersect the given start position.
+    ///
+    /// This is the linear offset of the smallest existing bin that contains or precedes the start
+    /// position. `start` is 1-based.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_csi::index::{reference_sequence::Bin, ReferenceSequence};
+    ///
+    /// let bins = vec![Bin::new(4681, bgzf::VirtualPosition::from(8), Vec::new())];
+    /// let reference_sequence = ReferenceSequence::new(bins, None);
+    ///
+    /// assert_eq!(
+    ///     reference_sequence.min_offset(14, 5, 16385),
+    ///     bgzf::VirtualPosition::from(8),
+    /// );
+    /// ```
+    pub fn min_offset(&self, min_shift: u8, depth: u8, start: i32) -> bgzf::VirtualPosition {
+        let start = (i64::from(start) - 1).max(0) as u64;
+        let mut id = bin::first_id(depth) + (start >> min_shift) as u32;
+
+        loop {
+            if let Some(bin) = self.bins().iter().find(|b| b.id() == id) {
+                return bin.loffset();
+            }
+
+            if id == 0 {
+                return bgzf::VirtualPosition::default();
+            }
+
+            let parent_id = bin::parent_id(id);
+            let first_sibling_id = (parent_id << 3) + 1;
+
+            if id > first_sibling_id {
+                id -= 1;
+            } else {
+                id = parent_id;
+            }
+        }
+    }
+}
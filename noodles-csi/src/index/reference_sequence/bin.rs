@@ -0,0 +1,184 @@
+//! CSI reference sequence bin and fields.
+
+mod chunk;
+
+pub use self::chunk::Chunk;
+
+use noodles_bgzf as bgzf;
+
+/// A CSI reference sequence bin.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bin {
+    id: u32,
+    loffset: bgzf::VirtualPosition,
+    chunks: Vec<Chunk>,
+}
+
+impl Bin {
+    /// Creates a CSI reference sequence bin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_csi::index::reference_sequence::Bin;
+    /// let bin = Bin::new(0, bgzf::VirtualPosition::default(), Vec::new());
+    /// ```
+    pub fn new(id: u32, loffset: bgzf::VirtualPosition, chunks: Vec<Chunk>) -> Self {
+        Self {
+            id,
+            loffset,
+            chunks,
+        }
+    }
+
+    /// Returns the bin ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_csi::index::reference_sequence::Bin;
+    /// let bin = Bin::new(8, bgzf::VirtualPosition::default(), Vec::new());
+    /// assert_eq!(bin.id(), 8);
+    /// ```
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the virtual position of the first record that overlaps the start of this bin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_csi::index::reference_sequence::Bin;
+    /// let bin = Bin::new(8, bgzf::VirtualPosition::from(13), Vec::new());
+    /// assert_eq!(bin.loffset(), bgzf::VirtualPosition::from(13));
+    /// ```
+    pub fn loffset(&self) -> bgzf::VirtualPosition {
+        self.loffset
+    }
+
+    /// Returns the list of chunks in this bin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_csi::index::reference_sequence::Bin;
+    /// let bin = Bin::new(8, bgzf::VirtualPosition::default(), Vec::new());
+    /// assert!(bin.chunks().is_empty());
+    /// ```
+    pub fn chunks(&self) -> &[Chunk] {
+        &self.chunks
+    }
+}
+
+/// Returns the ID of the first bin at the given level.
+pub(crate) fn first_id(level: u8) -> u32 {
+    (((1u64 << (3 * u32::from(level))) - 1) / 7) as u32
+}
+
+/// Returns the number of bins in a binning index of the given depth.
+pub(crate) fn count(depth: u8) -> u32 {
+    first_id(depth + 1)
+}
+
+/// Returns the ID of the metadata pseudo-bin in a binning index of the given depth.
+pub(crate) fn metadata_id(depth: u8) -> u32 {
+    count(depth) + 1
+}
+
+pub(crate) fn parent_id(id: u32) -> u32 {
+    (id - 1) >> 3
+}
+
+fn level(id: u32) -> u8 {
+    let mut level = 0;
+    let mut id = id;
+
+    while id > 0 {
+        id = parent_id(id);
+        level += 1;
+    }
+
+    level
+}
+
+/// Returns the 0-based, inclusive interval covered by the given bin.
+pub(crate) fn interval(min_shift: u8, depth: u8, id: u32) -> (u64, u64) {
+    let level = level(id);
+    let shift = u32::from(min_shift) + 3 * u32::from(depth - level.min(depth));
+    let offset = u64::from(id - first_id(level));
+    let start = offset << shift;
+    (start, start + (1 << shift) - 1)
+}
+
+/// Calculates the bin ID of the smallest bin that contains the given interval.
+///
+/// `start` and `end` are 0-based, inclusive.
+pub(crate) fn region_to_bin(min_shift: u8, depth: u8, start: u64, end: u64) -> u32 {
+    let mut shift = u32::from(min_shift);
+
+    for level in (1..=depth).rev() {
+        if start >> shift == end >> shift {
+            return first_id(level) + (start >> shift) as u32;
+        }
+
+        shift += 3;
+    }
+
+    0
+}
+
+/// Returns the position of the first linear index window covered by the given bin.
+pub(crate) fn first_window(depth: u8, id: u32) -> usize {
+    let level = level(id);
+    ((id - first_id(level)) as usize) << (3 * u32::from(depth - level.min(depth)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_id() {
+        assert_eq!(first_id(0), 0);
+        assert_eq!(first_id(1), 1);
+        assert_eq!(first_id(2), 9);
+        assert_eq!(first_id(5), 4681);
+    }
+
+    #[test]
+    fn test_metadata_id() {
+        assert_eq!(metadata_id(5), 37450);
+        assert_eq!(metadata_id(6), 299594);
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(interval(14, 5, 0), (0, (1 << 29) - 1));
+        assert_eq!(interval(14, 5, 1), (0, (1 << 26) - 1));
+        assert_eq!(interval(14, 5, 4682), (1 << 14, (1 << 15) - 1));
+    }
+
+    #[test]
+    fn test_region_to_bin() {
+        // These match the BAI/tabix binning scheme (min_shift = 14, depth = 5).
+        assert_eq!(region_to_bin(14, 5, 7, 12), 4681);
+        assert_eq!(region_to_bin(14, 5, 63245985, 63245985), 8541);
+        assert_eq!(region_to_bin(14, 5, 16383, 16384), 585);
+        assert_eq!(region_to_bin(14, 5, 0, (1 << 29) - 1), 0);
+
+        // A reference sequence longer than 2^29 bases.
+        assert_eq!(region_to_bin(14, 6, 1 << 30, 1 << 30), 37449 + (1 << 16));
+    }
+
+    #[test]
+    fn test_first_window() {
+        assert_eq!(first_window(5, 0), 0);
+        assert_eq!(first_window(5, 2), 4096);
+        assert_eq!(first_window(5, 4682), 1);
+    }
+}
@@ -0,0 +1,285 @@
+use std::{cmp, collections::HashMap, io};
+
+use noodles_bgzf as bgzf;
+
+use super::{
+    bin::{self, Chunk},
+    Bin, Metadata, ReferenceSequence,
+};
+
+#[derive(Debug)]
+pub struct Builder {
+    min_shift: u8,
+    depth: u8,
+    bins: HashMap<u32, Vec<Chunk>>,
+    linear_index: Vec<Option<bgzf::VirtualPosition>>,
+    last_start: i32,
+    start_position: bgzf::VirtualPosition,
+    end_position: bgzf::VirtualPosition,
+    mapped_record_count: u64,
+    unmapped_record_count: u64,
+}
+
+impl Builder {
+    pub fn new(min_shift: u8, depth: u8) -> Self {
+        Self {
+            min_shift,
+            depth,
+            bins: HashMap::new(),
+            linear_index: Vec::new(),
+            last_start: 0,
+            start_position: bgzf::VirtualPosition::max(),
+            end_position: bgzf::VirtualPosition::default(),
+            mapped_record_count: 0,
+            unmapped_record_count: 0,
+        }
+    }
+
+    pub fn add_record(
+        &mut self,
+        start: i32,
+        end: i32,
+        is_mapped: bool,
+        chunk: Chunk,
+    ) -> io::Result<()> {
+        if start < self.last_start {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "records must be coordinate-sorted",
+            ));
+        }
+
+        self.last_start = start;
+
+        // Convert the 1-based, inclusive interval to a 0-based, inclusive interval. Empty
+        // intervals occupy the position they start at.
+        let start = (start - 1) as u64;
+        let end = cmp::max(end - 1, start as i32) as u64;
+
+        self.update_bins(start, end, chunk);
+        self.update_linear_index(start, end, chunk);
+        self.update_metadata(is_mapped, chunk);
+
+        Ok(())
+    }
+
+    pub fn build(self) -> ReferenceSequence {
+        if self.bins.is_empty() {
+            return ReferenceSequence::default();
+        }
+
+        let linear_index = fill_linear_index(self.linear_index);
+        let depth = self.depth;
+
+        let mut bins: Vec<_> = self
+            .bins
+            .into_iter()
+            .map(|(id, chunks)| {
+                let loffset = linear_index
+                    .get(bin::first_window(depth, id))
+                    .copied()
+                    .unwrap_or_default();
+
+                Bin::new(id, loffset, chunks)
+            })
+            .collect();
+
+        bins.sort_unstable_by_key(|bin| bin.id());
+
+        let metadata = Metadata::new(
+            self.start_position,
+            self.end_position,
+            self.mapped_record_count,
+            self.unmapped_record_count,
+        );
+
+        ReferenceSequence::new(bins, Some(metadata))
+    }
+
+    fn update_bins(&mut self, start: u64, end: u64, chunk: Chunk) {
+        let id = bin::region_to_bin(self.min_shift, self.depth, start, end);
+        let chunks = self.bins.entry(id).or_default();
+
+        // Records are added in order, so a chunk that starts in the same BGZF block as the end
+        // of the previous chunk can be merged. Reading the block is required for either chunk,
+        // so the records in between cost no extra seek.
+        if let Some(last_chunk) = chunks.last_mut() {
+            if chunk.start().compressed() == last_chunk.end().compressed() {
+                *last_chunk = Chunk::new(last_chunk.start(), chunk.end());
+                return;
+            }
+        }
+
+        chunks.push(chunk);
+    }
+
+    fn update_linear_index(&mut self, start: u64, end: u64, chunk: Chunk) {
+        let start_window = (start >> self.min_shift) as usize;
+        let end_window = (end >> self.min_shift) as usize;
+
+        if end_window >= self.linear_index.len() {
+            self.linear_index.resize(end_window + 1, None);
+        }
+
+        for offset in &mut self.linear_index[start_window..=end_window] {
+            offset.get_or_insert(chunk.start());
+        }
+    }
+
+    fn update_metadata(&mut self, is_mapped: bool, chunk: Chunk) {
+        if is_mapped {
+            self.mapped_record_count += 1;
+        } else {
+            self.unmapped_record_count += 1;
+        }
+
+        self.start_position = cmp::min(self.start_position, chunk.start());
+        self.end_position = cmp::max(self.end_position, chunk.end());
+    }
+}
+
+// Windows without records take the offset of the nearest preceding window.
+fn fill_linear_index(
+    linear_index: Vec<Option<bgzf::VirtualPosition>>,
+) -> Vec<bgzf::VirtualPosition> {
+    let mut last_offset = bgzf::VirtualPosition::default();
+
+    linear_index
+        .into_iter()
+        .map(|offset| {
+            if let Some(offset) = offset {
+                last_offset = offset;
+            }
+
+            last_offset
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() -> io::Result<()> {
+        let mut builder = Builder::new(14, 5);
+
+        builder.add_record(
+            2,
+            5,
+            true,
+            Chunk::new(
+                bgzf::VirtualPosition::from(55),
+                bgzf::VirtualPosition::from(89),
+            ),
+        )?;
+
+        builder.add_record(
+            6,
+            5,
+            false,
+            Chunk::new(
+                bgzf::VirtualPosition::from(89),
+                bgzf::VirtualPosition::from(144),
+            ),
+        )?;
+
+        builder.add_record(
+            16385,
+            16390,
+            true,
+            Chunk::new(
+                bgzf::VirtualPosition::from(233),
+                bgzf::VirtualPosition::from(377),
+            ),
+        )?;
+
+        let actual = builder.build();
+
+        let expected = ReferenceSequence::new(
+            vec![
+                Bin::new(
+                    4681,
+                    bgzf::VirtualPosition::from(55),
+                    vec![Chunk::new(
+                        bgzf::VirtualPosition::from(55),
+                        bgzf::VirtualPosition::from(144),
+                    )],
+                ),
+                Bin::new(
+                    4682,
+                    bgzf::VirtualPosition::from(233),
+                    vec![Chunk::new(
+                        bgzf::VirtualPosition::from(233),
+                        bgzf::VirtualPosition::from(377),
+                    )],
+                ),
+            ],
+            Some(Metadata::new(
+                bgzf::VirtualPosition::from(55),
+                bgzf::VirtualPosition::from(377),
+                2,
+                1,
+            )),
+        );
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_chunks_in_the_same_block() -> Result<(), Box<dyn std::error::Error>> {
+        use std::convert::TryFrom;
+
+        let mut builder = Builder::new(14, 5);
+
+        let chunks = [((0, 10), (0, 20)), ((0, 30), (1, 5)), ((2, 0), (2, 8))];
+
+        for (start, end) in chunks {
+            let chunk = Chunk::new(
+                bgzf::VirtualPosition::try_from(start)?,
+                bgzf::VirtualPosition::try_from(end)?,
+            );
+
+            builder.add_record(2, 5, true, chunk)?;
+        }
+
+        let reference_sequence = builder.build();
+        let bins = reference_sequence.bins();
+        assert_eq!(bins.len(), 1);
+
+        let expected = [
+            Chunk::new(
+                bgzf::VirtualPosition::try_from((0, 10))?,
+                bgzf::VirtualPosition::try_from((1, 5))?,
+            ),
+            Chunk::new(
+                bgzf::VirtualPosition::try_from((2, 0))?,
+                bgzf::VirtualPosition::try_from((2, 8))?,
+            ),
+        ];
+
+        assert_eq!(bins[0].chunks(), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_record_with_unsorted_records() -> io::Result<()> {
+        let chunk = Chunk::new(
+            bgzf::VirtualPosition::from(55),
+            bgzf::VirtualPosition::from(89),
+        );
+
+        let mut builder = Builder::new(14, 5);
+        builder.add_record(8, 13, true, chunk)?;
+
+        assert!(matches!(
+            builder.add_record(5, 8, true, chunk),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+//! CSI reference sequence metadata.
+
+use noodles_bgzf::VirtualPosition;
+
+/// Metadata in a CSI reference sequence.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Metadata {
+    start_position: VirtualPosition,
+    end_position: VirtualPosition,
+    mapped_record_count: u64,
+    unmapped_record_count: u64,
+}
+
+impl Metadata {
+    /// Creates reference sequence metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::reference_sequence::Metadata;
+    /// use noodles_bgzf::VirtualPosition;
+    /// let meta = Metadata::new(VirtualPosition::from(610), VirtualPosition::from(1597), 55, 0);
+    /// ```
+    pub fn new(
+        start_position: VirtualPosition,
+        end_position: VirtualPosition,
+        mapped_record_count: u64,
+        unmapped_record_count: u64,
+    ) -> Self {
+        Self {
+            start_position,
+            end_position,
+            mapped_record_count,
+            unmapped_record_count,
+        }
+    }
+
+    /// Returns the start virtual position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::reference_sequence::Metadata;
+    /// use noodles_bgzf::VirtualPosition;
+    /// let meta = Metadata::new(VirtualPosition::from(610), VirtualPosition::from(1597), 55, 0);
+    /// assert_eq!(meta.start_position(), VirtualPosition::from(610));
+    /// ```
+    pub fn start_position(&self) -> VirtualPosition {
+        self.start_position
+    }
+
+    /// Returns the end virtual position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::reference_sequence::Metadata;
+    /// use noodles_bgzf::VirtualPosition;
+    /// let meta = Metadata::new(VirtualPosition::from(610), VirtualPosition::from(1597), 55, 0);
+    /// assert_eq!(meta.end_position(), VirtualPosition::from(1597));
+    /// ```
+    pub fn end_position(&self) -> VirtualPosition {
+        self.end_position
+    }
+
+    /// Returns the number of mapped records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::reference_sequence::Metadata;
+    /// use noodles_bgzf::VirtualPosition;
+    /// let meta = Metadata::new(VirtualPosition::from(610), VirtualPosition::from(1597), 55, 0);
+    /// assert_eq!(meta.mapped_record_count(), 55);
+    /// ```
+    pub fn mapped_record_count(&self) -> u64 {
+        self.mapped_record_count
+    }
+
+    /// Returns the number of unmapped records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::index::reference_sequence::Metadata;
+    /// use noodles_bgzf::VirtualPosition;
+    /// let meta = Metadata::new(VirtualPosition::from(610), VirtualPosition::from(1597), 55, 0);
+    /// assert_eq!(meta.unmapped_record_count(), 0);
+    /// ```
+    pub fn unmapped_record_count(&self) -> u64 {
+        self.unmapped_record_count
+    }
+}
@@ -0,0 +1,177 @@
+#![deny(missing_docs)]
+
+//! **noodles-csi** handles the reading and writing of the [coordinate-sorted index (CSI)
+//! format][csi].
+//!
+//! A CSI is an index used with an associated coordinate-sorted, BGZF-compressed file, e.g., BAM or
+//! bgzipped VCF. Like BAI and tabix, it uses a hierarchical binning scheme, but the size of the
+//! smallest bin (`min_shift`) and the number of levels (`depth`) are configurable. This allows
+//! indexing reference sequences longer than 2^29 bases.
+//!
+//! [csi]: https://samtools.github.io/hts-specs/CSIv1.pdf
+//!
+//! # Examples
+//!
+//! ## Read a CSI
+//!
+//! ```no_run
+//! # use std::io;
+//! use noodles_csi as csi;
+//! let index = csi::read("sample.bam.csi")?;
+//! # Ok::<(), io::Error>(())
+//! ```
+
+mod binning_index;
+pub mod index;
+mod reader;
+mod writer;
+
+pub use self::{binning_index::BinningIndex, index::Index, reader::Reader, writer::Writer};
+
+use std::{fs::File, io, path::Path};
+
+use noodles_bgzf::VirtualPosition;
+
+use self::index::reference_sequence::bin::Chunk;
+
+static MAGIC_NUMBER: &[u8] = b"CSI\x01";
+
+/// Reads the entire contents of a coordinate-sorted index (CSI).
+///
+/// This is a convenience function and is equivalent to opening the file at the given path and
+/// reading the index.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::io;
+/// use noodles_csi as csi;
+/// let index = csi::read("sample.bam.csi")?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn read<P>(src: P) -> io::Result<Index>
+where
+    P: AsRef<Path>,
+{
+    let mut reader = File::open(src).map(Reader::new)?;
+    reader.read_header()?;
+    reader.read_index()
+}
+
+/// Writes a coordinate-sorted index (CSI) to a file.
+///
+/// This is a convenience function and is equivalent to creating a file at the given path, writing
+/// the header, and writing the index.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::io;
+/// use noodles_csi as csi;
+/// let index = csi::Index::default();
+/// csi::write("sample.bam.csi", &index)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn write<P>(dst: P, index: &Index) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let mut writer = File::create(dst).map(Writer::new)?;
+    writer.write_header()?;
+    writer.write_index(index)?;
+    writer.try_finish()
+}
+
+/// Optimizes a list of chunks into a list of non-overlapping chunks.
+///
+/// Chunks that end before `min_offset` (typically the lower bound of the query from the index)
+/// are removed, and the remaining chunks are sorted and merged.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_bgzf as bgzf;
+/// use noodles_csi::{self as csi, index::reference_sequence::bin::Chunk};
+///
+/// let chunks = [
+///     Chunk::new(bgzf::VirtualPosition::from(2), bgzf::VirtualPosition::from(3)),
+///     Chunk::new(bgzf::VirtualPosition::from(5), bgzf::VirtualPosition::from(8)),
+///     Chunk::new(bgzf::VirtualPosition::from(7), bgzf::VirtualPosition::from(13)),
+///     Chunk::new(bgzf::VirtualPosition::from(21), bgzf::VirtualPosition::from(34)),
+/// ];
+/// let min_offset = bgzf::VirtualPosition::from(5);
+///
+/// let actual = csi::optimize_chunks(&chunks, min_offset);
+///
+/// let expected = [
+///     Chunk::new(bgzf::VirtualPosition::from(5), bgzf::VirtualPosition::from(13)),
+///     Chunk::new(bgzf::VirtualPosition::from(21), bgzf::VirtualPosition::from(34)),
+/// ];
+///
+/// assert_eq!(actual, expected);
+/// ```
+pub fn optimize_chunks(chunks: &[Chunk], min_offset: VirtualPosition) -> Vec<Chunk> {
+    let mut chunks: Vec<_> = chunks
+        .iter()
+        .filter(|c| c.end() > min_offset)
+        .copied()
+        .collect();
+
+    if chunks.is_empty() {
+        return chunks;
+    }
+
+    chunks.sort_unstable_by_key(|c| c.start());
+
+    let mut merged_chunks = Vec::with_capacity(chunks.len());
+
+    // `chunks` is guaranteed to be non-empty.
+    let mut current_chunk = chunks[0];
+
+    for next_chunk in chunks.iter().skip(1) {
+        if next_chunk.start() > current_chunk.end() {
+            merged_chunks.push(current_chunk);
+            current_chunk = *next_chunk;
+        } else if current_chunk.end() < next_chunk.end() {
+            current_chunk = Chunk::new(current_chunk.start(), next_chunk.end());
+        }
+    }
+
+    merged_chunks.push(current_chunk);
+
+    merged_chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimize_chunks() {
+        let chunks = [
+            Chunk::new(VirtualPosition::from(2), VirtualPosition::from(5)),
+            Chunk::new(VirtualPosition::from(3), VirtualPosition::from(4)),
+            Chunk::new(VirtualPosition::from(5), VirtualPosition::from(7)),
+            Chunk::new(VirtualPosition::from(9), VirtualPosition::from(12)),
+            Chunk::new(VirtualPosition::from(10), VirtualPosition::from(15)),
+            Chunk::new(VirtualPosition::from(16), VirtualPosition::from(21)),
+        ];
+
+        let actual = optimize_chunks(&chunks, VirtualPosition::default());
+        let expected = [
+            Chunk::new(VirtualPosition::from(2), VirtualPosition::from(7)),
+            Chunk::new(VirtualPosition::from(9), VirtualPosition::from(15)),
+            Chunk::new(VirtualPosition::from(16), VirtualPosition::from(21)),
+        ];
+        assert_eq!(actual, expected);
+
+        let actual = optimize_chunks(&chunks, VirtualPosition::from(10));
+        let expected = [
+            Chunk::new(VirtualPosition::from(9), VirtualPosition::from(15)),
+            Chunk::new(VirtualPosition::from(16), VirtualPosition::from(21)),
+        ];
+        assert_eq!(actual, expected);
+
+        assert!(optimize_chunks(&[], VirtualPosition::default()).is_empty());
+    }
+}
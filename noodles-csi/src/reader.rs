@@ -0,0 +1,296 @@
+use std::{
+    convert::TryFrom,
+    io::{self, Read},
+};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+use noodles_bgzf as bgzf;
+
+use super::{
+    index::{
+        reference_sequence::{
+            bin::{self, Chunk},
+            Bin, Metadata,
+        },
+        validate_binning_scheme, ReferenceSequence,
+    },
+    Index, MAGIC_NUMBER,
+};
+
+/// A CSI reader.
+///
+/// A CSI is BGZF-compressed and has four top-level fields:
+///
+///   1. a magic number,
+///   2. the binning scheme parameters (`min_shift` and `depth`) and auxiliary data,
+///   3. a list of reference sequences,
+///   4. and optionally, the number of unplaced unmapped records in the associated file.
+///
+/// While these fields can be read individually, consider using [`super::read`] to read the entire
+/// index at once.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_csi as csi;
+/// let mut reader = File::open("sample.bam.csi").map(csi::Reader::new)?;
+/// reader.read_header()?;
+/// let index = reader.read_index()?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct Reader<R> {
+    inner: bgzf::Reader<R>,
+}
+
+impl<R> Reader<R>
+where
+    R: Read,
+{
+    /// Creates a CSI reader.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_csi as csi;
+    /// let reader = File::open("sample.bam.csi").map(csi::Reader::new)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: bgzf::Reader::new(inner),
+        }
+    }
+
+    /// Reads the CSI header.
+    ///
+    /// The CSI header is just the magic number of the file format.
+    ///
+    /// The position of the stream is expected to be at the start.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_csi as csi;
+    /// let mut reader = File::open("sample.bam.csi").map(csi::Reader::new)?;
+    /// reader.read_header()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_header(&mut self) -> io::Result<()> {
+        let mut magic = [0; 4];
+        self.inner.read_exact(&mut magic)?;
+
+        if magic != MAGIC_NUMBER {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid CSI header",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the CSI.
+    ///
+    /// The position of the stream is expected to be directly after the header.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_csi as csi;
+    /// let mut reader = File::open("sample.bam.csi").map(csi::Reader::new)?;
+    /// reader.read_header()?;
+    /// let index = reader.read_index()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_index(&mut self) -> io::Result<Index> {
+        let min_shift = self.inner.read_i32::<LittleEndian>().and_then(|n| {
+            u8::try_from(n).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })?;
+
+        let depth = self.inner.read_i32::<LittleEndian>().and_then(|n| {
+            u8::try_from(n).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })?;
+
+        validate_binning_scheme(min_shift, depth, io::ErrorKind::InvalidData)?;
+
+        let aux = read_aux(&mut self.inner)?;
+        let reference_sequences = read_reference_sequences(&mut self.inner, depth)?;
+        let n_no_coor = self.inner.read_u64::<LittleEndian>().ok();
+
+        let mut builder = Index::builder()
+            .set_min_shift(min_shift)
+            .set_depth(depth)
+            .set_aux(aux)
+            .set_reference_sequences(reference_sequences);
+
+        if let Some(unplaced_unmapped_record_count) = n_no_coor {
+            builder = builder.set_unplaced_unmapped_record_count(unplaced_unmapped_record_count);
+        }
+
+        builder.build()
+    }
+}
+
+fn read_len<R>(reader: &mut R) -> io::Result<usize>
+where
+    R: Read,
+{
+    reader
+        .read_i32::<LittleEndian>()
+        .and_then(|n| usize::try_from(n).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+fn read_aux<R>(reader: &mut R) -> io::Result<Vec<u8>>
+where
+    R: Read,
+{
+    let l_aux = read_len(reader)?;
+    let mut aux = vec![0; l_aux];
+    reader.read_exact(&mut aux)?;
+    Ok(aux)
+}
+
+fn read_reference_sequences<R>(reader: &mut R, depth: u8) -> io::Result<Vec<ReferenceSequence>>
+where
+    R: Read,
+{
+    let n_ref = read_len(reader)?;
+    let mut reference_sequences = Vec::with_capacity(n_ref);
+
+    for _ in 0..n_ref {
+        let (bins, metadata) = read_bins(reader, depth)?;
+        reference_sequences.push(ReferenceSequence::new(bins, metadata));
+    }
+
+    Ok(reference_sequences)
+}
+
+fn read_bins<R>(reader: &mut R, depth: u8) -> io::Result<(Vec<Bin>, Option<Metadata>)>
+where
+    R: Read,
+{
+    let metadata_id = bin::metadata_id(depth);
+
+    let n_bin = read_len(reader)?;
+    let mut bins = Vec::with_capacity(n_bin);
+    let mut metadata = None;
+
+    for _ in 0..n_bin {
+        let id = reader.read_u32::<LittleEndian>()?;
+        let loffset = reader
+            .read_u64::<LittleEndian>()
+            .map(bgzf::VirtualPosition::from)?;
+        let chunks = read_chunks(reader)?;
+
+        if id == metadata_id {
+            metadata = parse_metadata(&chunks).map(Some)?;
+        } else {
+            bins.push(Bin::new(id, loffset, chunks));
+        }
+    }
+
+    Ok((bins, metadata))
+}
+
+fn read_chunks<R>(reader: &mut R) -> io::Result<Vec<Chunk>>
+where
+    R: Read,
+{
+    let n_chunk = read_len(reader)?;
+    let mut chunks = Vec::with_capacity(n_chunk);
+
+    for _ in 0..n_chunk {
+        let chunk_beg = reader
+            .read_u64::<LittleEndian>()
+            .map(bgzf::VirtualPosition::from)?;
+
+        let chunk_end = reader
+            .read_u64::<LittleEndian>()
+            .map(bgzf::VirtualPosition::from)?;
+
+        chunks.push(Chunk::new(chunk_beg, chunk_end));
+    }
+
+    Ok(chunks)
+}
+
+fn parse_metadata(chunks: &[Chunk]) -> io::Result<Metadata> {
+    match chunks {
+        [positions_chunk, counts_chunk] => Ok(Metadata::new(
+            positions_chunk.start(),
+            positions_chunk.end(),
+            u64::from(counts_chunk.start()),
+            u64::from(counts_chunk.end()),
+        )),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "invalid metadata pseudo-bin: expected 2 chunks, got {}",
+                chunks.len()
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+
+    #[test]
+    fn test_read_header_with_invalid_magic_number() -> io::Result<()> {
+        let mut writer = bgzf::Writer::new(Vec::new());
+        writer.write_all(b"BAI\x01")?;
+        let data = writer.finish()?;
+
+        let mut reader = Reader::new(&data[..]);
+
+        assert!(matches!(
+            reader.read_header(),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_index_with_invalid_binning_scheme() -> io::Result<()> {
+        use byteorder::WriteBytesExt;
+
+        fn read_index(min_shift: i32, depth: i32) -> io::Result<Index> {
+            let mut writer = bgzf::Writer::new(Vec::new());
+            writer.write_i32::<LittleEndian>(min_shift)?;
+            writer.write_i32::<LittleEndian>(depth)?;
+            let data = writer.finish()?;
+
+            let mut reader = Reader::new(&data[..]);
+            reader.read_index()
+        }
+
+        // The binning scheme is valid, but the index is truncated.
+        assert!(matches!(
+            read_index(32, 10),
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+
+        assert!(matches!(
+            read_index(0, 11),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+        assert!(matches!(
+            read_index(33, 10),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+        assert!(matches!(
+            read_index(63, 0),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    convert::TryFrom,
+    io::{self, Write},
+};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use noodles_bgzf as bgzf;
+
+use super::{
+    index::{
+        reference_sequence::{
+            bin::{self, Chunk},
+            Bin, Metadata,
+        },
+        ReferenceSequence,
+    },
+    Index, MAGIC_NUMBER,
+};
+
+/// A CSI writer.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_csi as csi;
+///
+/// let index = csi::Index::default();
+///
+/// let mut writer = File::create("sample.bam.csi").map(csi::Writer::new)?;
+/// writer.write_header()?;
+/// writer.write_index(&index)?;
+/// writer.try_finish()?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct Writer<W>
+where
+    W: Write,
+{
+    inner: bgzf::Writer<W>,
+}
+
+impl<W> Writer<W>
+where
+    W: Write,
+{
+    /// Creates a CSI writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi as csi;
+    /// let writer = csi::Writer::new(Vec::new());
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner: bgzf::Writer::new(inner),
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi as csi;
+    /// let writer = csi::Writer::new(Vec::new());
+    /// assert!(writer.get_ref().is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &W {
+        self.inner.get_ref()
+    }
+
+    /// Attempts to finish the output stream.
+    ///
+    /// This is typically only manually called if the underlying stream is needed before the
+    /// writer is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    /// let mut writer = csi::Writer::new(Vec::new());
+    /// writer.try_finish()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn try_finish(&mut self) -> io::Result<()> {
+        self.inner.try_finish()
+    }
+
+    /// Writes a CSI header.
+    ///
+    /// This writes the magic number of the file format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    /// let mut writer = csi::Writer::new(Vec::new());
+    /// writer.write_header()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_header(&mut self) -> io::Result<()> {
+        self.inner.write_all(MAGIC_NUMBER)
+    }
+
+    /// Writes a CSI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi as csi;
+    ///
+    /// let index = csi::Index::default();
+    ///
+    /// let mut writer = csi::Writer::new(Vec::new());
+    /// writer.write_header()?;
+    /// writer.write_index(&index)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_index(&mut self, index: &Index) -> io::Result<()> {
+        self.inner
+            .write_i32::<LittleEndian>(i32::from(index.min_shift()))?;
+        self.inner
+            .write_i32::<LittleEndian>(i32::from(index.depth()))?;
+
+        write_len(&mut self.inner, index.aux().len())?;
+        self.inner.write_all(index.aux())?;
+
+        write_len(&mut self.inner, index.reference_sequences().len())?;
+
+        for reference_sequence in index.reference_sequences() {
+            write_reference_sequence(&mut self.inner, index.depth(), reference_sequence)?;
+        }
+
+        if let Some(n_no_coor) = index.unplaced_unmapped_record_count() {
+            self.inner.write_u64::<LittleEndian>(n_no_coor)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_len<W>(writer: &mut W, len: usize) -> io::Result<()>
+where
+    W: Write,
+{
+    let n = i32::try_from(len).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    writer.write_i32::<LittleEndian>(n)
+}
+
+fn write_reference_sequence<W>(
+    writer: &mut W,
+    depth: u8,
+    reference_sequence: &ReferenceSequence,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    let mut n_bin = reference_sequence.bins().len();
+
+    if reference_sequence.metadata().is_some() {
+        n_bin += 1;
+    }
+
+    write_len(writer, n_bin)?;
+
+    for bin in reference_sequence.bins() {
+        write_bin(writer, bin)?;
+    }
+
+    if let Some(metadata) = reference_sequence.metadata() {
+        let bin = metadata_to_bin(depth, metadata);
+        write_bin(writer, &bin)?;
+    }
+
+    Ok(())
+}
+
+fn metadata_to_bin(depth: u8, metadata: &Metadata) -> Bin {
+    let positions_chunk = Chunk::new(metadata.start_position(), metadata.end_position());
+
+    let counts_chunk = Chunk::new(
+        bgzf::VirtualPosition::from(metadata.mapped_record_count()),
+        bgzf::VirtualPosition::from(metadata.unmapped_record_count()),
+    );
+
+    Bin::new(
+        bin::metadata_id(depth),
+        bgzf::VirtualPosition::default(),
+        vec![positions_chunk, counts_chunk],
+    )
+}
+
+fn write_bin<W>(writer: &mut W, bin: &Bin) -> io::Result<()>
+where
+    W: Write,
+{
+    writer.write_u32::<LittleEndian>(bin.id())?;
+    writer.write_u64::<LittleEndian>(u64::from(bin.loffset()))?;
+
+    write_len(writer, bin.chunks().len())?;
+
+    for chunk in bin.chunks() {
+        writer.write_u64::<LittleEndian>(u64::from(chunk.start()))?;
+        writer.write_u64::<LittleEndian>(u64::from(chunk.end()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{index::Indexer, Reader};
+
+    use super::*;
+
+    #[test]
+    fn test_write_index() -> io::Result<()> {
+        let mut indexer = Indexer::new(14, 6)?;
+        indexer.set_aux(b"ndls".to_vec());
+
+        indexer.add_record(
+            Some(0),
+            8,
+            13,
+            true,
+            Chunk::new(
+                bgzf::VirtualPosition::from(55),
+                bgzf::VirtualPosition::from(89),
+            ),
+        )?;
+
+        // This is past the maximum position of a BAI.
+        indexer.add_record(
+            Some(1),
+            1 << 30,
+            (1 << 30) + 8,
+            true,
+            Chunk::new(
+                bgzf::VirtualPosition::from(89),
+                bgzf::VirtualPosition::from(144),
+            ),
+        )?;
+
+        indexer.add_record(
+            None,
+            0,
+            0,
+            false,
+            Chunk::new(
+                bgzf::VirtualPosition::from(144),
+                bgzf::VirtualPosition::from(233),
+            ),
+        )?;
+
+        let index = indexer.build(2);
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header()?;
+        writer.write_index(&index)?;
+        writer.try_finish()?;
+
+        let mut reader = Reader::new(&writer.get_ref()[..]);
+        reader.read_header()?;
+        let actual = reader.read_index()?;
+
+        assert_eq!(actual, index);
+        assert_eq!(actual.min_shift(), 14);
+        assert_eq!(actual.depth(), 6);
+        assert_eq!(actual.aux(), b"ndls");
+        assert_eq!(actual.unplaced_unmapped_record_count(), Some(1));
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
 //! Prints the reference sequence names and lengths of all the records in a FASTA file.
+//!
+//! The input can be uncompressed, gzip-compressed, or BGZF-compressed.
 
 use std::{
     env,
@@ -6,6 +8,7 @@ use std::{
     io::{self, BufReader},
 };
 
+use noodles_bgzf as bgzf;
 use noodles_fasta as fasta;
 
 fn main() -> io::Result<()> {
@@ -13,6 +16,7 @@ fn main() -> io::Result<()> {
 
     let mut reader = File::open(src)
         .map(BufReader::new)
+        .and_then(bgzf::AutoReader::new)
         .map(fasta::Reader::new)?;
 
     for result in reader.records() {
@@ -1,4 +1,6 @@
 //! Counts the number of records in a FASTQ file.
+//!
+//! The input can be uncompressed, gzip-compressed, or BGZF-compressed.
 
 use std::{
     env,
@@ -6,6 +8,7 @@ use std::{
     io::{self, BufReader},
 };
 
+use noodles_bgzf as bgzf;
 use noodles_fastq as fastq;
 
 fn main() -> io::Result<()> {
@@ -13,6 +16,7 @@ fn main() -> io::Result<()> {
 
     let mut reader = File::open(src)
         .map(BufReader::new)
+        .and_then(bgzf::AutoReader::new)
         .map(fastq::Reader::new)?;
 
     let mut record = fastq::Record::default();
@@ -191,6 +191,27 @@ dcba
         assert_eq!(len, 0);
     }
 
+    #[test]
+    fn test_read_record_with_bgzf_compressed_data() -> io::Result<()> {
+        use std::io::Write;
+
+        use noodles_bgzf as bgzf;
+
+        let mut writer = bgzf::Writer::new(Vec::new());
+        writer.write_all(b"@noodles:1/1\nAGCT\n+\nabcd\n")?;
+        let data = writer.finish()?;
+
+        let mut reader = bgzf::AutoReader::new(&data[..]).map(Reader::new)?;
+        let mut record = Record::default();
+
+        reader.read_record(&mut record)?;
+        assert_eq!(record, Record::new("noodles:1/1", "AGCT", "abcd"));
+
+        assert_eq!(reader.read_record(&mut record)?, 0);
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_line() -> io::Result<()> {
         let mut buf = Vec::new();
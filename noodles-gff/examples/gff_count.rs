@@ -1,6 +1,8 @@
 //! Counts the number of records in a GFF file.
 //!
 //! The result matches the output of `grep --invert-match '^#' <src> | wc -l`.
+//!
+//! The input can be uncompressed, gzip-compressed, or BGZF-compressed.
 
 use std::{
     env,
@@ -8,12 +10,16 @@ use std::{
     io::{self, BufReader},
 };
 
+use noodles_bgzf as bgzf;
 use noodles_gff as gff;
 
 fn main() -> io::Result<()> {
     let src = env::args().nth(1).expect("missing src");
 
-    let mut reader = File::open(src).map(BufReader::new).map(gff::Reader::new)?;
+    let mut reader = File::open(src)
+        .map(BufReader::new)
+        .and_then(bgzf::AutoReader::new)
+        .map(gff::Reader::new)?;
     let mut n = 0;
 
     for result in reader.records() {
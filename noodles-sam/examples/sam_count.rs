@@ -1,6 +1,8 @@
 //! Counts the number of records in a SAM file.
 //!
 //! The result matches the output of `samtools view -c <src>`.
+//!
+//! The input can be uncompressed, gzip-compressed, or BGZF-compressed.
 
 use std::{
     env,
@@ -8,12 +10,16 @@ use std::{
     io::{self, BufReader},
 };
 
+use noodles_bgzf as bgzf;
 use noodles_sam as sam;
 
 fn main() -> io::Result<()> {
     let src = env::args().nth(1).expect("missing src");
 
-    let mut reader = File::open(src).map(BufReader::new).map(sam::Reader::new)?;
+    let mut reader = File::open(src)
+        .map(BufReader::new)
+        .and_then(bgzf::AutoReader::new)
+        .map(sam::Reader::new)?;
     reader.read_header()?;
 
     let mut n = 0;
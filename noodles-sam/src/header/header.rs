@@ -104,6 +104,23 @@ impl Header {
         self.sort_order
     }
 
+    /// Returns a mutable reference to the sort order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::header::header::{Header, SortOrder};
+    ///
+    /// let mut header = Header::default();
+    /// assert!(header.sort_order().is_none());
+    ///
+    /// *header.sort_order_mut() = Some(SortOrder::Coordinate);
+    /// assert_eq!(header.sort_order(), Some(SortOrder::Coordinate));
+    /// ```
+    pub fn sort_order_mut(&mut self) -> &mut Option<SortOrder> {
+        &mut self.sort_order
+    }
+
     /// Returns the group order.
     ///
     /// # Examples
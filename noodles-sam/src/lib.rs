@@ -34,6 +34,7 @@
 pub mod header;
 pub mod reader;
 pub mod record;
-mod writer;
+pub mod validator;
+pub mod writer;
 
 pub use self::{header::Header, reader::Reader, record::Record, writer::Writer};
@@ -6,7 +6,7 @@ use std::{error, fmt, ops::Deref, str::FromStr};
 
 pub use self::op::Op;
 
-use super::NULL_FIELD;
+use super::{data::field::Value, NULL_FIELD};
 
 use self::op::Kind;
 
@@ -44,6 +44,38 @@ impl Cigar {
             })
             .sum()
     }
+
+    /// Calculates the read length.
+    ///
+    /// This sums the lengths of the CIGAR operations that consume the read, i.e., alignment
+    /// matches (`M`), insertions to the reference (`I`), soft clips (`S`), sequence matches (`=`),
+    /// and sequence mismatches (`X`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::{cigar::{op::Kind, Op}, Cigar};
+    ///
+    /// let cigar = Cigar::from(vec![
+    ///     Op::new(Kind::Match, 36),
+    ///     Op::new(Kind::Deletion, 4),
+    ///     Op::new(Kind::SoftClip, 8),
+    /// ]);
+    ///
+    /// assert_eq!(cigar.read_len(), 44);
+    /// ```
+    pub fn read_len(&self) -> u32 {
+        self.iter()
+            .filter_map(|op| match op.kind() {
+                Kind::Match
+                | Kind::Insertion
+                | Kind::SoftClip
+                | Kind::SeqMatch
+                | Kind::SeqMismatch => Some(op.len()),
+                _ => None,
+            })
+            .sum()
+    }
 }
 
 impl Deref for Cigar {
@@ -74,6 +106,12 @@ impl From<Vec<Op>> for Cigar {
     }
 }
 
+impl From<Cigar> for Value {
+    fn from(cigar: Cigar) -> Self {
+        Self::String(cigar.to_string())
+    }
+}
+
 /// An error returned when a raw CIGAR string fails to parse.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ParseError {
@@ -143,6 +181,12 @@ mod tests {
         assert_eq!(format!("{}", cigar), "1M13N144S");
     }
 
+    #[test]
+    fn test_from_cigar_for_value() {
+        let cigar = Cigar::from(vec![Op::new(Kind::SoftClip, 2), Op::new(Kind::Match, 4)]);
+        assert_eq!(Value::from(cigar), Value::String(String::from("2S4M")));
+    }
+
     #[test]
     fn test_fmt_when_cigar_has_no_ops() {
         let cigar = Cigar::default();
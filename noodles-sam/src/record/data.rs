@@ -1,11 +1,21 @@
 //! SAM record data and fields.
 
+pub mod alternative_hits;
 pub mod field;
+pub mod other_alignments;
+pub mod strand;
 
-pub use self::field::Field;
+pub use self::{
+    alternative_hits::AlternativeHits, field::Field, other_alignments::OtherAlignments,
+    strand::Strand,
+};
 
 use std::{error, fmt, ops::Deref, str::FromStr};
 
+use super::{cigar, Cigar};
+
+use self::field::{value::Type, Tag};
+
 const DELIMITER: char = '\t';
 
 /// SAM record data.
@@ -14,6 +24,65 @@ const DELIMITER: char = '\t';
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Data(Vec<Field>);
 
+impl Data {
+    /// Returns the field with the given tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::{
+    ///     data::{field::{Tag, Value}, Field},
+    ///     Cigar, Data,
+    /// };
+    ///
+    /// let data = Data::from(vec![
+    ///     Field::new(Tag::AlignmentHitCount, Value::Int32(1)),
+    ///     Field::new(Tag::MateCigar, Value::String(String::from("4M"))),
+    /// ]);
+    ///
+    /// let mate_cigar = data
+    ///     .get(&Tag::MateCigar)
+    ///     .and_then(|field| field.value().as_str())
+    ///     .map(|s| s.parse::<Cigar>())
+    ///     .transpose()?;
+    ///
+    /// assert_eq!(mate_cigar.map(|cigar| cigar.to_string()), Some(String::from("4M")));
+    /// assert!(data.get(&Tag::ReadGroup).is_none());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get(&self, tag: &Tag) -> Option<&Field> {
+        self.iter().find(|field| field.tag() == tag)
+    }
+
+    /// Returns the parsed mate CIGAR (`MC`), if the field is present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::{
+    ///     data::{field::{Tag, Value}, Field},
+    ///     Cigar, Data,
+    /// };
+    ///
+    /// let cigar: Cigar = "2S4M".parse()?;
+    /// let data = Data::from(vec![Field::new(Tag::MateCigar, Value::from(cigar.clone()))]);
+    /// assert_eq!(data.mate_cigar().transpose()?, Some(cigar));
+    ///
+    /// assert!(Data::default().mate_cigar().is_none());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn mate_cigar(&self) -> Option<Result<Cigar, MateCigarError>> {
+        self.get(&Tag::MateCigar).map(|field| {
+            let value = field.value();
+
+            value
+                .as_str()
+                .ok_or_else(|| MateCigarError::InvalidType(value.ty()))
+                .and_then(|s| s.parse().map_err(MateCigarError::InvalidCigar))
+        })
+    }
+}
+
 impl Deref for Data {
     type Target = [Field];
 
@@ -42,6 +111,26 @@ impl From<Vec<Field>> for Data {
     }
 }
 
+/// An error returned when a SAM record data mate CIGAR (`MC`) field value is invalid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MateCigarError {
+    /// The value type is not a string (`Z`).
+    InvalidType(Type),
+    /// The CIGAR is invalid.
+    InvalidCigar(cigar::ParseError),
+}
+
+impl error::Error for MateCigarError {}
+
+impl fmt::Display for MateCigarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidType(ty) => write!(f, "invalid type: expected Z, got {}", ty),
+            Self::InvalidCigar(e) => write!(f, "invalid CIGAR: {}", e),
+        }
+    }
+}
+
 /// An error returned when raw SAM record data fails to parse.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ParseError {
@@ -76,7 +165,7 @@ impl FromStr for Data {
 
 #[cfg(test)]
 mod tests {
-    use super::field::{Tag, Value};
+    use super::field::Value;
 
     use super::*;
 
@@ -92,6 +181,43 @@ mod tests {
         assert_eq!(data.to_string(), expected);
     }
 
+    #[test]
+    fn test_mate_cigar() -> Result<(), cigar::ParseError> {
+        let cigar: Cigar = "2S4M".parse()?;
+        let data = Data::from(vec![Field::new(Tag::MateCigar, Value::from(cigar.clone()))]);
+        assert_eq!(data.mate_cigar(), Some(Ok(cigar)));
+
+        let data = Data::from(vec![Field::new(Tag::MateCigar, Value::Int32(4))]);
+        assert_eq!(
+            data.mate_cigar(),
+            Some(Err(MateCigarError::InvalidType(Type::Int32)))
+        );
+
+        let data = Data::from(vec![Field::new(
+            Tag::MateCigar,
+            Value::String(String::new()),
+        )]);
+        assert_eq!(
+            data.mate_cigar(),
+            Some(Err(MateCigarError::InvalidCigar(cigar::ParseError::Empty)))
+        );
+
+        assert!(Data::default().mate_cigar().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mate_cigar_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let cigar: Cigar = "1M13N144S".parse()?;
+        let data = Data::from(vec![Field::new(Tag::MateCigar, Value::from(cigar.clone()))]);
+
+        let actual: Data = data.to_string().parse()?;
+        assert_eq!(actual.mate_cigar().transpose()?, Some(cigar));
+
+        Ok(())
+    }
+
     #[test]
     fn test_from_str() {
         assert_eq!(
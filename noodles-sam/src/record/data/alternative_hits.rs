@@ -0,0 +1,166 @@
+//! SAM record data alternative hits (`XA`) and components.
+
+pub mod hit;
+
+pub use self::hit::Hit;
+
+use std::{error, fmt, ops::Deref, str::FromStr};
+
+use super::field::Value;
+
+const TERMINATOR: char = ';';
+
+/// SAM record data alternative hits (`XA`).
+///
+/// This is the list of alternative hits of a read, as written by BWA. Each hit is terminated by a
+/// semicolon, e.g., `sq0,+8,4M,0;sq1,-21,4M,1;`.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::record::data::{
+///     field::{Tag, Value},
+///     AlternativeHits, Field,
+/// };
+///
+/// let alternative_hits: AlternativeHits = "sq0,+8,4M,0;".parse()?;
+/// assert_eq!(alternative_hits.len(), 1);
+///
+/// let field = Field::new("XA".parse()?, Value::from(alternative_hits));
+/// assert_eq!(field.to_string(), "XA:Z:sq0,+8,4M,0;");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AlternativeHits(Vec<Hit>);
+
+impl Deref for AlternativeHits {
+    type Target = [Hit];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for AlternativeHits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for hit in self.iter() {
+            write!(f, "{}{}", hit, TERMINATOR)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Vec<Hit>> for AlternativeHits {
+    fn from(hits: Vec<Hit>) -> Self {
+        Self(hits)
+    }
+}
+
+impl From<AlternativeHits> for Value {
+    fn from(alternative_hits: AlternativeHits) -> Self {
+        Self::String(alternative_hits.to_string())
+    }
+}
+
+/// An error returned when raw SAM record data alternative hits (`XA`) fail to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// A hit is invalid.
+    InvalidHit(hit::ParseError),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::InvalidHit(e) => write!(f, "invalid hit: {}", e),
+        }
+    }
+}
+
+impl FromStr for AlternativeHits {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        // The final terminator is optional.
+        s.strip_suffix(TERMINATOR)
+            .unwrap_or(s)
+            .split(TERMINATOR)
+            .map(|t| t.parse().map_err(ParseError::InvalidHit))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::record::Position;
+
+    use super::{super::Strand, *};
+
+    fn build_alternative_hits() -> Result<AlternativeHits, Box<dyn std::error::Error>> {
+        Ok(AlternativeHits::from(vec![
+            Hit::new(
+                "sq0".parse()?,
+                Strand::Forward,
+                Position::try_from(8)?,
+                "4M".parse()?,
+                0,
+            ),
+            Hit::new(
+                "sq1".parse()?,
+                Strand::Reverse,
+                Position::try_from(21)?,
+                "4M".parse()?,
+                1,
+            ),
+        ]))
+    }
+
+    #[test]
+    fn test_fmt() -> Result<(), Box<dyn std::error::Error>> {
+        let alternative_hits = build_alternative_hits()?;
+        assert_eq!(alternative_hits.to_string(), "sq0,+8,4M,0;sq1,-21,4M,1;");
+        assert!(AlternativeHits::default().to_string().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> Result<(), Box<dyn std::error::Error>> {
+        let expected = build_alternative_hits()?;
+
+        assert_eq!("sq0,+8,4M,0;sq1,-21,4M,1;".parse(), Ok(expected.clone()));
+        assert_eq!("sq0,+8,4M,0;sq1,-21,4M,1".parse(), Ok(expected));
+
+        assert_eq!("".parse::<AlternativeHits>(), Err(ParseError::Empty));
+        assert_eq!(
+            "sq0,+8,4M".parse::<AlternativeHits>(),
+            Err(ParseError::InvalidHit(hit::ParseError::MissingEditDistance))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_alternative_hits_for_value() -> Result<(), Box<dyn std::error::Error>> {
+        let alternative_hits: AlternativeHits = "sq0,+8,4M,0;".parse()?;
+
+        assert_eq!(
+            Value::from(alternative_hits),
+            Value::String(String::from("sq0,+8,4M,0;"))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+//! SAM record data alternative hits (`XA`) hit.
+
+use std::{error, fmt, num, str::FromStr};
+
+use crate::record::{
+    cigar, position, reference_sequence_name, Cigar, Position, ReferenceSequenceName,
+};
+
+use super::super::{strand, Strand};
+
+const DELIMITER: char = ',';
+
+/// A SAM record data alternative hits (`XA`) hit.
+///
+/// This is a single entry in the list of alternative hits, formatted as `rname,±pos,CIGAR,NM`,
+/// where the position is prefixed with the strand.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Hit {
+    reference_sequence_name: ReferenceSequenceName,
+    strand: Strand,
+    position: Position,
+    cigar: Cigar,
+    edit_distance: u32,
+}
+
+impl Hit {
+    /// Creates an alternative hits (`XA`) hit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// use noodles_sam::record::{
+    ///     data::{alternative_hits::Hit, Strand},
+    ///     Position,
+    /// };
+    ///
+    /// let hit = Hit::new(
+    ///     "sq0".parse()?,
+    ///     Strand::Reverse,
+    ///     Position::try_from(8)?,
+    ///     "4M".parse()?,
+    ///     0,
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(
+        reference_sequence_name: ReferenceSequenceName,
+        strand: Strand,
+        position: Position,
+        cigar: Cigar,
+        edit_distance: u32,
+    ) -> Self {
+        Self {
+            reference_sequence_name,
+            strand,
+            position,
+            cigar,
+            edit_distance,
+        }
+    }
+
+    /// Returns the reference sequence name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::alternative_hits::Hit;
+    /// let hit: Hit = "sq0,-8,4M,0".parse()?;
+    /// assert_eq!(hit.reference_sequence_name().as_str(), "sq0");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reference_sequence_name(&self) -> &ReferenceSequenceName {
+        &self.reference_sequence_name
+    }
+
+    /// Returns the strand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{alternative_hits::Hit, Strand};
+    /// let hit: Hit = "sq0,-8,4M,0".parse()?;
+    /// assert_eq!(hit.strand(), Strand::Reverse);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn strand(&self) -> Strand {
+        self.strand
+    }
+
+    /// Returns the start position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::alternative_hits::Hit;
+    /// let hit: Hit = "sq0,-8,4M,0".parse()?;
+    /// assert_eq!(i32::from(hit.position()), 8);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Returns the CIGAR operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::alternative_hits::Hit;
+    /// let hit: Hit = "sq0,-8,4M,0".parse()?;
+    /// assert_eq!(hit.cigar().to_string(), "4M");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn cigar(&self) -> &Cigar {
+        &self.cigar
+    }
+
+    /// Returns the edit distance (`NM`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::alternative_hits::Hit;
+    /// let hit: Hit = "sq0,-8,4M,0".parse()?;
+    /// assert_eq!(hit.edit_distance(), 0);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn edit_distance(&self) -> u32 {
+        self.edit_distance
+    }
+}
+
+impl fmt::Display for Hit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{rname}{d}{strand}{pos}{d}{cigar}{d}{nm}",
+            rname = self.reference_sequence_name,
+            strand = self.strand,
+            pos = i32::from(self.position),
+            cigar = self.cigar,
+            nm = self.edit_distance,
+            d = DELIMITER,
+        )
+    }
+}
+
+/// An error returned when a raw SAM record data alternative hits (`XA`) hit fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The reference sequence name is missing.
+    MissingReferenceSequenceName,
+    /// The reference sequence name is invalid.
+    InvalidReferenceSequenceName(reference_sequence_name::ParseError),
+    /// The position is missing.
+    MissingPosition,
+    /// The strand is invalid.
+    InvalidStrand(strand::ParseError),
+    /// The position is invalid.
+    InvalidPosition(position::ParseError),
+    /// The CIGAR is missing.
+    MissingCigar,
+    /// The CIGAR is invalid.
+    InvalidCigar(cigar::ParseError),
+    /// The edit distance is missing.
+    MissingEditDistance,
+    /// The edit distance is invalid.
+    InvalidEditDistance(num::ParseIntError),
+    /// The input has an unexpected field after the edit distance.
+    UnexpectedField,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingReferenceSequenceName => f.write_str("missing reference sequence name"),
+            Self::InvalidReferenceSequenceName(e) => {
+                write!(f, "invalid reference sequence name: {}", e)
+            }
+            Self::MissingPosition => f.write_str("missing position"),
+            Self::InvalidStrand(e) => write!(f, "invalid strand: {}", e),
+            Self::InvalidPosition(e) => write!(f, "invalid position: {}", e),
+            Self::MissingCigar => f.write_str("missing CIGAR"),
+            Self::InvalidCigar(e) => write!(f, "invalid CIGAR: {}", e),
+            Self::MissingEditDistance => f.write_str("missing edit distance"),
+            Self::InvalidEditDistance(e) => write!(f, "invalid edit distance: {}", e),
+            Self::UnexpectedField => f.write_str("unexpected field"),
+        }
+    }
+}
+
+impl FromStr for Hit {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split(DELIMITER);
+
+        let reference_sequence_name = fields
+            .next()
+            .ok_or(ParseError::MissingReferenceSequenceName)
+            .and_then(|t| t.parse().map_err(ParseError::InvalidReferenceSequenceName))?;
+
+        let (strand, position) = fields
+            .next()
+            .ok_or(ParseError::MissingPosition)
+            .and_then(parse_strand_and_position)?;
+
+        let cigar = fields
+            .next()
+            .ok_or(ParseError::MissingCigar)
+            .and_then(|t| t.parse().map_err(ParseError::InvalidCigar))?;
+
+        let edit_distance = fields
+            .next()
+            .ok_or(ParseError::MissingEditDistance)
+            .and_then(|t| t.parse().map_err(ParseError::InvalidEditDistance))?;
+
+        if fields.next().is_some() {
+            return Err(ParseError::UnexpectedField);
+        }
+
+        Ok(Self::new(
+            reference_sequence_name,
+            strand,
+            position,
+            cigar,
+            edit_distance,
+        ))
+    }
+}
+
+fn parse_strand_and_position(s: &str) -> Result<(Strand, Position), ParseError> {
+    let i = s.char_indices().nth(1).map(|(i, _)| i).unwrap_or(s.len());
+    let (raw_strand, raw_position) = s.split_at(i);
+
+    let strand = raw_strand.parse().map_err(ParseError::InvalidStrand)?;
+    let position = raw_position.parse().map_err(ParseError::InvalidPosition)?;
+
+    Ok((strand, position))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::record::cigar::{op::Kind, Op};
+
+    use super::*;
+
+    fn build_hit() -> Result<Hit, Box<dyn std::error::Error>> {
+        Ok(Hit::new(
+            "sq0".parse()?,
+            Strand::Reverse,
+            Position::try_from(8)?,
+            Cigar::from(vec![Op::new(Kind::SoftClip, 2), Op::new(Kind::Match, 4)]),
+            1,
+        ))
+    }
+
+    #[test]
+    fn test_fmt() -> Result<(), Box<dyn std::error::Error>> {
+        let hit = build_hit()?;
+        assert_eq!(hit.to_string(), "sq0,-8,2S4M,1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!("sq0,-8,2S4M,1".parse(), Ok(build_hit()?));
+
+        assert_eq!("sq0".parse::<Hit>(), Err(ParseError::MissingPosition));
+        assert_eq!(
+            "sq0,,2S4M,1".parse::<Hit>(),
+            Err(ParseError::InvalidStrand(strand::ParseError::Empty))
+        );
+        assert_eq!(
+            "sq0,8,2S4M,1".parse::<Hit>(),
+            Err(ParseError::InvalidStrand(strand::ParseError::Invalid))
+        );
+        assert!(matches!(
+            "sq0,-,2S4M,1".parse::<Hit>(),
+            Err(ParseError::InvalidPosition(_))
+        ));
+        assert!(matches!(
+            "sq0,-0,2S4M,1".parse::<Hit>(),
+            Err(ParseError::InvalidPosition(_))
+        ));
+        assert_eq!(
+            "sq0,-8,2S4M".parse::<Hit>(),
+            Err(ParseError::MissingEditDistance)
+        );
+        assert_eq!(
+            "sq0,-8,2S4M,1,junk".parse::<Hit>(),
+            Err(ParseError::UnexpectedField)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+//! SAM record data other alignments (`SA`) and components.
+
+pub mod alignment;
+
+pub use self::alignment::Alignment;
+
+use std::{error, fmt, ops::Deref, str::FromStr};
+
+use super::field::Value;
+
+const TERMINATOR: char = ';';
+
+/// SAM record data other alignments (`SA`).
+///
+/// This is the list of other parts of a chimeric alignment. Each alignment is terminated by a
+/// semicolon, e.g., `sq0,8,+,4M2S,13,0;sq1,21,-,2S4M,13,1;`.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::record::data::{
+///     field::{Tag, Value},
+///     Field, OtherAlignments,
+/// };
+///
+/// let other_alignments: OtherAlignments = "sq0,8,+,4M2S,13,0;".parse()?;
+/// assert_eq!(other_alignments.len(), 1);
+///
+/// let field = Field::new(Tag::OtherAlignments, Value::from(other_alignments));
+/// assert_eq!(field.to_string(), "SA:Z:sq0,8,+,4M2S,13,0;");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OtherAlignments(Vec<Alignment>);
+
+impl Deref for OtherAlignments {
+    type Target = [Alignment];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for OtherAlignments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for alignment in self.iter() {
+            write!(f, "{}{}", alignment, TERMINATOR)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Vec<Alignment>> for OtherAlignments {
+    fn from(alignments: Vec<Alignment>) -> Self {
+        Self(alignments)
+    }
+}
+
+impl From<OtherAlignments> for Value {
+    fn from(other_alignments: OtherAlignments) -> Self {
+        Self::String(other_alignments.to_string())
+    }
+}
+
+/// An error returned when raw SAM record data other alignments (`SA`) fail to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// An alignment is invalid.
+    InvalidAlignment(alignment::ParseError),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::InvalidAlignment(e) => write!(f, "invalid alignment: {}", e),
+        }
+    }
+}
+
+impl FromStr for OtherAlignments {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        // The final terminator is optional.
+        s.strip_suffix(TERMINATOR)
+            .unwrap_or(s)
+            .split(TERMINATOR)
+            .map(|t| t.parse().map_err(ParseError::InvalidAlignment))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::record::{MappingQuality, Position};
+
+    use super::{super::Strand, *};
+
+    fn build_other_alignments() -> Result<OtherAlignments, Box<dyn std::error::Error>> {
+        Ok(OtherAlignments::from(vec![
+            Alignment::new(
+                "sq0".parse()?,
+                Position::try_from(8)?,
+                Strand::Forward,
+                "4M2S".parse()?,
+                MappingQuality::from(13),
+                0,
+            ),
+            Alignment::new(
+                "sq1".parse()?,
+                Position::try_from(21)?,
+                Strand::Reverse,
+                "2S4M".parse()?,
+                MappingQuality::from(255),
+                1,
+            ),
+        ]))
+    }
+
+    #[test]
+    fn test_fmt() -> Result<(), Box<dyn std::error::Error>> {
+        let other_alignments = build_other_alignments()?;
+
+        assert_eq!(
+            other_alignments.to_string(),
+            "sq0,8,+,4M2S,13,0;sq1,21,-,2S4M,255,1;"
+        );
+
+        assert!(OtherAlignments::default().to_string().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> Result<(), Box<dyn std::error::Error>> {
+        let expected = build_other_alignments()?;
+
+        assert_eq!(
+            "sq0,8,+,4M2S,13,0;sq1,21,-,2S4M,255,1;".parse(),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            "sq0,8,+,4M2S,13,0;sq1,21,-,2S4M,255,1".parse(),
+            Ok(expected)
+        );
+
+        assert_eq!("".parse::<OtherAlignments>(), Err(ParseError::Empty));
+        assert_eq!(
+            "sq0,8,+,4M2S,13,0;;".parse::<OtherAlignments>(),
+            Err(ParseError::InvalidAlignment(
+                alignment::ParseError::InvalidReferenceSequenceName(
+                    crate::record::reference_sequence_name::ParseError::Empty
+                )
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_other_alignments_for_value() -> Result<(), Box<dyn std::error::Error>> {
+        let other_alignments: OtherAlignments = "sq0,8,+,4M2S,13,0;".parse()?;
+
+        assert_eq!(
+            Value::from(other_alignments),
+            Value::String(String::from("sq0,8,+,4M2S,13,0;"))
+        );
+
+        Ok(())
+    }
+}
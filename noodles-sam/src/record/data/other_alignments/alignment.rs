@@ -0,0 +1,346 @@
+//! SAM record data other alignments (`SA`) alignment.
+
+use std::{error, fmt, num, str::FromStr};
+
+use crate::record::{
+    cigar, position, reference_sequence_name, Cigar, MappingQuality, Position,
+    ReferenceSequenceName,
+};
+
+use super::super::{strand, Strand};
+
+const DELIMITER: char = ',';
+
+/// A SAM record data other alignments (`SA`) alignment.
+///
+/// This is a single entry in the list of other parts of a chimeric alignment, formatted as
+/// `rname,pos,strand,CIGAR,mapQ,NM`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alignment {
+    reference_sequence_name: ReferenceSequenceName,
+    position: Position,
+    strand: Strand,
+    cigar: Cigar,
+    mapping_quality: MappingQuality,
+    edit_distance: u32,
+}
+
+impl Alignment {
+    /// Creates an other alignments (`SA`) alignment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// use noodles_sam::record::{
+    ///     data::{other_alignments::Alignment, Strand},
+    ///     MappingQuality, Position,
+    /// };
+    ///
+    /// let alignment = Alignment::new(
+    ///     "sq0".parse()?,
+    ///     Position::try_from(8)?,
+    ///     Strand::Forward,
+    ///     "4M".parse()?,
+    ///     MappingQuality::from(13),
+    ///     0,
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(
+        reference_sequence_name: ReferenceSequenceName,
+        position: Position,
+        strand: Strand,
+        cigar: Cigar,
+        mapping_quality: MappingQuality,
+        edit_distance: u32,
+    ) -> Self {
+        Self {
+            reference_sequence_name,
+            position,
+            strand,
+            cigar,
+            mapping_quality,
+            edit_distance,
+        }
+    }
+
+    /// Returns the reference sequence name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::other_alignments::Alignment;
+    /// let alignment: Alignment = "sq0,8,+,4M,13,0".parse()?;
+    /// assert_eq!(alignment.reference_sequence_name().as_str(), "sq0");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reference_sequence_name(&self) -> &ReferenceSequenceName {
+        &self.reference_sequence_name
+    }
+
+    /// Returns the start position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::other_alignments::Alignment;
+    /// let alignment: Alignment = "sq0,8,+,4M,13,0".parse()?;
+    /// assert_eq!(i32::from(alignment.position()), 8);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Returns the strand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{other_alignments::Alignment, Strand};
+    /// let alignment: Alignment = "sq0,8,+,4M,13,0".parse()?;
+    /// assert_eq!(alignment.strand(), Strand::Forward);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn strand(&self) -> Strand {
+        self.strand
+    }
+
+    /// Returns the CIGAR operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::other_alignments::Alignment;
+    /// let alignment: Alignment = "sq0,8,+,4M,13,0".parse()?;
+    /// assert_eq!(alignment.cigar().to_string(), "4M");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn cigar(&self) -> &Cigar {
+        &self.cigar
+    }
+
+    /// Returns the mapping quality.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::other_alignments::Alignment;
+    /// let alignment: Alignment = "sq0,8,+,4M,13,0".parse()?;
+    /// assert_eq!(*alignment.mapping_quality(), Some(13));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn mapping_quality(&self) -> MappingQuality {
+        self.mapping_quality
+    }
+
+    /// Returns the edit distance (`NM`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::other_alignments::Alignment;
+    /// let alignment: Alignment = "sq0,8,+,4M,13,0".parse()?;
+    /// assert_eq!(alignment.edit_distance(), 0);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn edit_distance(&self) -> u32 {
+        self.edit_distance
+    }
+}
+
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{rname}{d}{pos}{d}{strand}{d}{cigar}{d}{mapq}{d}{nm}",
+            rname = self.reference_sequence_name,
+            pos = i32::from(self.position),
+            strand = self.strand,
+            cigar = self.cigar,
+            mapq = u8::from(self.mapping_quality),
+            nm = self.edit_distance,
+            d = DELIMITER,
+        )
+    }
+}
+
+/// An error returned when a raw SAM record data other alignments (`SA`) alignment fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The reference sequence name is missing.
+    MissingReferenceSequenceName,
+    /// The reference sequence name is invalid.
+    InvalidReferenceSequenceName(reference_sequence_name::ParseError),
+    /// The position is missing.
+    MissingPosition,
+    /// The position is invalid.
+    InvalidPosition(position::ParseError),
+    /// The strand is missing.
+    MissingStrand,
+    /// The strand is invalid.
+    InvalidStrand(strand::ParseError),
+    /// The CIGAR is missing.
+    MissingCigar,
+    /// The CIGAR is invalid.
+    InvalidCigar(cigar::ParseError),
+    /// The mapping quality is missing.
+    MissingMappingQuality,
+    /// The mapping quality is invalid.
+    InvalidMappingQuality(num::ParseIntError),
+    /// The edit distance is missing.
+    MissingEditDistance,
+    /// The edit distance is invalid.
+    InvalidEditDistance(num::ParseIntError),
+    /// The input has an unexpected field after the edit distance.
+    UnexpectedField,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingReferenceSequenceName => f.write_str("missing reference sequence name"),
+            Self::InvalidReferenceSequenceName(e) => {
+                write!(f, "invalid reference sequence name: {}", e)
+            }
+            Self::MissingPosition => f.write_str("missing position"),
+            Self::InvalidPosition(e) => write!(f, "invalid position: {}", e),
+            Self::MissingStrand => f.write_str("missing strand"),
+            Self::InvalidStrand(e) => write!(f, "invalid strand: {}", e),
+            Self::MissingCigar => f.write_str("missing CIGAR"),
+            Self::InvalidCigar(e) => write!(f, "invalid CIGAR: {}", e),
+            Self::MissingMappingQuality => f.write_str("missing mapping quality"),
+            Self::InvalidMappingQuality(e) => write!(f, "invalid mapping quality: {}", e),
+            Self::MissingEditDistance => f.write_str("missing edit distance"),
+            Self::InvalidEditDistance(e) => write!(f, "invalid edit distance: {}", e),
+            Self::UnexpectedField => f.write_str("unexpected field"),
+        }
+    }
+}
+
+impl FromStr for Alignment {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split(DELIMITER);
+
+        let reference_sequence_name = fields
+            .next()
+            .ok_or(ParseError::MissingReferenceSequenceName)
+            .and_then(|t| t.parse().map_err(ParseError::InvalidReferenceSequenceName))?;
+
+        let position = fields
+            .next()
+            .ok_or(ParseError::MissingPosition)
+            .and_then(|t| t.parse().map_err(ParseError::InvalidPosition))?;
+
+        let strand = fields
+            .next()
+            .ok_or(ParseError::MissingStrand)
+            .and_then(|t| t.parse().map_err(ParseError::InvalidStrand))?;
+
+        let cigar = fields
+            .next()
+            .ok_or(ParseError::MissingCigar)
+            .and_then(|t| t.parse().map_err(ParseError::InvalidCigar))?;
+
+        let mapping_quality = fields
+            .next()
+            .ok_or(ParseError::MissingMappingQuality)
+            .and_then(|t| {
+                t.parse::<u8>()
+                    .map(MappingQuality::from)
+                    .map_err(ParseError::InvalidMappingQuality)
+            })?;
+
+        let edit_distance = fields
+            .next()
+            .ok_or(ParseError::MissingEditDistance)
+            .and_then(|t| t.parse().map_err(ParseError::InvalidEditDistance))?;
+
+        if fields.next().is_some() {
+            return Err(ParseError::UnexpectedField);
+        }
+
+        Ok(Self::new(
+            reference_sequence_name,
+            position,
+            strand,
+            cigar,
+            mapping_quality,
+            edit_distance,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::record::cigar::{op::Kind, Op};
+
+    use super::*;
+
+    fn build_alignment() -> Result<Alignment, Box<dyn std::error::Error>> {
+        Ok(Alignment::new(
+            "sq0".parse()?,
+            Position::try_from(8)?,
+            Strand::Reverse,
+            Cigar::from(vec![Op::new(Kind::SoftClip, 2), Op::new(Kind::Match, 4)]),
+            MappingQuality::from(13),
+            1,
+        ))
+    }
+
+    #[test]
+    fn test_fmt() -> Result<(), Box<dyn std::error::Error>> {
+        let alignment = build_alignment()?;
+        assert_eq!(alignment.to_string(), "sq0,8,-,2S4M,13,1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!("sq0,8,-,2S4M,13,1".parse(), Ok(build_alignment()?));
+
+        assert_eq!(
+            "sq0,8,-,2S4M,255,1"
+                .parse::<Alignment>()
+                .map(|alignment| *alignment.mapping_quality()),
+            Ok(None)
+        );
+
+        assert_eq!(
+            "".parse::<Alignment>(),
+            Err(ParseError::InvalidReferenceSequenceName(
+                reference_sequence_name::ParseError::Empty
+            ))
+        );
+        assert_eq!("sq0".parse::<Alignment>(), Err(ParseError::MissingPosition));
+        assert!(matches!(
+            "sq0,0,-,2S4M,13,1".parse::<Alignment>(),
+            Err(ParseError::InvalidPosition(_))
+        ));
+        assert_eq!(
+            "sq0,8,.,2S4M,13,1".parse::<Alignment>(),
+            Err(ParseError::InvalidStrand(strand::ParseError::Invalid))
+        );
+        assert_eq!(
+            "sq0,8,-,2S4M,13".parse::<Alignment>(),
+            Err(ParseError::MissingEditDistance)
+        );
+        assert!(matches!(
+            "sq0,8,-,2S4M,256,1".parse::<Alignment>(),
+            Err(ParseError::InvalidMappingQuality(_))
+        ));
+        assert_eq!(
+            "sq0,8,+,4M,13,0,junk".parse::<Alignment>(),
+            Err(ParseError::UnexpectedField)
+        );
+
+        Ok(())
+    }
+}
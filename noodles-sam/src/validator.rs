@@ -0,0 +1,281 @@
+//! SAM record validation.
+
+mod error;
+
+pub use self::error::ValidationError;
+
+use std::collections::HashSet;
+
+use super::{
+    header::ReferenceSequences,
+    record::{
+        data::field::{Tag, Value},
+        Flags,
+    },
+    Header, Record,
+};
+
+/// A SAM record validator.
+///
+/// A validator checks records against the SAM specification and a SAM header. A record is valid
+/// when
+///
+///   * its reference sequence name and mate reference sequence name, if set, are in the header
+///     reference sequences;
+///   * its position and mate position, if set, are within their reference sequence lengths
+///     (`@SQ LN`);
+///   * its CIGAR read length, if the CIGAR and sequence are set, is equal to the sequence length;
+///   * its number of quality scores, if set, is equal to the sequence length;
+///   * its flags do not set mate or segment flags on an unpaired read; and
+///   * its read group (`RG`) data field, if set, references a header read group.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::{self as sam, validator::Validator};
+///
+/// let header = sam::Header::default();
+/// let validator = Validator::new(&header);
+///
+/// let record = sam::Record::default();
+/// assert!(validator.validate(&record).is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Validator {
+    reference_sequences: ReferenceSequences,
+    read_group_ids: HashSet<String>,
+}
+
+impl Validator {
+    /// Creates a validator for records described by the given header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, validator::Validator};
+    /// let header = sam::Header::default();
+    /// let validator = Validator::new(&header);
+    /// ```
+    pub fn new(header: &Header) -> Self {
+        Self {
+            reference_sequences: header.reference_sequences().clone(),
+            read_group_ids: header.read_groups().keys().cloned().collect(),
+        }
+    }
+
+    /// Validates a record and returns all of its errors.
+    ///
+    /// The returned list is empty if the record is valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{
+    ///     self as sam,
+    ///     validator::{ValidationError, Validator},
+    /// };
+    ///
+    /// let header = sam::Header::default();
+    /// let validator = Validator::new(&header);
+    ///
+    /// let record = sam::Record::builder()
+    ///     .set_reference_sequence_name("sq0".parse()?)
+    ///     .build();
+    ///
+    /// assert_eq!(
+    ///     validator.validate(&record),
+    ///     [ValidationError::InvalidReferenceSequenceName(String::from("sq0"))]
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn validate(&self, record: &Record) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        if let Some(name) = record.reference_sequence_name() {
+            match self.reference_sequences.get(name.as_str()) {
+                Some(reference_sequence) => {
+                    if let Some(position) = record.position().map(i32::from) {
+                        if position > reference_sequence.len() {
+                            errors.push(ValidationError::PositionOutOfRange {
+                                position,
+                                reference_sequence_len: reference_sequence.len(),
+                            });
+                        }
+                    }
+                }
+                None => errors.push(ValidationError::InvalidReferenceSequenceName(
+                    name.to_string(),
+                )),
+            }
+        }
+
+        if let Some(name) = record.mate_reference_sequence_name() {
+            match self.reference_sequences.get(name.as_str()) {
+                Some(reference_sequence) => {
+                    if let Some(mate_position) = record.mate_position().map(i32::from) {
+                        if mate_position > reference_sequence.len() {
+                            errors.push(ValidationError::MatePositionOutOfRange {
+                                mate_position,
+                                reference_sequence_len: reference_sequence.len(),
+                            });
+                        }
+                    }
+                }
+                None => errors.push(ValidationError::InvalidMateReferenceSequenceName(
+                    name.to_string(),
+                )),
+            }
+        }
+
+        let sequence_len = record.sequence().len() as u32;
+
+        if !record.cigar().is_empty() && sequence_len > 0 {
+            let cigar_read_len = record.cigar().read_len();
+
+            if cigar_read_len != sequence_len {
+                errors.push(ValidationError::CigarReadLengthMismatch {
+                    cigar_read_len,
+                    sequence_len,
+                });
+            }
+        }
+
+        let quality_scores_len = record.quality_scores().len() as u32;
+
+        if quality_scores_len > 0 && quality_scores_len != sequence_len {
+            errors.push(ValidationError::QualityScoresLengthMismatch {
+                quality_scores_len,
+                sequence_len,
+            });
+        }
+
+        let flags = record.flags();
+
+        if !is_valid_flags(flags) {
+            errors.push(ValidationError::InvalidFlags(flags));
+        }
+
+        let read_group = record
+            .data()
+            .iter()
+            .find(|field| field.tag() == &Tag::ReadGroup);
+
+        if let Some(field) = read_group {
+            match field.value() {
+                Value::String(id) if self.read_group_ids.contains(id) => {}
+                Value::String(id) => errors.push(ValidationError::InvalidReadGroup(id.clone())),
+                value => errors.push(ValidationError::InvalidReadGroup(value.to_string())),
+            }
+        }
+
+        errors
+    }
+}
+
+/// Returns whether the flags are consistent.
+///
+/// The flags are inconsistent if an unpaired read has any of the proper pair, mate unmapped, mate
+/// reverse complemented, first segment, or last segment flags set.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::{record::Flags, validator::is_valid_flags};
+/// assert!(is_valid_flags(Flags::PAIRED | Flags::MATE_UNMAPPED));
+/// assert!(!is_valid_flags(Flags::MATE_UNMAPPED));
+/// ```
+pub fn is_valid_flags(flags: Flags) -> bool {
+    let paired_flags = Flags::PROPER_PAIR
+        | Flags::MATE_UNMAPPED
+        | Flags::MATE_REVERSE_COMPLEMENTED
+        | Flags::READ_1
+        | Flags::READ_2;
+
+    flags.is_paired() || !flags.intersects(paired_flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        header::{ReadGroup, ReferenceSequence},
+        record::{
+            data::{self, Field},
+            Data,
+        },
+    };
+
+    use super::*;
+
+    fn build_header() -> Header {
+        Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .add_read_group(ReadGroup::new(String::from("rg0")))
+            .build()
+    }
+
+    #[test]
+    fn test_validate() -> Result<(), Box<dyn std::error::Error>> {
+        let validator = Validator::new(&build_header());
+
+        let record = Record::builder()
+            .set_flags(Flags::PAIRED | Flags::READ_1)
+            .set_reference_sequence_name("sq0".parse()?)
+            .set_position("8".parse()?)
+            .set_cigar("2M".parse()?)
+            .set_mate_reference_sequence_name("sq0".parse()?)
+            .set_mate_position("1".parse()?)
+            .set_sequence("AC".parse()?)
+            .set_quality_scores("ND".parse()?)
+            .set_data(Data::from(vec![Field::new(
+                data::field::Tag::ReadGroup,
+                data::field::Value::String(String::from("rg0")),
+            )]))
+            .build();
+
+        assert!(validator.validate(&record).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_invalid_record() -> Result<(), Box<dyn std::error::Error>> {
+        let validator = Validator::new(&build_header());
+
+        let record = Record::builder()
+            .set_flags(Flags::MATE_REVERSE_COMPLEMENTED)
+            .set_reference_sequence_name("sq0".parse()?)
+            .set_position("9".parse()?)
+            .set_cigar("3M".parse()?)
+            .set_mate_reference_sequence_name("sq1".parse()?)
+            .set_sequence("AC".parse()?)
+            .set_quality_scores("NDL".parse()?)
+            .set_data(Data::from(vec![Field::new(
+                data::field::Tag::ReadGroup,
+                data::field::Value::String(String::from("rg1")),
+            )]))
+            .build();
+
+        assert_eq!(
+            validator.validate(&record),
+            [
+                ValidationError::PositionOutOfRange {
+                    position: 9,
+                    reference_sequence_len: 8
+                },
+                ValidationError::InvalidMateReferenceSequenceName(String::from("sq1")),
+                ValidationError::CigarReadLengthMismatch {
+                    cigar_read_len: 3,
+                    sequence_len: 2
+                },
+                ValidationError::QualityScoresLengthMismatch {
+                    quality_scores_len: 3,
+                    sequence_len: 2
+                },
+                ValidationError::InvalidFlags(Flags::MATE_REVERSE_COMPLEMENTED),
+                ValidationError::InvalidReadGroup(String::from("rg1")),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use std::{error, fmt};
+
+use crate::record::Flags;
+
+/// An error describing an invalid SAM record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+    /// The reference sequence name is not in the header reference sequences.
+    InvalidReferenceSequenceName(String),
+    /// The mate reference sequence name is not in the header reference sequences.
+    InvalidMateReferenceSequenceName(String),
+    /// The position is past the end of the reference sequence.
+    PositionOutOfRange {
+        /// The 1-based position.
+        position: i32,
+        /// The length of the reference sequence.
+        reference_sequence_len: i32,
+    },
+    /// The mate position is past the end of the mate reference sequence.
+    MatePositionOutOfRange {
+        /// The 1-based mate position.
+        mate_position: i32,
+        /// The length of the mate reference sequence.
+        reference_sequence_len: i32,
+    },
+    /// The read length calculated from the CIGAR does not match the sequence length.
+    CigarReadLengthMismatch {
+        /// The read length calculated from the CIGAR.
+        cigar_read_len: u32,
+        /// The sequence length.
+        sequence_len: u32,
+    },
+    /// The number of quality scores does not match the sequence length.
+    QualityScoresLengthMismatch {
+        /// The number of quality scores.
+        quality_scores_len: u32,
+        /// The sequence length.
+        sequence_len: u32,
+    },
+    /// The flags are inconsistent, e.g., mate flags are set on an unpaired read.
+    InvalidFlags(Flags),
+    /// The read group (`RG`) data field does not reference a header read group.
+    InvalidReadGroup(String),
+}
+
+impl error::Error for ValidationError {}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidReferenceSequenceName(name) => {
+                write!(f, "invalid reference sequence name: {}", name)
+            }
+            Self::InvalidMateReferenceSequenceName(name) => {
+                write!(f, "invalid mate reference sequence name: {}", name)
+            }
+            Self::PositionOutOfRange {
+                position,
+                reference_sequence_len,
+            } => write!(
+                f,
+                "position out of range: expected <= {}, got {}",
+                reference_sequence_len, position
+            ),
+            Self::MatePositionOutOfRange {
+                mate_position,
+                reference_sequence_len,
+            } => write!(
+                f,
+                "mate position out of range: expected <= {}, got {}",
+                reference_sequence_len, mate_position
+            ),
+            Self::CigarReadLengthMismatch {
+                cigar_read_len,
+                sequence_len,
+            } => write!(
+                f,
+                "CIGAR read length mismatch: expected {}, got {}",
+                sequence_len, cigar_read_len
+            ),
+            Self::QualityScoresLengthMismatch {
+                quality_scores_len,
+                sequence_len,
+            } => write!(
+                f,
+                "quality scores length mismatch: expected {}, got {}",
+                sequence_len, quality_scores_len
+            ),
+            Self::InvalidFlags(flags) => write!(f, "invalid flags: {:?}", flags),
+            Self::InvalidReadGroup(id) => write!(f, "invalid read group: {}", id),
+        }
+    }
+}
@@ -1,6 +1,12 @@
+//! SAM writer.
+
+mod builder;
+
+pub use self::builder::Builder;
+
 use std::io::{self, Write};
 
-use super::{record, Header, Record};
+use super::{record, validator::Validator, Header, Record};
 
 /// A SAM writer.
 ///
@@ -37,6 +43,7 @@ where
     W: Write,
 {
     inner: W,
+    validator: Option<Validator>,
 }
 
 impl<W> Writer<W>
@@ -52,7 +59,19 @@ where
     /// let writer = sam::Writer::new(Vec::new());
     /// ```
     pub fn new(inner: W) -> Self {
-        Self { inner }
+        Builder::new(inner).build()
+    }
+
+    /// Creates a SAM writer builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam as sam;
+    /// let writer = sam::Writer::builder(Vec::new()).set_strict(true).build();
+    /// ```
+    pub fn builder(inner: W) -> Builder<W> {
+        Builder::new(inner)
     }
 
     /// Returns a reference to the underlying writer.
@@ -85,11 +104,17 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn write_header(&mut self, header: &Header) -> io::Result<()> {
+        if let Some(validator) = self.validator.as_mut() {
+            *validator = Validator::new(header);
+        }
+
         write!(self.inner, "{}", header)
     }
 
     /// Writes a SAM record.
     ///
+    /// In strict mode, an error is returned if the record is invalid.
+    ///
     /// # Examples
     ///
     /// ```
@@ -102,6 +127,12 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        if let Some(validator) = self.validator.as_ref() {
+            if let Some(e) = validator.validate(record).into_iter().next() {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, e));
+            }
+        }
+
         let qname = record
             .read_name()
             .map(|name| name.as_str())
@@ -182,4 +213,36 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_write_record_with_strict_mode() -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = Writer::builder(Vec::new()).set_strict(true).build();
+
+        let header = Header::builder()
+            .add_reference_sequence(crate::header::ReferenceSequence::new(
+                String::from("sq0"),
+                8,
+            ))
+            .build();
+
+        writer.write_header(&header)?;
+
+        let record = Record::builder()
+            .set_reference_sequence_name("sq0".parse()?)
+            .set_position("8".parse()?)
+            .build();
+
+        writer.write_record(&record)?;
+
+        let record = Record::builder()
+            .set_reference_sequence_name("sq1".parse()?)
+            .build();
+
+        assert!(matches!(
+            writer.write_record(&record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,62 @@
+use std::io::Write;
+
+use super::Writer;
+use crate::validator::Validator;
+
+/// A SAM writer builder.
+pub struct Builder<W> {
+    inner: W,
+    strict: bool,
+}
+
+impl<W> Builder<W>
+where
+    W: Write,
+{
+    pub(crate) fn new(inner: W) -> Self {
+        Self {
+            inner,
+            strict: false,
+        }
+    }
+
+    /// Sets whether to validate records before writing them.
+    ///
+    /// When enabled, each record is checked by a [`Validator`] for the header passed to
+    /// [`Writer::write_header`], and an error is returned for an invalid record. If no header is
+    /// written, records are validated against an empty header.
+    ///
+    /// By default, records are not validated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam as sam;
+    /// let writer = sam::Writer::builder(Vec::new()).set_strict(true).build();
+    /// ```
+    pub fn set_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Builds a SAM writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam as sam;
+    /// let writer = sam::Writer::builder(Vec::new()).build();
+    /// ```
+    pub fn build(self) -> Writer<W> {
+        let validator = if self.strict {
+            Some(Validator::default())
+        } else {
+            None
+        };
+
+        Writer {
+            inner: self.inner,
+            validator,
+        }
+    }
+}
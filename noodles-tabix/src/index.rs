@@ -9,6 +9,10 @@ pub use self::{
     builder::Builder, header::Header, indexer::Indexer, reference_sequence::ReferenceSequence,
 };
 
+use std::io;
+
+use noodles_csi::{self as csi, BinningIndex};
+
 /// A tabix index.
 #[derive(Debug)]
 pub struct Index {
@@ -109,3 +113,79 @@ impl Default for Index {
         Builder::default().build()
     }
 }
+
+impl BinningIndex for Index {
+    /// Returns the chunks that overlap with the given region.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_csi::BinningIndex;
+    /// use noodles_tabix::{self as tabix, index::ReferenceSequence};
+    ///
+    /// let index = tabix::Index::builder()
+    ///     .set_reference_sequence_names(vec![String::from("sq0")])
+    ///     .set_reference_sequences(vec![ReferenceSequence::new(Vec::new(), Vec::new())])
+    ///     .build();
+    ///
+    /// let chunks = index.query(0, 8, 13)?;
+    /// assert!(chunks.is_empty());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    fn query(
+        &self,
+        reference_sequence_id: usize,
+        start: i32,
+        end: i32,
+    ) -> io::Result<Vec<csi::index::reference_sequence::bin::Chunk>> {
+        let reference_sequence = self
+            .reference_sequences()
+            .get(reference_sequence_id)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "invalid reference sequence ID: {} >= {}",
+                        reference_sequence_id,
+                        self.reference_sequences().len()
+                    ),
+                )
+            })?;
+
+        let query_bins = reference_sequence.query(start, end);
+
+        let chunks: Vec<_> = query_bins
+            .iter()
+            .flat_map(|bin| bin.chunks())
+            .map(|chunk| {
+                csi::index::reference_sequence::bin::Chunk::new(chunk.start(), chunk.end())
+            })
+            .collect();
+
+        let min_offset = reference_sequence.min_offset(start);
+
+        Ok(csi::optimize_chunks(&chunks, min_offset))
+    }
+
+    /// Returns the index of the reference sequence with the given name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_csi::BinningIndex;
+    /// use noodles_tabix as tabix;
+    ///
+    /// let index = tabix::Index::builder()
+    ///     .set_reference_sequence_names(vec![String::from("sq0"), String::from("sq1")])
+    ///     .build();
+    ///
+    /// assert_eq!(index.reference_sequence_id("sq1"), Some(1));
+    /// assert!(index.reference_sequence_id("sq2").is_none());
+    /// ```
+    fn reference_sequence_id(&self, name: &str) -> Option<usize> {
+        self.reference_sequence_names()
+            .iter()
+            .position(|n| n == name)
+    }
+}
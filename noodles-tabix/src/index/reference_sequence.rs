@@ -7,6 +7,8 @@ pub use self::bin::Bin;
 
 pub(crate) use self::builder::Builder;
 
+use std::ops::RangeInclusive;
+
 use noodles_bgzf as bgzf;
 
 const WINDOW_SIZE: i32 = 16384;
@@ -60,4 +62,80 @@ impl ReferenceSequence {
     pub fn intervals(&self) -> &[bgzf::VirtualPosition] {
         &self.intervals
     }
+
+    /// Returns the bins in this reference sequence that intersect the given range.
+    ///
+    /// `start` and `end` are 1-based, inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_tabix::index::{reference_sequence::Bin, ReferenceSequence};
+    ///
+    /// let bins = vec![Bin::new(0, Vec::new()), Bin::new(4681, Vec::new()), Bin::new(4682, Vec::new())];
+    /// let reference_sequence = ReferenceSequence::new(bins, Vec::new());
+    ///
+    /// let query_bins = reference_sequence.query(8, 13);
+    /// let ids: Vec<_> = query_bins.iter().map(|bin| bin.id()).collect();
+    /// assert_eq!(ids, [0, 4681]);
+    /// ```
+    pub fn query(&self, start: i32, end: i32) -> Vec<&Bin> {
+        let region_bins = region_to_bins((start - 1) as u32, end as u32);
+
+        self.bins()
+            .iter()
+            .filter(|b| region_bins.iter().any(|ids| ids.contains(&b.id())))
+            .collect()
+    }
+
+    /// Finds the minimum start offset in the linear index for a given start position.
+    ///
+    /// `start` is 1-based.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_tabix::index::ReferenceSequence;
+    /// let reference_sequence = ReferenceSequence::new(Vec::new(), Vec::new());
+    /// assert_eq!(reference_sequence.min_offset(13), bgzf::VirtualPosition::from(0));
+    /// ```
+    pub fn min_offset(&self, start: i32) -> bgzf::VirtualPosition {
+        let i = ((start - 1) / WINDOW_SIZE) as usize;
+        self.intervals.get(i).copied().unwrap_or_default()
+    }
+}
+
+// 0-based, [start, end)
+fn region_to_bins(start: u32, end: u32) -> [RangeInclusive<u32>; 6] {
+    let end = end - 1;
+
+    [
+        0..=0,
+        (1 + (start >> 26))..=(1 + (end >> 26)),
+        (9 + (start >> 23))..=(9 + (end >> 23)),
+        (73 + (start >> 20))..=(73 + (end >> 20)),
+        (585 + (start >> 17))..=(585 + (end >> 17)),
+        (4681 + (start >> 14))..=(4681 + (end >> 14)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_to_bins() {
+        // [8, 13]
+        assert_eq!(
+            region_to_bins(7, 13),
+            [0..=0, 1..=1, 9..=9, 73..=73, 585..=585, 4681..=4681]
+        );
+
+        // [63245986, 63255986]
+        assert_eq!(
+            region_to_bins(63245985, 63255986),
+            [0..=0, 1..=1, 16..=16, 133..=133, 1067..=1067, 8541..=8541]
+        );
+    }
 }
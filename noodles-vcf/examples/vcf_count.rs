@@ -1,6 +1,8 @@
 //! Counts the number of records in a VCF file.
 //!
 //! The result matches the output of `bcftools view --no-header <src> | wc -l`.
+//!
+//! The input can be uncompressed, gzip-compressed, or BGZF-compressed.
 
 use std::{
     env,
@@ -8,12 +10,16 @@ use std::{
     io::{self, BufReader},
 };
 
+use noodles_bgzf as bgzf;
 use noodles_vcf as vcf;
 
 fn main() -> io::Result<()> {
     let src = env::args().nth(1).expect("missing src");
 
-    let mut reader = File::open(src).map(BufReader::new).map(vcf::Reader::new)?;
+    let mut reader = File::open(src)
+        .map(BufReader::new)
+        .and_then(bgzf::AutoReader::new)
+        .map(vcf::Reader::new)?;
     reader.read_header()?;
 
     let mut n = 0;
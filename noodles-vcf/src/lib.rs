@@ -21,7 +21,7 @@
 //! ```
 
 pub mod header;
-mod reader;
+pub mod reader;
 pub mod record;
 mod writer;
 
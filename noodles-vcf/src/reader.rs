@@ -1,12 +1,15 @@
 //! VCF reader and iterators.
 
+mod query;
 mod records;
 
-pub use self::records::Records;
+pub use self::{query::Query, records::Records};
 
 use std::io::{self, BufRead, Read, Seek};
 
+use noodles::Region;
 use noodles_bgzf as bgzf;
+use noodles_csi::BinningIndex;
 
 const LINE_FEED: char = '\n';
 const CARRIAGE_RETURN: char = '\r';
@@ -240,6 +243,68 @@ where
     pub fn seek(&mut self, pos: bgzf::VirtualPosition) -> io::Result<bgzf::VirtualPosition> {
         self.inner.seek(pos)
     }
+
+    /// Returns an iterator over records that intersect the given region.
+    ///
+    /// The index can be any binning index that stores reference sequence names, e.g., a tabix
+    /// index or a CSI of a tabix-indexed file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::fs::File;
+    /// use noodles::Region;
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_tabix as tabix;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut reader = File::open("sample.vcf.gz")
+    ///     .map(bgzf::Reader::new)
+    ///     .map(vcf::Reader::new)?;
+    ///
+    /// let index = tabix::read("sample.vcf.gz.tbi")?;
+    /// let region = Region::mapped("sq0", 8, 13);
+    /// let query = reader.query(&index, &region)?;
+    ///
+    /// for result in query {
+    ///     let record = result?;
+    ///     println!("{:?}", record);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn query<I>(&mut self, index: &I, region: &Region) -> io::Result<Query<'_, R>>
+    where
+        I: BinningIndex,
+    {
+        let (reference_sequence_id, name, start, end) = resolve_region(index, region)?;
+        let chunks = index.query(reference_sequence_id, start, end)?;
+        Ok(Query::new(self, chunks, name.into(), start, end))
+    }
+}
+
+fn resolve_region<'r, I>(index: &I, region: &'r Region) -> io::Result<(usize, &'r str, i32, i32)>
+where
+    I: BinningIndex,
+{
+    match region {
+        Region::Mapped { name, start, end } => {
+            let i = index.reference_sequence_id(name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "region reference sequence does not exist in index: {:?}",
+                        region
+                    ),
+                )
+            })?;
+
+            Ok((i, name, *start, *end))
+        }
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "region is not mapped",
+        )),
+    }
 }
 
 // Reads all bytes until a line feed ('\n') or EOF is reached.
@@ -350,4 +415,70 @@ sq0\t13
 
         Ok(())
     }
+
+    #[test]
+    fn test_query() -> Result<(), Box<dyn std::error::Error>> {
+        use std::io::{Cursor, Write};
+
+        use noodles_tabix as tabix;
+
+        let mut writer = bgzf::Writer::new(Vec::new());
+        writer
+            .write_all(b"##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n")?;
+        writer.write_all(b"sq0\t1\t.\tA\t.\t.\tPASS\t.\n")?;
+        writer.write_all(b"sq0\t8\t.\tACGT\t.\t.\tPASS\t.\n")?;
+        writer.write_all(b"sq0\t16\t.\tN\t<DEL>\t.\tPASS\tEND=21\n")?;
+        writer.write_all(b"sq1\t13\t.\tA\t.\t.\tPASS\t.\n")?;
+        let data = writer.finish()?;
+
+        let mut reader = Reader::new(bgzf::Reader::new(Cursor::new(data)));
+        reader.read_header()?;
+
+        let mut indexer = tabix::Index::indexer();
+        let mut start_position = reader.virtual_position();
+
+        let mut buf = String::new();
+
+        while reader.read_record(&mut buf)? != 0 {
+            let end_position = reader.virtual_position();
+            let record: crate::Record = buf.parse()?;
+
+            let chunk =
+                tabix::index::reference_sequence::bin::Chunk::new(start_position, end_position);
+
+            indexer.add_record(
+                &record.chromosome().to_string(),
+                record.position(),
+                query::end(&record),
+                chunk,
+            );
+
+            buf.clear();
+            start_position = end_position;
+        }
+
+        let index = indexer.build();
+
+        let positions = |reader: &mut Reader<_>, region| -> io::Result<Vec<i32>> {
+            reader
+                .query(&index, &region)?
+                .map(|result| result.map(|record| record.position()))
+                .collect()
+        };
+
+        assert_eq!(positions(&mut reader, Region::mapped("sq0", 2, 9))?, [8]);
+        assert_eq!(
+            positions(&mut reader, Region::mapped("sq0", 11, 20))?,
+            [8, 16]
+        );
+        assert_eq!(positions(&mut reader, Region::mapped("sq1", 1, 13))?, [13]);
+        assert!(positions(&mut reader, Region::mapped("sq1", 14, 21))?.is_empty());
+
+        assert!(matches!(
+            reader.query(&index, &Region::mapped("sq2", 1, 5)),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
 }
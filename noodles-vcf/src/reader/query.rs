@@ -0,0 +1,157 @@
+use std::io::{self, Read, Seek};
+
+use noodles_bgzf as bgzf;
+use noodles_csi::index::reference_sequence::bin::Chunk;
+
+use crate::{
+    record::{
+        chromosome::Chromosome,
+        info::field::{Key, Value},
+    },
+    Record,
+};
+
+use super::Reader;
+
+enum State {
+    Seek,
+    Read(bgzf::VirtualPosition),
+    End,
+}
+
+/// An iterator over records of a VCF reader that intersect a given region.
+///
+/// This is created by calling [`Reader::query`].
+pub struct Query<'a, R>
+where
+    R: Read + Seek,
+{
+    reader: &'a mut Reader<bgzf::Reader<R>>,
+    chunks: Vec<Chunk>,
+    reference_sequence_name: String,
+    start: i32,
+    end: i32,
+    i: usize,
+    state: State,
+    line_buf: String,
+}
+
+impl<'a, R> Query<'a, R>
+where
+    R: Read + Seek,
+{
+    pub(crate) fn new(
+        reader: &'a mut Reader<bgzf::Reader<R>>,
+        chunks: Vec<Chunk>,
+        reference_sequence_name: String,
+        start: i32,
+        end: i32,
+    ) -> Self {
+        Self {
+            reader,
+            chunks,
+            reference_sequence_name,
+            start,
+            end,
+            i: 0,
+            state: State::Seek,
+            line_buf: String::new(),
+        }
+    }
+
+    fn next_chunk(&mut self) -> io::Result<Option<bgzf::VirtualPosition>> {
+        if self.i >= self.chunks.len() {
+            return Ok(None);
+        }
+
+        let chunk = self.chunks[self.i];
+        self.reader.seek(chunk.start())?;
+
+        self.i += 1;
+
+        Ok(Some(chunk.end()))
+    }
+
+    fn read_record(&mut self) -> Option<io::Result<Record>> {
+        self.line_buf.clear();
+
+        match self.reader.read_record(&mut self.line_buf) {
+            Ok(0) => None,
+            Ok(_) => Some(
+                self.line_buf
+                    .parse()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            ),
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    fn intersects(&self, record: &Record) -> bool {
+        let name = match record.chromosome() {
+            Chromosome::Name(name) => name,
+            Chromosome::Symbol(_) => return false,
+        };
+
+        name == &self.reference_sequence_name
+            && in_interval(record.position(), end(record), self.start, self.end)
+    }
+}
+
+impl<'a, R> Iterator for Query<'a, R>
+where
+    R: Read + Seek,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.state {
+                State::Seek => {
+                    self.state = match self.next_chunk() {
+                        Ok(Some(chunk_end)) => State::Read(chunk_end),
+                        Ok(None) => State::End,
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                State::Read(chunk_end) => match self.read_record() {
+                    Some(result) => {
+                        if self.reader.virtual_position() >= chunk_end {
+                            self.state = State::Seek;
+                        }
+
+                        match result {
+                            Ok(record) => {
+                                if self.intersects(&record) {
+                                    return Some(Ok(record));
+                                }
+                            }
+                            Err(e) => return Some(Err(e)),
+                        }
+                    }
+                    None => {
+                        self.state = State::Seek;
+                    }
+                },
+                State::End => return None,
+            }
+        }
+    }
+}
+
+// Returns the end position of the record, preferring the `END` info field when present.
+pub(crate) fn end(record: &Record) -> i32 {
+    let end = record
+        .info()
+        .iter()
+        .find(|field| field.key() == &Key::EndPosition)
+        .and_then(|field| match field.value() {
+            Value::Integer(n) => Some(*n),
+            _ => None,
+        });
+
+    end.unwrap_or_else(|| record.position() + record.reference_bases().len() as i32 - 1)
+}
+
+fn in_interval(a_start: i32, a_end: i32, b_start: i32, b_end: i32) -> bool {
+    a_start <= b_end && b_start <= a_end
+}